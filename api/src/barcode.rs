@@ -0,0 +1,95 @@
+//! Code 128 条码编码，输出条与空的模块宽度，由调用方绘制。
+
+/// 每个码字依次为条、空、条、空、条、空的宽度，共 11 个模块
+const PATTERNS: [&[u8; 6]; 106] = [
+    b"212222", b"222122", b"222221", b"121223", b"121322", b"131222", b"122213", b"122312",
+    b"132212", b"221213", b"221312", b"231212", b"112232", b"122132", b"122231", b"113222",
+    b"123122", b"123221", b"223211", b"221132", b"221231", b"213212", b"223112", b"312131",
+    b"311222", b"321122", b"321221", b"312212", b"322112", b"322211", b"212123", b"212321",
+    b"232121", b"111323", b"131123", b"131321", b"112313", b"132113", b"132311", b"211313",
+    b"231113", b"231311", b"112133", b"112331", b"132131", b"113123", b"113321", b"133121",
+    b"313121", b"211331", b"231131", b"213113", b"213311", b"213131", b"311123", b"311321",
+    b"331121", b"312113", b"312311", b"332111", b"314111", b"221411", b"431111", b"111224",
+    b"111422", b"121124", b"121421", b"141122", b"141221", b"112214", b"112412", b"122114",
+    b"122411", b"142112", b"142211", b"241211", b"221114", b"413111", b"241112", b"134111",
+    b"111242", b"121142", b"121241", b"114212", b"124112", b"124211", b"411212", b"421112",
+    b"421211", b"212141", b"214121", b"412121", b"111143", b"111341", b"131141", b"114113",
+    b"114311", b"411113", b"411311", b"113141", b"114131", b"311141", b"411131", b"211412",
+    b"211214", b"211232",
+];
+/// 终止符多一根 2 模块宽的条
+const STOP: &[u8; 7] = b"2331112";
+const START_B: usize = 104;
+const START_C: usize = 105;
+
+/// 可打印 ASCII 使用 B 字符集，偶数位纯数字使用 C 字符集以缩短条码。
+/// 含其他字符时返回 `None`
+pub fn code128(text: &str) -> Option<Vec<u8>> {
+    let bytes = text.as_bytes();
+    let codes: Vec<usize> =
+        if !bytes.is_empty() && bytes.len().is_multiple_of(2) && bytes.iter().all(u8::is_ascii_digit) {
+            std::iter::once(START_C)
+                .chain(bytes.chunks(2).map(|pair| ((pair[0] - b'0') * 10 + pair[1] - b'0').into()))
+                .collect()
+        } else if !bytes.is_empty() && bytes.iter().all(|byte| (b' '..=b'~').contains(byte)) {
+            std::iter::once(START_B)
+                .chain(bytes.iter().map(|byte| (byte - b' ').into()))
+                .collect()
+        } else {
+            return None;
+        };
+    // 起始符权重为 1，之后第 n 个码字权重为 n
+    let checksum = codes
+        .iter()
+        .enumerate()
+        .map(|(position, code)| position.max(1) * code)
+        .sum::<usize>()
+        % 103;
+    let widths = codes
+        .iter()
+        .chain(std::iter::once(&checksum))
+        .flat_map(|&code| PATTERNS[code].iter())
+        .chain(STOP.iter())
+        .map(|width| width - b'0')
+        .collect();
+    Some(widths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modules(widths: &[u8]) -> u32 {
+        widths.iter().map(|&width| width as u32).sum()
+    }
+
+    #[test]
+    fn encodes_even_digits_with_code_c() {
+        let widths = code128("00000001").unwrap();
+        // 起始符、4 个码字、校验符各 6 个宽度，终止符 7 个
+        assert_eq!(widths.len(), 6 * 6 + 7);
+        assert_eq!(modules(&widths), 6 * 11 + 13);
+        assert_eq!(widths[..6], [2, 1, 1, 2, 3, 2]);
+        // 校验值 (105 + 1 × 4) % 103 = 6
+        assert_eq!(widths[30..36], [1, 2, 2, 2, 1, 3]);
+        assert_eq!(widths[36..], [2, 3, 3, 1, 1, 1, 2]);
+    }
+
+    #[test]
+    fn encodes_other_ascii_with_code_b() {
+        let widths = code128("Wikipedia").unwrap();
+        assert_eq!(widths.len(), 11 * 6 + 7);
+        assert_eq!(widths[..6], [2, 1, 1, 2, 1, 4]);
+        // 校验值 3281 % 103 = 88
+        assert_eq!(widths[60..66], [4, 2, 1, 2, 1, 1]);
+        // 奇数位数字无法用 C 字符集
+        assert_eq!(code128("123").unwrap().len(), 5 * 6 + 7);
+    }
+
+    #[test]
+    fn rejects_unencodable_text() {
+        assert_eq!(code128(""), None);
+        assert_eq!(code128("张三"), None);
+        assert_eq!(code128("a\tb"), None);
+    }
+}
@@ -0,0 +1,113 @@
+//! 服务端生成的 SVG 图表，直接嵌入页面，不依赖前端脚本。
+
+use std::fmt::Write;
+
+const WIDTH: f64 = 720.0;
+const HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 24.0;
+const MARGIN_TOP: f64 = 12.0;
+/// 横轴最多显示的标签数，区间较多时间隔显示
+const MAX_X_LABELS: usize = 12;
+
+/// 柱状图，每个区间一根柱子，鼠标悬停显示数值
+pub fn bar_chart(points: &[(String, i64)], color: &str) -> String {
+    let plot_width = WIDTH - MARGIN_LEFT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let max = nice_max(points.iter().map(|(_, value)| *value).max().unwrap_or(0));
+    let slot = plot_width / points.len().max(1) as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+    let label_step = points.len().div_ceil(MAX_X_LABELS).max(1);
+
+    let mut svg = open_svg(WIDTH, HEIGHT);
+    for tick in 0..=4 {
+        let value = max * tick / 4;
+        let y = MARGIN_TOP + plot_height * (1.0 - value as f64 / max as f64);
+        let _ = write!(
+            svg,
+            r##"<line x1="{MARGIN_LEFT}" y1="{y:.1}" x2="{WIDTH}" y2="{y:.1}" stroke="#dee2e6"/><text x="{:.1}" y="{:.1}" font-size="10" text-anchor="end" fill="#6c757d">{value}</text>"##,
+            MARGIN_LEFT - 4.0,
+            y + 3.0,
+        );
+    }
+    for (index, (label, value)) in points.iter().enumerate() {
+        let height = plot_height * *value as f64 / max as f64;
+        let x = MARGIN_LEFT + slot * index as f64 + (slot - bar_width) / 2.0;
+        let y = MARGIN_TOP + plot_height - height;
+        let label = escape(label);
+        let _ = write!(
+            svg,
+            r#"<rect x="{x:.1}" y="{y:.1}" width="{bar_width:.1}" height="{height:.1}" fill="{color}"><title>{label}：{value}</title></rect>"#,
+        );
+        if index % label_step == 0 {
+            let _ = write!(
+                svg,
+                r##"<text x="{:.1}" y="{:.1}" font-size="10" text-anchor="middle" fill="#6c757d">{label}</text>"##,
+                x + bar_width / 2.0,
+                HEIGHT - 8.0,
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// 横向条形图，用于排行榜，名称显示在条形左侧
+pub fn ranking_chart(points: &[(String, i64)], color: &str) -> String {
+    const ROW: f64 = 24.0;
+    const LABEL_WIDTH: f64 = 260.0;
+    let height = ROW * points.len().max(1) as f64;
+    let max = points.iter().map(|(_, value)| *value).max().unwrap_or(0).max(1);
+    let plot_width = WIDTH - LABEL_WIDTH - 40.0;
+
+    let mut svg = open_svg(WIDTH, height);
+    for (index, (label, value)) in points.iter().enumerate() {
+        let y = ROW * index as f64;
+        let width = plot_width * *value as f64 / max as f64;
+        let label = escape(&truncate(label, 20));
+        let _ = write!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" font-size="12" text-anchor="end" fill="#212529">{label}</text><rect x="{LABEL_WIDTH}" y="{:.1}" width="{width:.1}" height="{:.1}" fill="{color}"><title>{label}：{value}</title></rect><text x="{:.1}" y="{:.1}" font-size="12" fill="#6c757d">{value}</text>"##,
+            LABEL_WIDTH - 8.0,
+            y + 16.0,
+            y + 4.0,
+            ROW - 8.0,
+            LABEL_WIDTH + width + 6.0,
+            y + 16.0,
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn open_svg(width: f64, height: f64) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width} {height}" width="100%" role="img">"#
+    )
+}
+
+/// 纵轴上限取整，使四等分的刻度为 1、2、5 乘以 10 的幂
+fn nice_max(max: i64) -> i64 {
+    let max = max.max(1);
+    let magnitude = 10_i64.pow((max as f64).log10().floor() as u32);
+    let step = [1, 2, 5, 10]
+        .into_iter()
+        .map(|step| step * magnitude)
+        .find(|step| step * 4 >= max)
+        .unwrap_or(10 * magnitude);
+    step * 4
+}
+
+fn truncate(label: &str, max_chars: usize) -> String {
+    match label.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &label[..index]),
+        None => label.to_owned(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
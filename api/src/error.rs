@@ -10,6 +10,7 @@ pub enum Error {
     SessionGetError(actix_session::SessionGetError),
     SessionInsertError(actix_session::SessionInsertError),
     BcryptError(bcrypt::BcryptError),
+    XlsxError(rust_xlsxwriter::XlsxError),
     Other(String),
 }
 
@@ -33,10 +34,50 @@ impl Error {
     pub fn book_not_found() -> Self {
         Error::ActixError(actix_web::error::ErrorNotFound("Book not found"))
     }
+
+    pub fn author_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Author not found"))
+    }
+    pub fn category_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Category not found"))
+    }
+
+    pub fn item_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Book item not found"))
+    }
+
     pub fn borrow_record_not_found() -> Self {
         Error::ActixError(actix_web::error::ErrorNotFound("Borrow record not found"))
     }
 
+    pub fn policy_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Circulation policy not found"))
+    }
+
+    pub fn fine_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Fine not found"))
+    }
+
+    pub fn incident_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Item incident not found"))
+    }
+
+    pub fn ill_request_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Interlibrary loan request not found"))
+    }
+
+    pub fn user_block_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("User block not found"))
+    }
+
+    pub fn course_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Course not found"))
+    }
+
+    pub fn hold_not_found() -> Self {
+        Error::ActixError(actix_web::error::ErrorNotFound("Hold not found"))
+    }
+
     pub fn email_not_found() -> Self {
         Error::ActixError(actix_web::error::ErrorNotFound("Email not found"))
     }
@@ -84,6 +125,12 @@ impl From<bcrypt::BcryptError> for Error {
     }
 }
 
+impl From<rust_xlsxwriter::XlsxError> for Error {
+    fn from(err: rust_xlsxwriter::XlsxError) -> Self {
+        Error::XlsxError(err)
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -93,6 +140,7 @@ impl Display for Error {
             Error::SessionGetError(err) => write!(f, "SessionGetError: {}", err),
             Error::SessionInsertError(err) => write!(f, "SessionInsertError: {}", err),
             Error::BcryptError(err) => write!(f, "BcryptError: {}", err),
+            Error::XlsxError(err) => write!(f, "XlsxError: {}", err),
             Error::Other(msg) => write!(f, "Other: {}", msg),
         }
     }
@@ -108,6 +156,7 @@ impl ResponseError for Error {
             Error::SessionGetError(err) => builder.body(err.to_string()),
             Error::SessionInsertError(err) => builder.body(err.to_string()),
             Error::BcryptError(err) => builder.body(err.to_string()),
+            Error::XlsxError(err) => builder.body(err.to_string()),
             Error::Other(msg) => builder.body(msg.to_string()),
         }
     }
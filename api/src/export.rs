@@ -0,0 +1,253 @@
+//! 列表与报表导出为 CSV 或 XLSX，表头与页面上的列名一致。
+//! CSV 按页查询、边查边发送；XLSX 需要完整生成后再发送。
+//! 图书还可以导出为 MARCXML，见 `marc::export_marcxml`。
+
+use std::future::Future;
+
+use actix_web::{
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web::Bytes,
+    HttpResponse,
+};
+use book_manager_service::sea_orm::DbErr;
+use chrono::NaiveDate;
+use entity::{users, BooksResult, BorrowedBooksResult};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Deserialize;
+
+use crate::error::Error;
+
+/// 每次查询的行数
+pub(crate) const EXPORT_PAGE_SIZE: u64 = 500;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    /// 只用于图书
+    Marcxml,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    pub format: ExportFormat,
+}
+
+pub enum Cell {
+    Text(String),
+    Integer(i64),
+    Date(NaiveDate),
+}
+
+/// 可以导出的行，`HEADERS` 与 `cells` 的顺序一致
+pub trait ExportRow {
+    const HEADERS: &'static [&'static str];
+
+    fn cells(&self) -> Vec<Cell>;
+}
+
+impl ExportRow for BooksResult {
+    const HEADERS: &'static [&'static str] =
+        &["ID", "书名", "作者", "出版社", "出版年份", "ISBN", "索书号", "副本", "可借"];
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            Cell::Integer(self.id as i64),
+            Cell::Text(self.name.clone()),
+            Cell::Text(self.author.clone()),
+            Cell::Text(self.publisher.clone()),
+            Cell::Date(self.publish_year),
+            Cell::Text(self.isbn.clone()),
+            Cell::Text(self.call_number.clone()),
+            Cell::Integer(self.copies as i64),
+            Cell::Integer(self.available as i64),
+        ]
+    }
+}
+
+impl ExportRow for users::Model {
+    const HEADERS: &'static [&'static str] =
+        &["ID", "用户名", "昵称", "权限组", "读者类别", "注册时间"];
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            Cell::Integer(self.id as i64),
+            Cell::Text(self.name.clone()),
+            Cell::Text(self.nickname.clone()),
+            Cell::Text(format!("{:?}", self.permission)),
+            Cell::Text(self.patron_category.clone()),
+            Cell::Date(self.registration_date),
+        ]
+    }
+}
+
+impl ExportRow for BorrowedBooksResult {
+    const HEADERS: &'static [&'static str] = &[
+        "借阅ID",
+        "用户名",
+        "昵称",
+        "书名",
+        "ISBN",
+        "条码",
+        "借阅时间",
+        "应还日期",
+        "应还时间",
+        "续借次数",
+    ];
+
+    fn cells(&self) -> Vec<Cell> {
+        vec![
+            Cell::Integer(self.borrow_id as i64),
+            Cell::Text(self.user_name.clone()),
+            Cell::Text(self.user_nickname.clone()),
+            Cell::Text(self.book_name.clone()),
+            Cell::Text(self.isbn.clone()),
+            Cell::Text(self.barcode.clone()),
+            Cell::Date(self.borrow_date),
+            Cell::Date(self.return_date),
+            Cell::Text(
+                self.due_at
+                    .map(|due_at| due_at.format("%H:%M").to_string())
+                    .unwrap_or_default(),
+            ),
+            Cell::Integer(self.renewals as i64),
+        ]
+    }
+}
+
+/// 以页码（从 1 开始）和每页行数逐页调用 `fetch_page` 直到没有数据，导出为 `name-日期.csv/xlsx`
+pub async fn export<R, F, Fut>(
+    format: ExportFormat,
+    name: &str,
+    fetch_page: F,
+) -> Result<HttpResponse, Error>
+where
+    R: ExportRow + 'static,
+    F: Fn(u64, u64) -> Fut + 'static,
+    Fut: Future<Output = Result<(Vec<R>, u64), DbErr>> + 'static,
+{
+    let today = chrono::Local::now().naive_local().date();
+    let extension = match format {
+        ExportFormat::Csv => "csv",
+        ExportFormat::Xlsx => "xlsx",
+        ExportFormat::Marcxml => return Err(Error::bad_request("该列表不能导出为 MARCXML")),
+    };
+    let disposition = ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(format!("{name}-{today}.{extension}"))],
+    };
+    let pages = stream::try_unfold(1, move |page| {
+        let rows = fetch_page(page, EXPORT_PAGE_SIZE);
+        async move {
+            let (rows, _) = rows.await?;
+            Ok::<_, Error>((!rows.is_empty()).then_some((rows, page + 1)))
+        }
+    });
+    match format {
+        ExportFormat::Csv => {
+            // 带 BOM，Excel 打开时才能正确识别中文
+            let header = csv_record(R::HEADERS.iter().map(|header| header.to_string()))?;
+            let header = [&b"\xEF\xBB\xBF"[..], &header].concat();
+            let rows = pages.and_then(|rows| async move {
+                let mut bytes = Vec::new();
+                for row in rows {
+                    bytes.extend(csv_record(row.cells().into_iter().map(cell_text))?);
+                }
+                Ok(Bytes::from(bytes))
+            });
+            Ok(HttpResponse::Ok()
+                .content_type("text/csv; charset=utf-8")
+                .insert_header(disposition)
+                .streaming(stream::once(async { Ok(Bytes::from(header)) }).chain(rows)))
+        }
+        ExportFormat::Marcxml => unreachable!(),
+        ExportFormat::Xlsx => {
+            let mut workbook = Workbook::new();
+            let worksheet = workbook.add_worksheet();
+            let bold = Format::new().set_bold();
+            let date_format = Format::new().set_num_format("yyyy-mm-dd");
+            for (col, header) in R::HEADERS.iter().enumerate() {
+                worksheet.write_string_with_format(0, col as u16, *header, &bold)?;
+            }
+            let mut row_index = 1;
+            futures_util::pin_mut!(pages);
+            while let Some(rows) = pages.try_next().await? {
+                for row in rows {
+                    for (col, cell) in row.cells().into_iter().enumerate() {
+                        let col = col as u16;
+                        match cell {
+                            Cell::Text(text) => worksheet.write_string(row_index, col, text)?,
+                            Cell::Integer(number) => {
+                                worksheet.write_number(row_index, col, number as f64)?
+                            }
+                            Cell::Date(date) => worksheet.write_datetime_with_format(
+                                row_index,
+                                col,
+                                date,
+                                &date_format,
+                            )?,
+                        };
+                    }
+                    row_index += 1;
+                }
+            }
+            worksheet.set_freeze_panes(1, 0)?;
+            worksheet.autofit();
+            Ok(HttpResponse::Ok()
+                .content_type("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
+                .insert_header(disposition)
+                .body(workbook.save_to_buffer()?))
+        }
+    }
+}
+
+/// 以 `=`、`+`、`-`、`@` 或制表符、回车开头的文本会被表格软件当作公式执行，CSV 中加 `'`
+/// 前缀原样显示。XLSX 中文本一律按字符串写入，不需要处理
+fn cell_text(cell: Cell) -> String {
+    match cell {
+        Cell::Text(text) if text.starts_with(['=', '+', '-', '@', '\t', '\r']) => {
+            format!("'{text}")
+        }
+        Cell::Text(text) => text,
+        Cell::Integer(number) => number.to_string(),
+        Cell::Date(date) => date.to_string(),
+    }
+}
+
+fn csv_record(fields: impl IntoIterator<Item = String>) -> Result<Vec<u8>, Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(fields).map_err(Error::new)?;
+    writer.into_inner().map_err(Error::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> String {
+        cell_text(Cell::Text(text.to_owned()))
+    }
+
+    #[test]
+    fn escapes_formula_cells() {
+        assert_eq!(text("=HYPERLINK(\"http://x\")"), "'=HYPERLINK(\"http://x\")");
+        assert_eq!(text("+1"), "'+1");
+        assert_eq!(text("-1"), "'-1");
+        assert_eq!(text("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(text("\t=1"), "'\t=1");
+        assert_eq!(text("数据结构 = 算法"), "数据结构 = 算法");
+        assert_eq!(text(""), "");
+        // 数字与日期不是文本，负数照常导出
+        assert_eq!(cell_text(Cell::Integer(-1)), "-1");
+        let date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        assert_eq!(cell_text(Cell::Date(date)), "2023-07-01");
+    }
+
+    #[test]
+    fn csv_record_quotes_fields() {
+        let record = csv_record([text("=1+1"), "a,b".to_owned(), "\"q\"".to_owned()]).unwrap();
+        assert_eq!(record, b"'=1+1,\"a,b\",\"\"\"q\"\"\"\n");
+    }
+}
@@ -1,11 +1,72 @@
-use std::collections::HashMap;
-
-use chrono::NaiveDate;
-use tera::{Value, try_get_value};
-
-
-pub fn is_overdue(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
-    let date = try_get_value!("is_overdue", "date", NaiveDate, value);
-    let now = chrono::Local::now().naive_local().date();
-    Ok(Value::Bool(now < date))
-}
+use std::collections::HashMap;
+
+pub use book_manager_service::format_money;
+use chrono::{NaiveDate, NaiveDateTime};
+use entity::borrowed_books;
+use tera::{Value, try_get_value};
+
+
+pub fn is_overdue(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let date = try_get_value!("is_overdue", "date", NaiveDate, value);
+    let now = chrono::Local::now().naive_local().date();
+    Ok(Value::Bool(now < date))
+}
+
+/// 借阅的应还时间，课程参考书的短期借阅精确到分钟
+pub fn format_due(loan: &borrowed_books::Model) -> String {
+    format_due_date(loan.return_date, loan.due_at)
+}
+
+pub fn format_due_date(return_date: NaiveDate, due_at: Option<NaiveDateTime>) -> String {
+    match due_at {
+        Some(due_at) => due_at.format("%Y-%m-%d %H:%M").to_string(),
+        None => return_date.to_string(),
+    }
+}
+
+/// 以分为单位的金额格式化为元
+pub fn money(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    let amount = try_get_value!("money", "amount", i64, value);
+    Ok(Value::String(format_money(amount)))
+}
+
+/// 解析以元为单位的非负金额，返回分，带正负号或超出范围时返回 `None`
+pub fn parse_money(value: &str) -> Option<i32> {
+    let (yuan, fen) = match value.trim().split_once('.') {
+        Some((yuan, fen)) if fen.len() <= 2 => (yuan, format!("{fen:0<2}")),
+        Some(_) => return None,
+        None => (value.trim(), "00".to_owned()),
+    };
+    if !yuan.bytes().chain(fen.bytes()).all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let yuan: i64 = if yuan.is_empty() { 0 } else { yuan.parse().ok()? };
+    let fen: i64 = fen.parse().ok()?;
+    i32::try_from(yuan.checked_mul(100)?.checked_add(fen)?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_yuan_to_fen() {
+        assert_eq!(parse_money("12"), Some(1200));
+        assert_eq!(parse_money(" 12.5 "), Some(1250));
+        assert_eq!(parse_money("0.05"), Some(5));
+        assert_eq!(parse_money(".5"), Some(50));
+        assert_eq!(parse_money("1.234"), None);
+        assert_eq!(parse_money("abc"), None);
+    }
+
+    #[test]
+    fn rejects_sign_and_overflow() {
+        assert_eq!(parse_money("-0.50"), None);
+        assert_eq!(parse_money("-1"), None);
+        assert_eq!(parse_money("+1"), None);
+        assert_eq!(parse_money("1.-5"), None);
+        assert_eq!(parse_money("21474836.47"), Some(i32::MAX));
+        assert_eq!(parse_money("21474836.48"), None);
+        assert_eq!(parse_money("99999999999999999999"), None);
+    }
+}
@@ -0,0 +1,24 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use book_manager_service::Query;
+
+use crate::{error::Error, handlers::basic_context, AppState};
+
+pub async fn author_detail_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let author = Query::find_author_by_id(conn, id.into_inner())
+        .await?
+        .ok_or(Error::author_not_found())?;
+    let books = Query::find_books_by_author(conn, author.id).await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", &author.name);
+    ctx.insert("author", &author);
+    ctx.insert("books", &books);
+    let body = template.read().unwrap().render("authors/detail.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
@@ -0,0 +1,30 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use book_manager_service::Query;
+
+use crate::{
+    error::Error,
+    handlers::{basic_context, PageParams, DEFAULT_NUMBER_PER_PAGE},
+    AppState,
+};
+
+pub async fn list_authors_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    params: web::Query<PageParams>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let page = params.page.unwrap_or(1);
+    let number_per_page = params.number_per_page.unwrap_or(DEFAULT_NUMBER_PER_PAGE);
+    let (authors, num_pages) =
+        Query::find_authors_detail_in_page(conn, page, number_per_page).await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "著者列表");
+    ctx.insert("authors", &authors);
+    ctx.insert("page", &page);
+    ctx.insert("num_pages", &num_pages);
+    ctx.insert("number_per_page", &number_per_page);
+    let body = template.read().unwrap().render("authors/list.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
@@ -0,0 +1,5 @@
+pub mod list;
+pub mod detail;
+
+pub use list::*;
+pub use detail::*;
@@ -1,18 +1,29 @@
-use book_manager_service::Mutation;
-use actix_web::{web, HttpResponse};
-
-use crate::{error::Error, AppState, handlers::DeleteParams};
-
-pub async fn delete_book_handler(
-    app_state: web::Data<AppState>,
-    book_id: web::Path<i32>,
-    params: web::Query<DeleteParams>,
-) -> Result<HttpResponse, Error> {
-    let book_id = book_id.into_inner();
-    let source = params.into_inner().source.unwrap_or("/books".to_string());
-    let conn = &app_state.conn;
-    Mutation::delete_book(conn, book_id).await?;
-    Ok(HttpResponse::Found()
-        .append_header(("Location", source))
-        .finish())
-}
+use book_manager_service::{LoanState, Mutation, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, AppState, handlers::DeleteParams, flash_error, flash_success};
+
+pub async fn delete_book_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    book_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+) -> Result<HttpResponse, Error> {
+    let book_id = book_id.into_inner();
+    let source = params.into_inner().source.unwrap_or("/books".to_string());
+    let conn = &app_state.conn;
+    // 保留借阅历史，有借阅记录的图书不能删除
+    if Query::find_borrowed_books_by_book_id(conn, book_id, LoanState::All)
+        .await?
+        .is_empty()
+    {
+        Mutation::delete_book(conn, book_id).await?;
+        flash_success(&session, "删除成功")?;
+    } else {
+        flash_error(&session, "存在借阅记录，无法删除")?;
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", source))
+        .finish())
+}
@@ -1,27 +1,58 @@
-use book_manager_service::Query;
-use actix_session::Session;
-use actix_web::{web, HttpResponse};
-
-use crate::{error::Error, AppState, handlers::basic_context};
-
-pub async fn book_detail_handler(
-    app_state: web::Data<AppState>,
-    session: Session,
-    id: web::Path<i32>,
-) -> Result<HttpResponse, Error> {
-    let template = &app_state.templates;
-    let id = id.into_inner();
-    let conn = &app_state.conn;
-    let book = Query::find_book_by_id(conn, id)
-        .await?
-        .ok_or(Error::book_not_found())?;
-    let borrowed_books = Query::find_borrowed_books_detail_by_book_id(conn, id).await?;
-    let date = chrono::Local::now().naive_local().date() + chrono::Duration::days(7);
-    let mut ctx = basic_context(&session)?;
-    ctx.insert("title", "图书详情");
-    ctx.insert("book", &book);
-    ctx.insert("date", &date);
-    ctx.insert("borrowed_books", &borrowed_books);
-    let body = template.read().unwrap().render("books/detail.html.tera", &ctx)?;
-    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+use book_manager_service::{Calendar, LoanState, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::ItemStatus;
+
+use crate::{error::Error, handlers::basic_context, AppState};
+
+pub async fn book_detail_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let id = id.into_inner();
+    let conn = &app_state.conn;
+    let book = Query::find_book_by_id(conn, id)
+        .await?
+        .ok_or(Error::book_not_found())?;
+    let today = chrono::Local::now().naive_local().date();
+    let contributors = Query::find_contributors_by_book_id(conn, id).await?;
+    let categories = Query::find_categories_by_book_id(conn, id).await?;
+    let book_items = Query::find_book_items_by_book_id(conn, id).await?;
+    let available = book_items
+        .iter()
+        .filter(|item| item.status == ItemStatus::Available)
+        .count();
+    let borrowed_books = Query::find_borrowed_books_detail_by_book_id(conn, id, LoanState::Active).await?;
+    let holds = Query::find_holds_detail_by_book_id(conn, id).await?;
+    let course = Query::find_active_course_by_book_id(conn, id, today).await?;
+    // 当前用户在预约队列中的位置
+    let user_id = session.get::<i32>("user_id")?;
+    let my_hold_position = holds
+        .iter()
+        .position(|hold| Some(hold.user_id) == user_id);
+    // 默认归还日期遇闭馆日顺延
+    let date = Calendar::load(conn).await?.next_open_day(today + chrono::Duration::days(7));
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "图书详情");
+    ctx.insert("book", &book);
+    ctx.insert("contributors", &contributors);
+    ctx.insert("categories", &categories);
+    ctx.insert("book_items", &book_items);
+    ctx.insert("available", &available);
+    ctx.insert("today", &today);
+    ctx.insert("date", &date);
+    ctx.insert("self_checkout", &app_state.self_checkout);
+    ctx.insert("borrowed_books", &borrowed_books);
+    if let Some(position) = my_hold_position {
+        ctx.insert("my_hold", &holds[position]);
+        ctx.insert("my_hold_position", &(position + 1));
+    }
+    ctx.insert("holds", &holds);
+    if let Some(course) = course {
+        ctx.insert("course", &course);
+    }
+    let body = template.read().unwrap().render("books/detail.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
 }
\ No newline at end of file
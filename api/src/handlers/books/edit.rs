@@ -1,9 +1,29 @@
+use std::collections::HashMap;
+
 use book_manager_service::{Mutation, Query};
 use actix_session::Session;
 use actix_web::{web, HttpResponse};
 use entity::books;
+use serde::{Deserialize, Serialize};
+
+use super::new::validate_isbn;
+use crate::{
+    error::Error,
+    handlers::{
+        basic_context,
+        categories::{parse_subjects, subject_codes},
+    },
+    AppState, flash_success,
+};
 
-use crate::{error::Error, AppState, handlers::basic_context, flash_success};
+#[derive(Debug, Deserialize, Serialize)]
+pub struct EditBookForm {
+    #[serde(flatten)]
+    book: books::Model,
+    /// 主题类号，多个用分号分隔
+    #[serde(default)]
+    subjects: String,
+}
 
 pub async fn edit_book_handler(
     app_state: web::Data<AppState>,
@@ -16,9 +36,13 @@ pub async fn edit_book_handler(
     let book = Query::find_book_by_id(conn, id)
         .await?
         .ok_or(actix_web::error::ErrorNotFound("Book not found"))?;
+    let form = EditBookForm {
+        book,
+        subjects: subject_codes(conn, id).await?,
+    };
     let mut ctx = basic_context(&session)?;
     ctx.insert("title", "编辑图书");
-    ctx.insert("book", &book);
+    ctx.insert("book", &form);
     let body = template.read().unwrap().render("books/edit.html.tera", &ctx)?;
     Ok(HttpResponse::Ok().content_type("text/html").body(body))
 }
@@ -27,12 +51,39 @@ pub async fn edit_book_post_handler(
     app_state: web::Data<AppState>,
     session: Session,
     id: web::Path<i32>,
-    post_form: web::Form<books::Model>,
+    post_form: web::Form<EditBookForm>,
 ) -> Result<HttpResponse, Error> {
-    let post_form = post_form.into_inner();
+    let mut form = post_form.into_inner();
     let id = id.into_inner();
     let conn = &app_state.conn;
-    Mutation::update_book_by_id(conn, id, post_form).await?;
+    let isbn = validate_isbn(conn, &form.book.isbn, Some(id)).await?;
+    let subjects = parse_subjects(conn, &form.subjects).await?;
+    let category_ids = match (isbn, subjects) {
+        (Ok(isbn), Ok(category_ids)) => {
+            form.book.isbn = isbn;
+            category_ids
+        }
+        (isbn, subjects) => {
+            let errors: HashMap<_, _> = [("isbn", isbn.err()), ("subjects", subjects.err())]
+                .into_iter()
+                .filter_map(|(field, message)| Some((field, message?)))
+                .collect();
+            form.book.id = id;
+            let mut ctx = basic_context(&session)?;
+            ctx.insert("title", "编辑图书");
+            ctx.insert("book", &form);
+            ctx.insert("errors", &errors);
+            let body = app_state
+                .templates
+                .read()
+                .unwrap()
+                .render("books/edit.html.tera", &ctx)?;
+            return Ok(HttpResponse::Ok().content_type("text/html").body(body));
+        }
+    };
+    form.book.call_number = form.book.call_number.trim().to_owned();
+    Mutation::update_book_by_id(conn, id, form.book).await?;
+    Mutation::set_book_categories(conn, id, &category_ids).await?;
     flash_success(&session, "修改成功")?;
     Ok(HttpResponse::Found()
         .append_header(("Location", "/books"))
@@ -0,0 +1,30 @@
+use actix_web::{web, HttpResponse};
+use book_manager_service::Query;
+
+use crate::{
+    error::Error,
+    export::{export, ExportFormat, ExportParams},
+    handlers::categories::{category_filter_ids, CategoryFilter},
+    marc, AppState,
+};
+
+pub async fn export_books_handler(
+    app_state: web::Data<AppState>,
+    params: web::Query<ExportParams>,
+    filter: web::Query<CategoryFilter>,
+) -> Result<HttpResponse, Error> {
+    let conn = app_state.conn.clone();
+    let category_ids = category_filter_ids(&conn, filter.category_id()?).await?;
+    let fetch_page = move |page, number_per_page| {
+        let conn = conn.clone();
+        let category_ids = category_ids.clone();
+        async move {
+            Query::find_books_detail_in_page(&conn, category_ids.as_deref(), page, number_per_page)
+                .await
+        }
+    };
+    match params.format {
+        ExportFormat::Marcxml => marc::export_marcxml("books", fetch_page).await,
+        format => export(format, "books", fetch_page).await,
+    }
+}
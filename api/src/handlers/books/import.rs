@@ -0,0 +1,438 @@
+use std::collections::HashSet;
+
+use book_manager_service::{
+    sea_orm::{self, DatabaseConnection, TransactionTrait},
+    Mutation, Query,
+};
+use actix_multipart::form::{bytes::Bytes, text::Text, MultipartForm};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use entity::{books, Isbn};
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    handlers::basic_context,
+    marc::{self, Record},
+    AppState, flash_success,
+};
+
+/// 一次导入的副本数上限，防止误填数量生成大量条码
+const MAX_COPIES: u32 = 100;
+
+/// CSV 各列按表头识别，同时接受英文列名和导出文件中的中文列名，其余列忽略
+const COLUMNS: [(&str, &str); 6] = [
+    ("name", "书名"),
+    ("author", "作者"),
+    ("publisher", "出版社"),
+    ("publish_year", "出版年份"),
+    ("isbn", "ISBN"),
+    ("copies", "副本"),
+];
+
+#[derive(MultipartForm)]
+pub struct BookImportForm {
+    /// 首次上传的文件
+    pub file: Option<Bytes>,
+    /// 预览后确认导入时回传的文件内容
+    pub content: Option<Text<String>>,
+    /// 已存在的 ISBN 是否合并为新增副本，否则视为错误
+    pub merge_copies: Option<Text<String>>,
+    /// MARC 记录没有副本数，统一按此数量建立副本，可以为 0
+    pub copies: Option<Text<u32>>,
+    pub shelf_location: Text<String>,
+    /// 为空时只预览，不写入数据库
+    pub commit: Option<Text<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ImportAction {
+    /// 新建图书及副本
+    Create,
+    /// 为已有图书增加副本
+    Merge,
+    /// 本行有误，不会导入
+    Invalid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReportLine {
+    pub line: usize,
+    pub name: String,
+    pub isbn: String,
+    pub copies: u32,
+    pub action: ImportAction,
+    pub message: String,
+    #[serde(skip)]
+    book: Option<books::Model>,
+}
+
+pub async fn import_books_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "批量导入图书");
+    let body = template.read().unwrap().render("books/import.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+/// 先校验每一行并显示预览，确认后在同一事务中导入所有有效行
+pub async fn import_books_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    form: MultipartForm<BookImportForm>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let BookImportForm {
+        file,
+        content,
+        merge_copies,
+        copies,
+        shelf_location,
+        commit,
+    } = form.into_inner();
+    let merge_copies = merge_copies.is_some();
+    let marc_copies = copies.map_or(1, |copies| copies.into_inner());
+    if marc_copies > MAX_COPIES {
+        return Err(Error::bad_request(format!("副本数不能超过 {MAX_COPIES}")));
+    }
+    let shelf_location = shelf_location.trim().to_owned();
+    let (content, rows) = match (content, file) {
+        (Some(content), _) => {
+            let content = content.into_inner();
+            let rows = parse_content(&content, marc_copies);
+            (content, rows)
+        }
+        // ISO 2709 含有控制字符，不便放进预览页面，转为 MARCXML 后回传
+        (None, Some(file)) if marc::is_iso2709(&file.data) => {
+            let records = marc::read_iso2709(&file.data);
+            let content = marc::to_marcxml(records.iter().filter_map(|record| record.as_ref().ok()));
+            (content, Ok(marc_rows(records, marc_copies)))
+        }
+        (None, Some(file)) => {
+            let content = String::from_utf8_lossy(&file.data).into_owned();
+            let rows = parse_content(&content, marc_copies);
+            (content, rows)
+        }
+        (None, None) => return Err(Error::bad_request("请选择要导入的文件")),
+    };
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(message) => {
+            let mut ctx = basic_context(&session)?;
+            ctx.insert("title", "批量导入图书");
+            ctx.insert("file_error", &message);
+            let body = template.read().unwrap().render("books/import.html.tera", &ctx)?;
+            return Ok(HttpResponse::Ok().content_type("text/html").body(body));
+        }
+    };
+    let report = check_rows(conn, rows, merge_copies).await?;
+    let count = |action: ImportAction| report.iter().filter(|line| line.action == action).count();
+
+    if commit.is_some() {
+        let (created, merged) = (count(ImportAction::Create), count(ImportAction::Merge));
+        conn.transaction::<_, (), sea_orm::DbErr>(|txn| {
+            Box::pin(async move {
+                for line in report {
+                    let Some(book) = line.book else {
+                        continue;
+                    };
+                    let book_id = match line.action {
+                        ImportAction::Create => Mutation::create_book(txn, book).await?.id,
+                        ImportAction::Merge => book.id,
+                        ImportAction::Invalid => continue,
+                    };
+                    Mutation::create_book_items(txn, book_id, line.copies, shelf_location.clone())
+                        .await?;
+                }
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|err| match err {
+            sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+            sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+        })?;
+        flash_success(&session, format!("导入完成：新建 {created} 种，合并 {merged} 种"))?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", "/books"))
+            .finish());
+    }
+
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "批量导入图书");
+    ctx.insert("created", &count(ImportAction::Create));
+    ctx.insert("merged", &count(ImportAction::Merge));
+    ctx.insert("invalid", &count(ImportAction::Invalid));
+    ctx.insert("report", &report);
+    ctx.insert("content", &content);
+    ctx.insert("merge_copies", &merge_copies);
+    ctx.insert("copies", &marc_copies);
+    ctx.insert("is_marc", &is_marcxml(&content));
+    ctx.insert("shelf_location", &shelf_location);
+    let body = template.read().unwrap().render("books/import.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+/// 待校验的一行（CSV）或一条记录（MARC），`parsed` 为映射出的图书与副本数
+struct ImportRow {
+    line: usize,
+    name: String,
+    isbn: String,
+    parsed: Result<(books::Model, u32), String>,
+}
+
+/// 以 `<` 开头的是 MARCXML，否则按 CSV 处理
+fn is_marcxml(content: &str) -> bool {
+    content.trim_start_matches('\u{feff}').trim_start().starts_with('<')
+}
+
+/// 文件本身无法解析（例如缺少必需的列）时返回错误说明
+fn parse_content(content: &str, marc_copies: u32) -> Result<Vec<ImportRow>, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    if is_marcxml(content) {
+        marc::read_marcxml(content).map(|records| marc_rows(records, marc_copies))
+    } else {
+        csv_rows(content)
+    }
+}
+
+fn marc_rows(records: Vec<Result<Record, String>>, copies: u32) -> Vec<ImportRow> {
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let title = record.as_ref().ok().and_then(Record::title);
+            let parsed = record.and_then(|record| record.to_book());
+            let (name, isbn) = match &parsed {
+                Ok(book) => (book.name.clone(), book.isbn.clone()),
+                Err(_) => (title.unwrap_or_default(), String::new()),
+            };
+            ImportRow {
+                line: index + 1,
+                name,
+                isbn,
+                parsed: parsed.map(|book| (book, copies)),
+            }
+        })
+        .collect()
+}
+
+fn csv_rows(content: &str) -> Result<Vec<ImportRow>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|err| format!("无法读取表头：{err}"))?
+        .clone();
+    let mut indexes = [0; COLUMNS.len()];
+    for (index, (english, chinese)) in indexes.iter_mut().zip(COLUMNS) {
+        *index = headers
+            .iter()
+            .position(|header| {
+                let header = header.trim();
+                header.eq_ignore_ascii_case(english) || header == chinese
+            })
+            .ok_or_else(|| format!("缺少“{chinese}”（{english}）列"))?;
+    }
+    // 索书号列可以省略
+    let call_number_index = headers.iter().position(|header| {
+        let header = header.trim();
+        header.eq_ignore_ascii_case("call_number") || header == "索书号"
+    });
+
+    let mut rows = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        // 表头占第 1 行，字段内含换行时以解析器记录的行号为准
+        let line = record
+            .as_ref()
+            .ok()
+            .and_then(|record| record.position())
+            .map_or(row + 2, |position| position.line() as usize);
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                rows.push(ImportRow {
+                    line,
+                    name: String::new(),
+                    isbn: String::new(),
+                    parsed: Err(format!("无法解析：{err}")),
+                });
+                continue;
+            }
+        };
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let field = |column: usize| record.get(indexes[column]).unwrap_or("").trim().to_owned();
+        let [name, author, publisher, publish_year, isbn, copies] =
+            [0, 1, 2, 3, 4, 5].map(field);
+        let parsed = parse_fields(&name, &isbn, &publish_year, &copies).map(
+            |(isbn, publish_year, copies)| {
+                let book = books::Model {
+                    id: 0,
+                    name: name.clone(),
+                    author,
+                    publisher,
+                    publish_year,
+                    isbn,
+                    call_number: call_number_index
+                        .and_then(|index| record.get(index))
+                        .unwrap_or("")
+                        .trim()
+                        .to_owned(),
+                };
+                (book, copies)
+            },
+        );
+        rows.push(ImportRow {
+            line,
+            name,
+            // 有效的 ISBN 显示规范化后的形式
+            isbn: parsed.as_ref().map_or(isbn, |(book, _)| book.isbn.clone()),
+            parsed,
+        });
+    }
+    Ok(rows)
+}
+
+/// 检查文件内重复的 ISBN 以及与已有图书的冲突，得到每行的处理方式
+async fn check_rows(
+    conn: &DatabaseConnection,
+    rows: Vec<ImportRow>,
+    merge_copies: bool,
+) -> Result<Vec<ImportReportLine>, Error> {
+    let mut report = Vec::with_capacity(rows.len());
+    let mut seen_isbns = HashSet::new();
+    for row in rows {
+        let mut report_line = ImportReportLine {
+            line: row.line,
+            name: row.name,
+            isbn: row.isbn,
+            copies: 0,
+            action: ImportAction::Invalid,
+            message: String::new(),
+            book: None,
+        };
+        let parsed = row.parsed.and_then(|parsed| {
+            if seen_isbns.insert(parsed.0.isbn.clone()) {
+                Ok(parsed)
+            } else {
+                Err("与文件中前面的记录 ISBN 重复".to_owned())
+            }
+        });
+        match parsed {
+            Ok((book, copies)) => {
+                report_line.copies = copies;
+                match Query::find_books_by_isbn(conn, book.isbn.clone()).await?.pop() {
+                    Some(existing) if merge_copies => {
+                        report_line.action = ImportAction::Merge;
+                        report_line.message =
+                            format!("已有图书《{}》，将增加 {copies} 个副本", existing.name);
+                        report_line.book = Some(existing);
+                    }
+                    Some(existing) => {
+                        report_line.message = format!("ISBN 与已有图书《{}》重复", existing.name);
+                    }
+                    None => {
+                        report_line.action = ImportAction::Create;
+                        report_line.book = Some(book);
+                    }
+                }
+            }
+            Err(message) => report_line.message = message,
+        }
+        report.push(report_line);
+    }
+    Ok(report)
+}
+
+/// 校验单行的字段，返回规范化的 ISBN、出版日期与副本数，副本数留空时为 1
+fn parse_fields(
+    name: &str,
+    isbn: &str,
+    publish_year: &str,
+    copies: &str,
+) -> Result<(String, NaiveDate, u32), String> {
+    if name.is_empty() {
+        return Err("书名不能为空".to_owned());
+    }
+    let isbn = Isbn::parse(isbn).map_err(|err| err.to_string())?;
+    let publish_year = parse_publish_year(publish_year)
+        .ok_or_else(|| format!("无法识别出版年份“{publish_year}”"))?;
+    let copies = match copies {
+        "" => 1,
+        copies => copies
+            .parse::<u32>()
+            .ok()
+            .filter(|copies| (1..=MAX_COPIES).contains(copies))
+            .ok_or_else(|| format!("副本数应为 1 到 {MAX_COPIES} 之间的整数"))?,
+    };
+    Ok((isbn.into(), publish_year, copies))
+}
+
+/// 接受完整日期或只写年份，只有年份时记为当年 1 月 1 日
+fn parse_publish_year(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y/%m/%d"))
+        .ok()
+        .or_else(|| NaiveDate::from_ymd_opt(value.parse().ok()?, 1, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_publish_year_formats() {
+        assert_eq!(parse_publish_year("2004-03-15"), Some(date(2004, 3, 15)));
+        assert_eq!(parse_publish_year("2004/03/15"), Some(date(2004, 3, 15)));
+        assert_eq!(parse_publish_year("2004"), Some(date(2004, 1, 1)));
+        assert_eq!(parse_publish_year("2004-02-30"), None);
+        assert_eq!(parse_publish_year("2004年"), None);
+        assert_eq!(parse_publish_year(""), None);
+    }
+
+    #[test]
+    fn parses_valid_fields() {
+        assert_eq!(
+            parse_fields("数据结构", "978-7-111-12748-2", "2004", ""),
+            Ok(("9787111127482".to_owned(), date(2004, 1, 1), 1))
+        );
+        assert_eq!(
+            parse_fields("数据结构", "9787111127482", "2004-03-15", "100"),
+            Ok(("9787111127482".to_owned(), date(2004, 3, 15), 100))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_fields() {
+        assert_eq!(
+            parse_fields("", "9787111127482", "2004", ""),
+            Err("书名不能为空".to_owned())
+        );
+        assert_eq!(
+            parse_fields("数据结构", "9787111127481", "2004", ""),
+            Err("ISBN 校验位错误，应为 2".to_owned())
+        );
+        assert_eq!(
+            parse_fields("数据结构", "9787111127482", "去年", ""),
+            Err("无法识别出版年份“去年”".to_owned())
+        );
+        for copies in ["0", "101", "-1", "两本"] {
+            assert_eq!(
+                parse_fields("数据结构", "9787111127482", "2004", copies),
+                Err("副本数应为 1 到 100 之间的整数".to_owned())
+            );
+        }
+    }
+}
@@ -4,7 +4,11 @@ use book_manager_service::Query;
 
 use crate::{
     error::Error,
-    handlers::{basic_context, PageParams, DEFAULT_NUMBER_PER_PAGE},
+    handlers::{
+        basic_context,
+        categories::{category_filter_ids, category_options, CategoryFilter},
+        PageParams, DEFAULT_NUMBER_PER_PAGE,
+    },
     AppState,
 };
 
@@ -12,15 +16,26 @@ pub async fn list_books_handler(
     app_state: web::Data<AppState>,
     session: Session,
     params: web::Query<PageParams>,
+    filter: web::Query<CategoryFilter>,
 ) -> Result<HttpResponse, Error> {
     let template = &app_state.templates;
     let conn = &app_state.conn;
     let page = params.page.unwrap_or(1);
     let number_per_page = params.number_per_page.unwrap_or(DEFAULT_NUMBER_PER_PAGE);
-    let (books, num_pages) = Query::find_books_in_page(conn, page, number_per_page).await?;
+    let category = filter.category_id()?;
+    let category_ids = category_filter_ids(conn, category).await?;
+    let (books, num_pages) =
+        Query::find_books_detail_in_page(conn, category_ids.as_deref(), page, number_per_page)
+            .await?;
+    // 分页与导出保留筛选条件
+    let filter_query = category.map(|id| format!("category={id}&")).unwrap_or_default();
     let mut ctx = basic_context(&session)?;
     ctx.insert("title", "图书列表");
     ctx.insert("books", &books);
+    ctx.insert("category", &category);
+    ctx.insert("category_options", &category_options(conn).await?);
+    ctx.insert("page_path", &format!("/books?{filter_query}"));
+    ctx.insert("export_path", &format!("/books/export?{filter_query}"));
     ctx.insert("page", &page);
     ctx.insert("num_pages", &num_pages);
     ctx.insert("number_per_page", &number_per_page);
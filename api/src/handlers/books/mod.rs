@@ -1,11 +1,15 @@
-pub mod detail;
-pub mod edit;
-pub mod new;
-pub mod list;
-pub mod delete;
-
-pub use detail::*;
-pub use edit::*;
-pub use new::*;
-pub use list::*;
-pub use delete::*;
\ No newline at end of file
+pub mod detail;
+pub mod edit;
+pub mod new;
+pub mod list;
+pub mod delete;
+pub mod export;
+pub mod import;
+
+pub use detail::*;
+pub use edit::*;
+pub use new::*;
+pub use list::*;
+pub use delete::*;
+pub use export::*;
+pub use import::*;
\ No newline at end of file
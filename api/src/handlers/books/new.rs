@@ -1,9 +1,20 @@
-use book_manager_service::Mutation;
+use std::collections::HashMap;
+
+use book_manager_service::{
+    sea_orm::{self, DatabaseConnection, TransactionTrait},
+    Mutation, Query,
+};
 use actix_session::Session;
 use actix_web::{web, HttpResponse};
-use entity::books;
+use chrono::NaiveDate;
+use entity::{books, Isbn};
+use serde::{Deserialize, Serialize};
 
-use crate::{error::Error, AppState, handlers::basic_context, flash_success};
+use crate::{
+    error::Error,
+    handlers::{basic_context, categories::parse_subjects},
+    AppState, flash_success,
+};
 
 pub async fn new_book_handler(
     app_state: web::Data<AppState>,
@@ -16,16 +27,106 @@ pub async fn new_book_handler(
     Ok(HttpResponse::Ok().content_type("text/html").body(body))
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NewBookForm {
+    name: String,
+    author: String,
+    publisher: String,
+    publish_year: NaiveDate,
+    isbn: String,
+    copies: u32,
+    shelf_location: String,
+    #[serde(default)]
+    call_number: String,
+    /// 主题类号，多个用分号分隔
+    #[serde(default)]
+    subjects: String,
+}
+
 pub async fn new_book_post_handler(
     app_state: web::Data<AppState>,
     session: Session,
-    post_form: web::Form<books::Model>,
+    post_form: web::Form<NewBookForm>,
 ) -> Result<HttpResponse, Error> {
-    let post_form = post_form.into_inner();
     let conn = &app_state.conn;
-    Mutation::create_book(conn, post_form).await?;
+    let form = post_form.into_inner();
+    let isbn = validate_isbn(conn, &form.isbn, None).await?;
+    let subjects = parse_subjects(conn, &form.subjects).await?;
+    let (isbn, category_ids) = match (isbn, subjects) {
+        (Ok(isbn), Ok(category_ids)) => (isbn, category_ids),
+        (isbn, subjects) => {
+            let errors: HashMap<_, _> = [("isbn", isbn.err()), ("subjects", subjects.err())]
+                .into_iter()
+                .filter_map(|(field, message)| Some((field, message?)))
+                .collect();
+            let mut ctx = basic_context(&session)?;
+            ctx.insert("title", "新建图书");
+            ctx.insert("book", &form);
+            ctx.insert("errors", &errors);
+            let body = app_state
+                .templates
+                .read()
+                .unwrap()
+                .render("books/new.html.tera", &ctx)?;
+            return Ok(HttpResponse::Ok().content_type("text/html").body(body));
+        }
+    };
+    let NewBookForm {
+        name,
+        author,
+        publisher,
+        publish_year,
+        copies,
+        shelf_location,
+        call_number,
+        ..
+    } = form;
+    let book = books::Model {
+        id: 0,
+        name,
+        author,
+        publisher,
+        publish_year,
+        isbn,
+        call_number: call_number.trim().to_owned(),
+    };
+    conn.transaction::<_, (), sea_orm::DbErr>(|txn| {
+        Box::pin(async move {
+            let book = Mutation::create_book(txn, book).await?;
+            Mutation::set_book_categories(txn, book.id, &category_ids).await?;
+            Mutation::create_book_items(txn, book.id, copies, shelf_location.trim().to_owned())
+                .await?;
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
     flash_success(&session, "添加成功")?;
     Ok(HttpResponse::Found()
         .append_header(("Location", "/books"))
         .finish())
 }
+
+/// 校验并规范化表单中的 ISBN，`id` 为正在编辑的图书。
+/// 无效或与其他图书重复时返回显示在输入框下的提示
+pub(super) async fn validate_isbn(
+    conn: &DatabaseConnection,
+    isbn: &str,
+    id: Option<i32>,
+) -> Result<Result<String, String>, Error> {
+    let isbn = match Isbn::parse(isbn) {
+        Ok(isbn) => String::from(isbn),
+        Err(err) => return Ok(Err(err.to_string())),
+    };
+    let duplicate = Query::find_books_by_isbn(conn, &isbn)
+        .await?
+        .into_iter()
+        .find(|book| Some(book.id) != id);
+    Ok(match duplicate {
+        Some(book) => Err(format!("ISBN 与已有图书《{}》重复", book.name)),
+        None => Ok(isbn),
+    })
+}
@@ -1,81 +1,134 @@
-use book_manager_service::{
-    sea_orm::{self, TransactionTrait},
-    Mutation, Query,
-};
-use actix_session::Session;
-use actix_web::{web, HttpResponse};
-use migration::DbErr;
-
-use crate::{AppState, error::Error, handlers::DeleteParams, flash_success};
-
-
-#[derive(Debug)]
-pub enum ReturnError {
-    Err(String),
-    DbError(DbErr),
-}
-
-impl ReturnError {
-    pub fn new<T: ToString>(msg: T) -> Self {
-        ReturnError::Err(msg.to_string())
-    }
-}
-
-impl std::fmt::Display for ReturnError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ReturnError::Err(msg) => write!(f, "Borrow error: {}", msg),
-            ReturnError::DbError(err) => write!(f, "Database error: {}", err),
-        }
-    }
-}
-
-impl std::error::Error for ReturnError {}
-
-impl From<DbErr> for ReturnError {
-    fn from(err: DbErr) -> Self {
-        ReturnError::DbError(err)
-    }
-}
-
-impl Into<Error> for ReturnError {
-    fn into(self) -> Error {
-        match self {
-            ReturnError::Err(err) => Error::Other(err),
-            ReturnError::DbError(err) => Error::DbErr(err),
-        }
-    }
-}
-
-pub async fn return_book_handler(
-    app_state: web::Data<AppState>,
-    session: Session,
-    borrow_id: web::Path<i32>,
-    params: web::Query<DeleteParams>,
-) -> Result<HttpResponse, Error> {
-    let conn = &app_state.conn;
-    let borrow_id = borrow_id.into_inner();
-    let borrowed_book = Query::find_borrowed_book_by_id(conn, borrow_id)
-        .await?
-        .ok_or(Error::borrow_record_not_found())?;
-    let book_id = borrowed_book.book_id;
-    conn.transaction::<_, (), ReturnError>(|txn| {
-        Box::pin(async move {
-            let book = Query::find_book_by_id(txn, book_id).await?.unwrap();
-            Mutation::update_book_copies_by_id(txn, book_id, book.copies + 1).await?;
-            Mutation::delete_borrowed_book(txn, borrow_id).await?;
-            Ok(())
-        })
-    })
-    .await
-    .map_err(|err| match err {
-        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
-        sea_orm::TransactionError::Transaction(err) => err.into(),
-    })?;
-    let source = params.into_inner().source.unwrap_or(format!("/books/{book_id}"));
-    flash_success(&session, "删除成功")?;
-
-    Ok(HttpResponse::Found()
-        .append_header(("Location", source))
-        .finish())
-}
+use book_manager_service::{
+    sea_orm::{self, DatabaseConnection, TransactionTrait},
+    Calendar, Fines, Holds, Mutation, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::{fines, IncidentKind, IncidentStatus};
+use migration::DbErr;
+
+use crate::{
+    AppState, error::Error, filters::format_money,
+    handlers::{incidents::resolve_incident, DeleteParams}, flash_error, flash_success,
+};
+
+
+#[derive(Debug)]
+pub enum ReturnError {
+    Err(String),
+    DbError(DbErr),
+}
+
+impl ReturnError {
+    pub fn new<T: ToString>(msg: T) -> Self {
+        ReturnError::Err(msg.to_string())
+    }
+}
+
+impl std::fmt::Display for ReturnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReturnError::Err(msg) => write!(f, "Borrow error: {}", msg),
+            ReturnError::DbError(err) => write!(f, "Database error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReturnError {}
+
+impl From<DbErr> for ReturnError {
+    fn from(err: DbErr) -> Self {
+        ReturnError::DbError(err)
+    }
+}
+
+impl From<ReturnError> for Error {
+    fn from(err: ReturnError) -> Self {
+        match err {
+            ReturnError::Err(err) => Error::Other(err),
+            ReturnError::DbError(err) => Error::DbErr(err),
+        }
+    }
+}
+
+pub async fn return_book_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    borrow_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let borrow_id = borrow_id.into_inner();
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let borrowed_book = Query::find_borrowed_book_by_id(conn, borrow_id)
+        .await?
+        .ok_or(Error::borrow_record_not_found())?;
+    let book_id = borrowed_book.book_id;
+    let source = params.into_inner().source.unwrap_or(format!("/books/{book_id}"));
+    if borrowed_book.returned_at.is_some() {
+        flash_error(&session, "该书已归还")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", source))
+            .finish());
+    }
+    let now = chrono::Local::now().naive_local();
+    let calendar = Calendar::load(conn).await?;
+    match return_book(conn, borrow_id, staff_id, now, &calendar).await {
+        Ok(Some(fine)) => flash_success(
+            &session,
+            format!("归还成功，逾期罚款 {} 元", format_money(fine.amount as i64)),
+        )?,
+        Ok(None) => flash_success(&session, "归还成功")?,
+        Err(Error::Other(msg)) => flash_error(&session, msg)?,
+        Err(err) => return Err(err),
+    }
+
+    Ok(HttpResponse::Found()
+        .append_header(("Location", source))
+        .finish())
+}
+
+/// 归还图书：结算逾期罚款，副本优先留给预约读者，返回产生的罚款。
+/// 声称已还的借阅视为找回，处理其登记，罚款停留在登记时
+pub(crate) async fn return_book(
+    conn: &DatabaseConnection,
+    borrow_id: i32,
+    staff_id: i32,
+    returned_at: chrono::NaiveDateTime,
+    calendar: &Calendar,
+) -> Result<Option<fines::Model>, Error> {
+    let calendar = calendar.clone();
+    conn.transaction::<_, Option<fines::Model>, ReturnError>(|txn| {
+        Box::pin(async move {
+            if let Some(incident) = Query::find_open_item_incident_by_borrow_id(txn, borrow_id)
+                .await?
+                .filter(|incident| incident.kind == IncidentKind::ClaimedReturned)
+            {
+                let note = "归还时找回".to_owned();
+                resolve_incident(txn, incident, IncidentStatus::Found, note, staff_id).await?;
+                return Ok(None);
+            }
+            // 重复提交或借还台同时扫描时只有一次能结束借阅
+            let Some(borrowed_book) =
+                Mutation::return_borrowed_book(txn, borrow_id, staff_id, returned_at).await?
+            else {
+                return Err(ReturnError::new("该书已归还"));
+            };
+            // 按归还时间结算逾期罚款
+            let fine = Fines::assess_loan(txn, &borrowed_book, returned_at, &calendar).await?;
+            // 归还的副本优先留给预约读者
+            let item = Query::find_book_item_by_id(txn, borrowed_book.item_id)
+                .await?
+                .ok_or(ReturnError::new("没有这个副本"))?;
+            Holds::release_book_item(txn, &item, staff_id).await?;
+            Ok(fine)
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => err.into(),
+    })
+}
@@ -1,145 +1,282 @@
-use book_manager_service::{
-    sea_orm::{self, DatabaseConnection, TransactionTrait},
-    Mutation, Query,
-};
-use actix_session::Session;
-use actix_web::{web, HttpResponse};
-use chrono::NaiveDate;
-use entity::borrowed_books;
-use migration::DbErr;
-use serde::Deserialize;
-
-use crate::{error::Error, AppState, flash_error, flash_success};
-
-#[derive(Deserialize)]
-pub struct BorrowBookForm {
-    pub user_name: String,
-    pub return_date: chrono::NaiveDate,
-}
-
-#[derive(Debug)]
-pub enum BorrowError {
-    Err(String),
-    DatabaseError(DbErr),
-}
-
-impl BorrowError {
-    pub fn new<T: ToString>(msg: T) -> Self {
-        BorrowError::Err(msg.to_string())
-    }
-}
-
-impl std::fmt::Display for BorrowError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            BorrowError::Err(msg) => write!(f, "Borrow error: {}", msg),
-            BorrowError::DatabaseError(err) => write!(f, "Database error: {}", err),
-        }
-    }
-}
-
-impl std::error::Error for BorrowError {}
-
-impl From<DbErr> for BorrowError {
-    fn from(err: DbErr) -> Self {
-        BorrowError::DatabaseError(err)
-    }
-}
-
-pub async fn borrow_book_post_handler(
-    app_state: web::Data<AppState>,
-    session: Session,
-    book_id: web::Path<i32>,
-    post_form: web::Form<BorrowBookForm>,
-) -> Result<HttpResponse, Error> {
-    let BorrowBookForm {
-        user_name,
-        return_date,
-    } = post_form.into_inner();
-
-    let book_id = book_id.into_inner();
-
-    // 使用一个单独的函数将HTTP响应构建成一个闭包，这样可以重复使用
-    let http_response = || {
-        HttpResponse::Found()
-            .append_header(("Location", format!("/books/{book_id}", book_id = book_id)))
-            .finish()
-    };
-
-    let conn = &app_state.conn;
-
-    // 通过用户名寻找用户，如果未找到用户则返回错误信息
-    if let Some(user) = Query::find_user_by_name(conn, &user_name).await? {
-        // 尝试借阅图书，如果出错则返回错误信息
-        match borrow_book(conn, user.id, book_id, return_date).await {
-            Ok(_) => {
-                flash_success(&session, "借阅成功")?;
-                Ok(http_response())
-            }
-            Err(err) => {
-                flash_error(&session, format!("借阅图书失败，错误信息：{}", err))?;
-                Ok(http_response())
-            }
-        }
-    } else {
-        flash_error(&session, "未找到用户")?;
-        Ok(http_response())
-    }
-}
-
-async fn borrow_book(
-    conn: &DatabaseConnection,
-    user_id: i32,
-    book_id: i32,
-    return_date: chrono::NaiveDate,
-) -> Result<(), BorrowError> {
-    conn.transaction::<_, (), BorrowError>(|txn| {
-        Box::pin(async move {
-            let borrowed_books = Query::find_borrowed_books_by_user_id(txn, user_id).await?;
-
-            let current_date = chrono::Local::now().naive_local().date();
-            can_borrow_book(&borrowed_books, current_date, return_date)
-                .map_err(BorrowError::Err)?;
-
-            let book = Query::find_book_by_id(txn, book_id).await?;
-            match book {
-                Some(book) if book.copies > 0 => {
-                    Mutation::update_book_copies_by_id(txn, book_id, book.copies - 1).await?;
-                }
-                Some(_) => return Err(BorrowError::Err("没有库存了".to_owned())),
-                None => return Err(BorrowError::Err("没有这本书".to_owned())),
-            }
-
-            Mutation::create_borrowed_book(txn, user_id, book_id, current_date, return_date)
-                .await?;
-            Ok(())
-        })
-    })
-    .await
-    .map_err(|err| match err {
-        sea_orm::TransactionError::Connection(err) => BorrowError::DatabaseError(err),
-        sea_orm::TransactionError::Transaction(err) => err,
-    })
-}
-
-fn can_borrow_book(
-    borrowed_books: &[borrowed_books::Model],
-    current_date: NaiveDate,
-    return_date: NaiveDate,
-) -> Result<(), String> {
-    if return_date < current_date {
-        return Err(String::from("归还日期不能早于当前日期"));
-    }
-    if borrowed_books.len() >= 8 {
-        return Err(String::from("最多只能借8本书"));
-    }
-    if (return_date - current_date).num_days() > 30 {
-        return Err(String::from("无法一次性借书超过30天"));
-    }
-    for book in borrowed_books {
-        if book.return_date < current_date {
-            return Err(String::from("有逾期未还的书籍"));
-        }
-    }
-    Ok(())
-}
+use book_manager_service::{
+    sea_orm::{self, DatabaseConnection, TransactionTrait},
+    Calendar, Fines, Holds, LoanState, Mutation, Policy, PolicyRejection, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::{borrowed_books, users, HoldStatus, ItemStatus};
+use migration::DbErr;
+use serde::Deserialize;
+
+use crate::{
+    error::Error, filters::format_due, AppState, flash_error, flash_success,
+};
+
+#[derive(Deserialize)]
+pub struct BorrowBookForm {
+    pub user_name: String,
+    pub return_date: chrono::NaiveDate,
+    pub barcode: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SelfCheckoutForm {
+    pub return_date: chrono::NaiveDate,
+}
+
+#[derive(Debug)]
+pub enum BorrowError {
+    Err(String),
+    DatabaseError(DbErr),
+}
+
+impl BorrowError {
+    pub fn new<T: ToString>(msg: T) -> Self {
+        BorrowError::Err(msg.to_string())
+    }
+}
+
+impl std::fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BorrowError::Err(msg) => write!(f, "Borrow error: {}", msg),
+            BorrowError::DatabaseError(err) => write!(f, "Database error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BorrowError {}
+
+impl From<DbErr> for BorrowError {
+    fn from(err: DbErr) -> Self {
+        BorrowError::DatabaseError(err)
+    }
+}
+
+pub async fn borrow_book_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    book_id: web::Path<i32>,
+    post_form: web::Form<BorrowBookForm>,
+) -> Result<HttpResponse, Error> {
+    let BorrowBookForm {
+        user_name,
+        return_date,
+        barcode,
+    } = post_form.into_inner();
+    let post_return_date = return_date;
+    let barcode = barcode
+        .map(|barcode| barcode.trim().to_owned())
+        .filter(|barcode| !barcode.is_empty());
+
+    let book_id = book_id.into_inner();
+
+    // 使用一个单独的函数将HTTP响应构建成一个闭包，这样可以重复使用
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", format!("/books/{book_id}", book_id = book_id)))
+            .finish()
+    };
+
+    let conn = &app_state.conn;
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+
+    // 通过用户名寻找用户，如果未找到用户则返回错误信息
+    if let Some(user) = Query::find_user_by_name(conn, &user_name).await? {
+        // 尝试借阅图书，如果出错则返回错误信息
+        let now = chrono::Local::now().naive_local();
+        let calendar = Calendar::load(conn).await?;
+        let return_date = Some(return_date);
+        match borrow_book(conn, staff_id, user, book_id, barcode, return_date, now, &calendar).await
+        {
+            Ok(loan) => {
+                flash_success(&session, borrow_message(&loan, post_return_date))?;
+                Ok(http_response())
+            }
+            Err(err) => {
+                flash_error(&session, format!("借阅图书失败，错误信息：{}", err))?;
+                Ok(http_response())
+            }
+        }
+    } else {
+        flash_error(&session, "未找到用户")?;
+        Ok(http_response())
+    }
+}
+
+/// 读者为自己借书，需开启 `SELF_CHECKOUT`，与馆员借出时执行相同的规则检查
+pub async fn self_checkout_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    book_id: web::Path<i32>,
+    post_form: web::Form<SelfCheckoutForm>,
+) -> Result<HttpResponse, Error> {
+    let book_id = book_id.into_inner();
+    let return_date = post_form.into_inner().return_date;
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", format!("/books/{book_id}")))
+            .finish()
+    };
+    if !app_state.self_checkout {
+        flash_error(&session, "未开放自助借阅，请到服务台办理")?;
+        return Ok(http_response());
+    }
+
+    let conn = &app_state.conn;
+    let user_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let user = Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    // 系统通知以第一位管理员的名义发送
+    let sender_id = Query::find_admin_ids(conn)
+        .await?
+        .first()
+        .copied()
+        .unwrap_or(user_id);
+    let now = chrono::Local::now().naive_local();
+    let calendar = Calendar::load(conn).await?;
+    match borrow_book(conn, sender_id, user, book_id, None, Some(return_date), now, &calendar)
+        .await
+    {
+        Ok(loan) => flash_success(&session, borrow_message(&loan, return_date))?,
+        Err(err) => flash_error(&session, format!("借阅图书失败，错误信息：{}", err))?,
+    }
+    Ok(http_response())
+}
+
+/// 实际的应还时间与所选归还日期不同时说明原因
+fn borrow_message(loan: &borrowed_books::Model, return_date: chrono::NaiveDate) -> String {
+    if loan.due_at.is_some() {
+        format!("借阅成功，该书为课程参考书，应于 {} 前归还", format_due(loan))
+    } else if loan.return_date != return_date {
+        format!("借阅成功，归还日期遇闭馆调整为 {}", loan.return_date)
+    } else {
+        "借阅成功".to_owned()
+    }
+}
+
+/// 在 `borrowed_at` 借出图书并返回借阅记录，未指定归还日期时按流通规则的借期计算。
+/// 课程期间的参考书按课程的借期以小时计，忽略指定的归还日期
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn borrow_book(
+    conn: &DatabaseConnection,
+    staff_id: i32,
+    user: users::Model,
+    book_id: i32,
+    barcode: Option<String>,
+    return_date: Option<chrono::NaiveDate>,
+    borrowed_at: chrono::NaiveDateTime,
+    calendar: &Calendar,
+) -> Result<borrowed_books::Model, BorrowError> {
+    let calendar = calendar.clone();
+    conn.transaction::<_, borrowed_books::Model, BorrowError>(|txn| {
+        Box::pin(async move {
+            let user_id = user.id;
+            let current_date = borrowed_at.date();
+            if Query::find_book_by_id(txn, book_id).await?.is_none() {
+                return Err(BorrowError::Err("没有这本书".to_owned()));
+            }
+            if let Some(block) = Query::find_active_user_block(txn, user_id, current_date).await? {
+                return Err(BorrowError::new(PolicyRejection::PatronBlocked(block.end_date)));
+            }
+
+            // 已到书的预约优先借出为其保留的副本
+            let hold = Query::find_active_hold_by_user_id_and_book_id(txn, user_id, book_id).await?;
+            let reserved_item_id = hold.as_ref().and_then(|hold| hold.item_id);
+
+            // 指定条码时借出对应副本，否则借出保留副本或任意一本在架副本
+            let item = match barcode {
+                Some(barcode) => match Query::find_book_item_by_barcode(txn, barcode).await? {
+                    Some(item) if item.book_id != book_id => {
+                        return Err(BorrowError::Err("条码与图书不匹配".to_owned()))
+                    }
+                    Some(item)
+                        if item.status == ItemStatus::OnHoldShelf
+                            && reserved_item_id != Some(item.id) =>
+                    {
+                        return Err(BorrowError::Err("该副本已为其他读者预留".to_owned()))
+                    }
+                    Some(item)
+                        if !matches!(item.status, ItemStatus::Available | ItemStatus::OnHoldShelf) =>
+                    {
+                        return Err(BorrowError::Err("该副本不可借".to_owned()))
+                    }
+                    Some(item) => item,
+                    None => return Err(BorrowError::Err("没有这个条码".to_owned())),
+                },
+                None => match reserved_item_id {
+                    Some(item_id) => Query::find_book_item_by_id(txn, item_id)
+                        .await?
+                        .ok_or(BorrowError::Err("没有这个副本".to_owned()))?,
+                    None => Query::find_available_book_item_by_book_id(txn, book_id)
+                        .await?
+                        .ok_or(BorrowError::Err("没有库存了".to_owned()))?,
+                },
+            };
+
+            // 按读者类别与副本类型适用的流通规则检查
+            let policy = Policy::find_policy(txn, &user.patron_category, &item.item_type).await?;
+            let course = Query::find_active_course_by_book_id(txn, book_id, current_date).await?;
+            let policy = match &course {
+                Some(course) => Policy::with_course(policy, course),
+                None => policy,
+            };
+            let borrowed_books =
+                Query::find_borrowed_books_by_user_id(txn, user_id, LoanState::Active).await?;
+            // 补录脱机借出时 `borrowed_at` 是过去的时间，罚款仍按今天结算
+            Fines::assess_user(txn, user_id, chrono::Local::now().naive_local(), &calendar).await?;
+            let balance = Query::find_user_balance(txn, user_id).await?;
+            let due_at = course.map(|course| {
+                borrowed_at + chrono::Duration::hours(course.loan_hours as i64)
+            });
+            // 归还日期落在闭馆日时调整到开放日，短期借阅按小时计不调整
+            let return_date = match due_at {
+                Some(due_at) => due_at.date(),
+                None => Policy::due_date(&policy, current_date, return_date, &calendar),
+            };
+            // 按调整后的归还日期检查，保证不超出借期
+            Policy::check_borrow(
+                &policy,
+                &borrowed_books,
+                balance,
+                borrowed_at,
+                return_date,
+                &calendar,
+            )
+            .map_err(BorrowError::new)?;
+
+            Mutation::update_book_item_status_by_id(txn, item.id, ItemStatus::Borrowed).await?;
+
+            let loan = Mutation::create_borrowed_book(
+                txn,
+                user_id,
+                &item,
+                current_date,
+                return_date,
+                due_at,
+            )
+            .await?;
+
+            if let Some(hold) = hold {
+                Mutation::update_hold_status_by_id(txn, hold.id, HoldStatus::Fulfilled).await?;
+                // 借走的不是保留副本时，保留副本转给下一位预约读者
+                if let Some(item_id) = reserved_item_id.filter(|&item_id| item_id != item.id) {
+                    if let Some(reserved_item) = Query::find_book_item_by_id(txn, item_id).await? {
+                        Holds::release_book_item(txn, &reserved_item, staff_id).await?;
+                    }
+                }
+            }
+            Ok(loan)
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => BorrowError::DatabaseError(err),
+        sea_orm::TransactionError::Transaction(err) => err,
+    })
+}
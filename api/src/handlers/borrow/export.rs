@@ -0,0 +1,24 @@
+use actix_web::{web, HttpResponse};
+use book_manager_service::{LoanState, Query};
+
+use crate::{
+    error::Error,
+    export::{export, ExportParams},
+    AppState,
+};
+
+/// 导出借阅列表中的未归还借阅
+pub async fn export_borrowed_books_handler(
+    app_state: web::Data<AppState>,
+    params: web::Query<ExportParams>,
+) -> Result<HttpResponse, Error> {
+    let conn = app_state.conn.clone();
+    export(params.format, "loans", move |page, number_per_page| {
+        let conn = conn.clone();
+        async move {
+            Query::find_borrowed_books_detail_in_page(&conn, LoanState::Active, page, number_per_page)
+                .await
+        }
+    })
+    .await
+}
@@ -0,0 +1,83 @@
+use book_manager_service::Query;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{
+    error::Error,
+    handlers::{basic_context, is_admin, PageParams, DEFAULT_NUMBER_PER_PAGE},
+    AppState,
+};
+
+pub async fn user_loan_history_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+    params: web::Query<PageParams>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let user_id = user_id.into_inner();
+    let conn = &app_state.conn;
+    let cache_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::user_not_found())?;
+    if cache_id != user_id && !is_admin(&session)? {
+        return Err(Error::unauthorized());
+    }
+    let user = Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let page = params.page.unwrap_or(1);
+    let number_per_page = params.number_per_page.unwrap_or(DEFAULT_NUMBER_PER_PAGE);
+    let (loans, num_pages) =
+        Query::find_loan_history_in_page_by_user_id(conn, user_id, page, number_per_page).await?;
+    let renewals = Query::find_loan_renewals_by_borrow_ids(
+        conn,
+        loans.iter().map(|loan| loan.borrow_id).collect(),
+    )
+    .await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "借阅历史");
+    ctx.insert("heading", &format!("{}({}) 的借阅历史", user.nickname, user.name));
+    ctx.insert("path", &format!("/users/{user_id}/history"));
+    ctx.insert("loans", &loans);
+    ctx.insert("renewals", &renewals);
+    ctx.insert("page", &page);
+    ctx.insert("num_pages", &num_pages);
+    ctx.insert("number_per_page", &number_per_page);
+    let body = template.read().unwrap().render("borrow/history.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+pub async fn book_loan_history_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    book_id: web::Path<i32>,
+    params: web::Query<PageParams>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let book_id = book_id.into_inner();
+    let conn = &app_state.conn;
+    let book = Query::find_book_by_id(conn, book_id)
+        .await?
+        .ok_or(Error::book_not_found())?;
+    let page = params.page.unwrap_or(1);
+    let number_per_page = params.number_per_page.unwrap_or(DEFAULT_NUMBER_PER_PAGE);
+    let (loans, num_pages) =
+        Query::find_loan_history_in_page_by_book_id(conn, book_id, page, number_per_page).await?;
+    let renewals = Query::find_loan_renewals_by_borrow_ids(
+        conn,
+        loans.iter().map(|loan| loan.borrow_id).collect(),
+    )
+    .await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "借阅历史");
+    ctx.insert("heading", &format!("《{}》的借阅历史", book.name));
+    ctx.insert("path", &format!("/books/{book_id}/history"));
+    ctx.insert("loans", &loans);
+    ctx.insert("renewals", &renewals);
+    ctx.insert("page", &page);
+    ctx.insert("num_pages", &num_pages);
+    ctx.insert("number_per_page", &number_per_page);
+    let body = template.read().unwrap().render("borrow/history.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
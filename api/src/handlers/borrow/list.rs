@@ -1,4 +1,4 @@
-use book_manager_service::Query;
+use book_manager_service::{LoanState, Query};
 use actix_session::Session;
 use actix_web::{web, HttpResponse};
 
@@ -18,11 +18,12 @@ pub async fn list_borrowed_books_handler(
     let page = params.page.unwrap_or(1);
     let number_per_page = params.number_per_page.unwrap_or(DEFAULT_NUMBER_PER_PAGE);
     let (borrowed_books, num_pages) =
-        Query::find_borrowed_books_detail_in_page(conn, page, number_per_page).await?;
+        Query::find_borrowed_books_detail_in_page(conn, LoanState::Active, page, number_per_page).await?;
     let is_admin = is_admin(&session)?;
     let mut ctx = basic_context(&session)?;
     ctx.insert("title", "借阅列表");
     ctx.insert("borrowed_books", &borrowed_books);
+    ctx.insert("export_path", "/borrow/export?");
     ctx.insert("page", &page);
     ctx.insert("num_pages", &num_pages);
     ctx.insert("number_per_page", &number_per_page);
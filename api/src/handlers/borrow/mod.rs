@@ -1,7 +1,14 @@
-pub mod borrow;
-pub mod _return;
-pub mod list;
-
-pub use borrow::*;
-pub use _return::*;
-pub use list::*;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod borrow;
+pub mod _return;
+pub mod list;
+pub mod history;
+pub mod renew;
+pub mod export;
+
+pub use borrow::*;
+pub use _return::*;
+pub use list::*;
+pub use history::*;
+pub use renew::*;
+pub use export::*;
\ No newline at end of file
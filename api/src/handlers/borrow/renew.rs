@@ -0,0 +1,120 @@
+use book_manager_service::{
+    sea_orm::{self, DatabaseConnection, DbErr, TransactionTrait},
+    Calendar, Mutation, Policy, PolicyRejection, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::borrowed_books;
+
+use crate::{
+    error::Error,
+    filters::format_due,
+    handlers::{is_admin, DeleteParams},
+    AppState, flash_error, flash_success,
+};
+
+pub async fn renew_book_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    borrow_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let borrow_id = borrow_id.into_inner();
+    let user_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let borrowed_book = Query::find_borrowed_book_by_id(conn, borrow_id)
+        .await?
+        .ok_or(Error::borrow_record_not_found())?;
+    // 读者只能续借自己的书，管理员可以代为续借
+    if borrowed_book.user_id != user_id && !is_admin(&session)? {
+        return Err(Error::unauthorized());
+    }
+    let source = params
+        .into_inner()
+        .source
+        .unwrap_or(format!("/users/{}", borrowed_book.user_id));
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", source.clone()))
+            .finish()
+    };
+
+    match renew_loan(conn, &borrowed_book, user_id).await? {
+        Ok(loan) => {
+            flash_success(&session, format!("续借成功，新的归还日期为 {}", format_due(&loan)))?;
+        }
+        Err(err) => flash_error(&session, format!("续借失败，错误信息：{}", err))?,
+    }
+    Ok(http_response())
+}
+
+/// 按借阅人类别与副本类型适用的流通规则续借，返回续借后的借阅记录或拒绝原因。
+/// 课程期间的参考书按课程的规则续借，从续借时起重新计算借期。
+/// 检查与更新在同一事务中进行，借阅记录在事务内重新读取，避免重复提交越过续借次数
+pub(crate) async fn renew_loan(
+    conn: &DatabaseConnection,
+    borrowed_book: &borrowed_books::Model,
+    renewed_by: i32,
+) -> Result<Result<borrowed_books::Model, PolicyRejection>, Error> {
+    let borrow_id = borrowed_book.id;
+    conn.transaction::<_, Result<borrowed_books::Model, PolicyRejection>, DbErr>(|txn| {
+        Box::pin(async move {
+            let borrowed_book = Query::find_borrowed_book_by_id(txn, borrow_id)
+                .await?
+                .ok_or(DbErr::RecordNotFound("Cannot find borrowed book.".to_owned()))?;
+            let patron = Query::find_user_by_id(txn, borrowed_book.user_id)
+                .await?
+                .ok_or(DbErr::RecordNotFound("Cannot find user.".to_owned()))?;
+            let item = Query::find_book_item_by_id(txn, borrowed_book.item_id)
+                .await?
+                .ok_or(DbErr::RecordNotFound("Cannot find book item.".to_owned()))?;
+            let policy = Policy::find_policy(txn, &patron.patron_category, &item.item_type).await?;
+            let now = chrono::Local::now().naive_local();
+            let current_date = now.date();
+            if let Some(block) =
+                Query::find_active_user_block(txn, borrowed_book.user_id, current_date).await?
+            {
+                return Ok(Err(PolicyRejection::PatronBlocked(block.end_date)));
+            }
+            let course =
+                Query::find_active_course_by_book_id(txn, borrowed_book.book_id, current_date)
+                    .await?;
+            let policy = match &course {
+                Some(course) => Policy::with_course(policy, course),
+                None => policy,
+            };
+            // 有读者排队预约时不能续借
+            let has_holds = Query::find_next_waiting_hold_by_book_id(txn, borrowed_book.book_id)
+                .await?
+                .is_some();
+            let calendar = Calendar::load(txn).await?;
+            if let Err(err) =
+                Policy::check_renew(&policy, &borrowed_book, now, has_holds, &calendar)
+            {
+                return Ok(Err(err));
+            }
+            let due_at =
+                course.map(|course| now + chrono::Duration::hours(course.loan_hours as i64));
+            let return_date = match due_at {
+                Some(due_at) => due_at.date(),
+                None => Policy::renewed_return_date(&policy, &borrowed_book, &calendar),
+            };
+            Ok(Mutation::renew_borrowed_book(
+                txn,
+                &borrowed_book,
+                renewed_by,
+                return_date,
+                due_at,
+            )
+            .await?
+            .ok_or(PolicyRejection::LoanChanged))
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })
+}
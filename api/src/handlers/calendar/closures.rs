@@ -0,0 +1,52 @@
+use book_manager_service::Mutation;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+#[derive(Deserialize)]
+pub struct ClosureForm {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub name: String,
+}
+
+pub async fn new_closure_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    post_form: web::Form<ClosureForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let ClosureForm {
+        start_date,
+        end_date,
+        name,
+    } = post_form.into_inner();
+    let name = name.trim().to_owned();
+    if name.is_empty() {
+        flash_error(&session, "名称不能为空")?;
+    } else if end_date < start_date {
+        flash_error(&session, "结束日期不能早于开始日期")?;
+    } else {
+        Mutation::create_closure(conn, start_date, end_date, name).await?;
+        flash_success(&session, "添加成功")?;
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/calendar"))
+        .finish())
+}
+
+pub async fn delete_closure_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    Mutation::delete_closure(conn, id.into_inner()).await?;
+    flash_success(&session, "删除成功")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/calendar"))
+        .finish())
+}
@@ -0,0 +1,52 @@
+use book_manager_service::Mutation;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveTime;
+use serde::Deserialize;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+#[derive(Deserialize)]
+pub struct OpeningHoursForm {
+    pub open_time: String,
+    pub close_time: String,
+    pub closed: bool,
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+        .ok()
+}
+
+pub async fn update_opening_hours_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    weekday: web::Path<i32>,
+    post_form: web::Form<OpeningHoursForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let OpeningHoursForm {
+        open_time,
+        close_time,
+        closed,
+    } = post_form.into_inner();
+    match (parse_time(&open_time), parse_time(&close_time)) {
+        (Some(open_time), Some(close_time)) if open_time < close_time => {
+            Mutation::update_opening_hour_by_weekday(
+                conn,
+                weekday.into_inner(),
+                open_time,
+                close_time,
+                closed,
+            )
+            .await?;
+            flash_success(&session, "修改成功")?;
+        }
+        (Some(_), Some(_)) => flash_error(&session, "闭馆时间必须晚于开馆时间")?,
+        _ => flash_error(&session, "请输入正确的时间")?,
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/calendar"))
+        .finish())
+}
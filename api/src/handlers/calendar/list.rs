@@ -0,0 +1,22 @@
+use book_manager_service::Query;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, handlers::basic_context, AppState};
+
+pub async fn calendar_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let opening_hours = Query::find_opening_hours(conn).await?;
+    let closures = Query::find_closures(conn).await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "开馆日历");
+    ctx.insert("opening_hours", &opening_hours);
+    ctx.insert("closures", &closures);
+    ctx.insert("today", &chrono::Local::now().naive_local().date());
+    let body = template.read().unwrap().render("calendar/list.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
@@ -0,0 +1,7 @@
+pub mod list;
+pub mod hours;
+pub mod closures;
+
+pub use list::*;
+pub use hours::*;
+pub use closures::*;
@@ -0,0 +1,31 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use book_manager_service::{CategoryTree, Mutation, Query};
+
+use crate::{error::Error, flash_error, flash_success, AppState};
+
+/// 有下级类目时不能删除，图书与该类目的关联随之删除
+pub async fn delete_category_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let category = Query::find_category_by_id(conn, id.into_inner())
+        .await?
+        .ok_or(Error::category_not_found())?;
+    let tree = CategoryTree::load(conn, category.scheme).await?;
+    if !tree.children(Some(category.id)).is_empty() {
+        flash_error(&session, "该类目下还有下级类目，请先删除下级类目")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", format!("/categories/{}", category.id)))
+            .finish());
+    }
+    Mutation::delete_category(conn, category.id).await?;
+    flash_success(&session, "删除成功")?;
+    let location = match category.parent_id {
+        Some(parent_id) => format!("/categories/{parent_id}"),
+        None => format!("/categories?scheme={:?}", category.scheme),
+    };
+    Ok(HttpResponse::Found().append_header(("Location", location)).finish())
+}
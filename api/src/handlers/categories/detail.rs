@@ -0,0 +1,43 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use book_manager_service::{CategoryTree, Query};
+
+use crate::{
+    error::Error,
+    handlers::{basic_context, PageParams, DEFAULT_NUMBER_PER_PAGE},
+    AppState,
+};
+
+use super::category_nodes;
+
+/// 类目及其下级类目，列出归入该类目或其下级类目的图书
+pub async fn category_detail_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+    params: web::Query<PageParams>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let category = Query::find_category_by_id(conn, id.into_inner())
+        .await?
+        .ok_or(Error::category_not_found())?;
+    let tree = CategoryTree::load(conn, category.scheme).await?;
+    let page = params.page.unwrap_or(1);
+    let number_per_page = params.number_per_page.unwrap_or(DEFAULT_NUMBER_PER_PAGE);
+    let ids = tree.subtree_ids(category.id);
+    let (books, num_pages) =
+        Query::find_books_detail_in_page(conn, Some(&ids), page, number_per_page).await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", &format!("{} {}", category.code, category.name));
+    ctx.insert("category", &category);
+    ctx.insert("ancestors", &tree.ancestors(category.id));
+    ctx.insert("children", &category_nodes(&tree, Some(category.id)));
+    ctx.insert("book_count", &tree.book_count(category.id));
+    ctx.insert("books", &books);
+    ctx.insert("page", &page);
+    ctx.insert("num_pages", &num_pages);
+    ctx.insert("number_per_page", &number_per_page);
+    let body = template.read().unwrap().render("categories/detail.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
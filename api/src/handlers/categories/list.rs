@@ -0,0 +1,31 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use book_manager_service::CategoryTree;
+use entity::CategoryScheme;
+use serde::Deserialize;
+
+use crate::{error::Error, handlers::basic_context, AppState};
+
+use super::category_nodes;
+
+#[derive(Debug, Deserialize)]
+pub struct SchemeParams {
+    scheme: Option<CategoryScheme>,
+}
+
+pub async fn list_categories_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    params: web::Query<SchemeParams>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let scheme = params.scheme.unwrap_or(CategoryScheme::Clc);
+    let tree = CategoryTree::load(conn, scheme).await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "分类浏览");
+    ctx.insert("scheme", &scheme);
+    ctx.insert("children", &category_nodes(&tree, None));
+    let body = template.read().unwrap().render("categories/list.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
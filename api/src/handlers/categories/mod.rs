@@ -0,0 +1,123 @@
+pub mod list;
+pub mod detail;
+pub mod new;
+pub mod delete;
+
+pub use list::*;
+pub use detail::*;
+pub use new::*;
+pub use delete::*;
+
+use book_manager_service::{sea_orm::ConnectionTrait, CategoryTree, Query};
+use entity::{categories, CategoryScheme};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// 图书列表与搜索的类目筛选，下拉框选“全部分类”时为空字符串
+#[derive(Debug, Deserialize)]
+pub struct CategoryFilter {
+    category: Option<String>,
+}
+
+impl CategoryFilter {
+    pub fn category_id(&self) -> Result<Option<i32>, Error> {
+        match self.category.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(id) => id
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::bad_request("Invalid category")),
+        }
+    }
+}
+
+/// 类目及其下级类目收录的图书种数
+#[derive(Debug, Serialize)]
+pub struct CategoryNode<'a> {
+    #[serde(flatten)]
+    category: &'a categories::Model,
+    books: usize,
+}
+
+fn category_nodes<'a>(tree: &'a CategoryTree, parent: Option<i32>) -> Vec<CategoryNode<'a>> {
+    tree.children(parent)
+        .into_iter()
+        .map(|category| CategoryNode {
+            category,
+            books: tree.book_count(category.id),
+        })
+        .collect()
+}
+
+/// 筛选条件中的类目及其全部下级类目
+pub async fn category_filter_ids<C: ConnectionTrait>(
+    db: &C,
+    category_id: Option<i32>,
+) -> Result<Option<Vec<i32>>, Error> {
+    let Some(id) = category_id else {
+        return Ok(None);
+    };
+    let category = Query::find_category_by_id(db, id)
+        .await?
+        .ok_or(Error::category_not_found())?;
+    let tree = CategoryTree::load(db, category.scheme).await?;
+    Ok(Some(tree.subtree_ids(id)))
+}
+
+/// 类目下拉框的选项，按层级缩进
+#[derive(Debug, Serialize)]
+pub struct CategoryOption {
+    id: i32,
+    scheme: CategoryScheme,
+    label: String,
+}
+
+pub async fn category_options<C: ConnectionTrait>(db: &C) -> Result<Vec<CategoryOption>, Error> {
+    let mut options = Vec::new();
+    for scheme in [CategoryScheme::Clc, CategoryScheme::Dewey] {
+        let tree = CategoryTree::load(db, scheme).await?;
+        options.extend(tree.flatten().into_iter().map(|(depth, category)| CategoryOption {
+            id: category.id,
+            scheme,
+            label: format!("{}{} {}", "　".repeat(depth), category.code, category.name),
+        }));
+    }
+    Ok(options)
+}
+
+/// 解析表单中以分号或逗号分隔的类号，两种分类法的类号都可以填写。
+/// 有不存在的类号时返回显示在输入框下的提示
+pub async fn parse_subjects<C: ConnectionTrait>(
+    db: &C,
+    text: &str,
+) -> Result<Result<Vec<i32>, String>, Error> {
+    let categories = Query::find_categories(db).await?;
+    let mut ids = Vec::new();
+    for code in text.split([';', '；', ',', '，']).map(str::trim).filter(|code| !code.is_empty()) {
+        let matched: Vec<i32> = categories
+            .iter()
+            .filter(|category| category.code.eq_ignore_ascii_case(code))
+            .map(|category| category.id)
+            .collect();
+        if matched.is_empty() {
+            return Ok(Err(format!("分类号 {code} 不存在，请先在分类表中添加")));
+        }
+        for id in matched {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    Ok(Ok(ids))
+}
+
+/// 图书已归入的类号，编辑表单中回填
+pub async fn subject_codes<C: ConnectionTrait>(db: &C, book_id: i32) -> Result<String, Error> {
+    Ok(Query::find_categories_by_book_id(db, book_id)
+        .await?
+        .into_iter()
+        .map(|category| category.code)
+        .collect::<Vec<_>>()
+        .join("；"))
+}
@@ -0,0 +1,67 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use book_manager_service::{Mutation, Query};
+use entity::CategoryScheme;
+use serde::Deserialize;
+
+use crate::{error::Error, flash_error, flash_success, AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct NewCategoryForm {
+    scheme: CategoryScheme,
+    code: String,
+    name: String,
+    /// 留空时取同一分类法中作为类号前缀的最长类号，例如 TP312 归入 TP31
+    parent_code: String,
+}
+
+pub async fn new_category_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    post_form: web::Form<NewCategoryForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let NewCategoryForm {
+        scheme,
+        code,
+        name,
+        parent_code,
+    } = post_form.into_inner();
+    let (code, name, parent_code) = (code.trim(), name.trim(), parent_code.trim());
+    let back = format!("/categories?scheme={scheme:?}");
+    if code.is_empty() || name.is_empty() {
+        flash_error(&session, "类号和类名不能为空")?;
+        return Ok(HttpResponse::Found().append_header(("Location", back)).finish());
+    }
+    if Query::find_category_by_code(conn, scheme, code).await?.is_some() {
+        flash_error(&session, format!("类号 {code} 已存在"))?;
+        return Ok(HttpResponse::Found().append_header(("Location", back)).finish());
+    }
+    let parent = if parent_code.is_empty() {
+        Query::find_categories_by_scheme(conn, scheme)
+            .await?
+            .into_iter()
+            .filter(|category| code.starts_with(&category.code))
+            .max_by_key(|category| category.code.len())
+    } else {
+        match Query::find_category_by_code(conn, scheme, parent_code).await? {
+            Some(parent) => Some(parent),
+            None => {
+                flash_error(&session, format!("上级类号 {parent_code} 不存在"))?;
+                return Ok(HttpResponse::Found().append_header(("Location", back)).finish());
+            }
+        }
+    };
+    let category = Mutation::create_category(
+        conn,
+        scheme,
+        code.to_owned(),
+        name.to_owned(),
+        parent.map(|parent| parent.id),
+    )
+    .await?;
+    flash_success(&session, "添加成功")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/categories/{}", category.id)))
+        .finish())
+}
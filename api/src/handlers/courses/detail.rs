@@ -0,0 +1,27 @@
+use book_manager_service::Query;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, AppState, handlers::basic_context};
+
+/// 课程的参考书与在架数量，无需登录即可查看
+pub async fn course_detail_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let course = Query::find_course_by_id(conn, id.into_inner())
+        .await?
+        .ok_or(Error::course_not_found())?;
+    let books = Query::find_course_reserve_books(conn, course.id).await?;
+    let today = chrono::Local::now().naive_local().date();
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", &course.name);
+    ctx.insert("course", &course);
+    ctx.insert("books", &books);
+    ctx.insert("today", &today);
+    let body = template.read().unwrap().render("courses/detail.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
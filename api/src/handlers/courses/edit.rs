@@ -0,0 +1,34 @@
+use book_manager_service::Mutation;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::courses;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+use super::normalize_course_form;
+
+/// 修改课程，延后结束日期可以让已到期的课程重新生效
+pub async fn edit_course_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+    post_form: web::Form<courses::Model>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let conn = &app_state.conn;
+    match normalize_course_form(post_form.into_inner()) {
+        Ok(course) => {
+            let today = chrono::Local::now().naive_local().date();
+            let course = courses::Model {
+                expired: course.end_date < today,
+                ..course
+            };
+            Mutation::update_course_by_id(conn, id, course).await?;
+            flash_success(&session, "修改成功")?;
+        }
+        Err(err) => flash_error(&session, err)?,
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/courses/{id}")))
+        .finish())
+}
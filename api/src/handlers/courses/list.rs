@@ -0,0 +1,21 @@
+use book_manager_service::Query;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, AppState, handlers::basic_context};
+
+pub async fn list_courses_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let courses = Query::find_courses(conn).await?;
+    let today = chrono::Local::now().naive_local().date();
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "课程参考书");
+    ctx.insert("courses", &courses);
+    ctx.insert("today", &today);
+    let body = template.read().unwrap().render("courses/list.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
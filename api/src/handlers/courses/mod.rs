@@ -0,0 +1,35 @@
+pub mod list;
+pub mod detail;
+pub mod new;
+pub mod edit;
+pub mod delete;
+pub mod reserves;
+
+pub use list::*;
+pub use detail::*;
+pub use new::*;
+pub use edit::*;
+pub use delete::*;
+pub use reserves::*;
+
+use entity::courses;
+
+/// 整理表单中的课程信息，检查日期与借期是否合法
+fn normalize_course_form(mut form_data: courses::Model) -> Result<courses::Model, &'static str> {
+    form_data.code = form_data.code.trim().to_owned();
+    form_data.name = form_data.name.trim().to_owned();
+    form_data.teacher = form_data.teacher.trim().to_owned();
+    if form_data.code.is_empty() || form_data.name.is_empty() {
+        return Err("课程编号与课程名称不能为空");
+    }
+    if form_data.end_date < form_data.start_date {
+        return Err("结束日期不能早于开始日期");
+    }
+    if form_data.loan_hours <= 0 {
+        return Err("借期至少为一小时");
+    }
+    if form_data.max_renewals < 0 {
+        return Err("续借次数不能为负数");
+    }
+    Ok(form_data)
+}
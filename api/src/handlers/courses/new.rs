@@ -0,0 +1,29 @@
+use book_manager_service::Mutation;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::courses;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+use super::normalize_course_form;
+
+pub async fn new_course_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    post_form: web::Form<courses::Model>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    match normalize_course_form(post_form.into_inner()) {
+        Ok(course) => {
+            let course = Mutation::create_course(conn, course).await?;
+            flash_success(&session, "添加成功，请为课程添加参考书")?;
+            return Ok(HttpResponse::Found()
+                .append_header(("Location", format!("/courses/{}", course.id)))
+                .finish());
+        }
+        Err(err) => flash_error(&session, err)?,
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/courses"))
+        .finish())
+}
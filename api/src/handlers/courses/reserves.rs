@@ -0,0 +1,65 @@
+use book_manager_service::{Mutation, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::Isbn;
+use serde::Deserialize;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+#[derive(Deserialize)]
+pub struct CourseReserveForm {
+    pub isbn: String,
+}
+
+/// 按 ISBN 把图书加入课程参考书
+pub async fn new_course_reserve_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    course_id: web::Path<i32>,
+    post_form: web::Form<CourseReserveForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let course = Query::find_course_by_id(conn, course_id.into_inner())
+        .await?
+        .ok_or(Error::course_not_found())?;
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", format!("/courses/{}", course.id)))
+            .finish()
+    };
+    let isbn = match Isbn::parse(&post_form.into_inner().isbn) {
+        Ok(isbn) => String::from(isbn),
+        Err(err) => {
+            flash_error(&session, err)?;
+            return Ok(http_response());
+        }
+    };
+    let Some(book) = Query::find_books_by_isbn(conn, isbn).await?.into_iter().next() else {
+        flash_error(&session, "没有这个 ISBN")?;
+        return Ok(http_response());
+    };
+    if Query::find_course_reserve(conn, course.id, book.id)
+        .await?
+        .is_some()
+    {
+        flash_error(&session, "该书已在参考书列表中")?;
+        return Ok(http_response());
+    }
+    Mutation::create_course_reserve(conn, course.id, book.id).await?;
+    flash_success(&session, format!("已添加《{}》", book.name))?;
+    Ok(http_response())
+}
+
+pub async fn delete_course_reserve_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    path: web::Path<(i32, i32)>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let (course_id, book_id) = path.into_inner();
+    Mutation::delete_course_reserve(conn, course_id, book_id).await?;
+    flash_success(&session, "已移出参考书列表")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/courses/{course_id}")))
+        .finish())
+}
@@ -0,0 +1,32 @@
+use book_manager_service::{LoanState, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, handlers::basic_context, AppState};
+
+use super::get_receipt;
+
+pub async fn desk_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "借还台");
+    // 当前读者及其借阅，便于核对
+    if let Some(patron_name) = session.get::<String>("desk_patron")? {
+        if let Some(patron) = Query::find_user_by_name(conn, &patron_name).await? {
+            let borrowed_books =
+                Query::find_borrowed_books_detail_by_user_id(conn, patron.id, LoanState::Active)
+                    .await?;
+            let balance = Query::find_user_balance(conn, patron.id).await?;
+            ctx.insert("patron", &patron);
+            ctx.insert("borrowed_books", &borrowed_books);
+            ctx.insert("balance", &balance);
+        }
+    }
+    ctx.insert("receipt", &get_receipt(&session)?);
+    let body = template.read().unwrap().render("desk/desk.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
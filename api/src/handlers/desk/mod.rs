@@ -0,0 +1,44 @@
+#[allow(clippy::module_inception)]
+pub mod desk;
+pub mod offline;
+pub mod receipt;
+pub mod scan;
+
+pub use desk::*;
+pub use offline::*;
+pub use receipt::*;
+pub use scan::*;
+
+use actix_session::Session;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// 会话中保留的借还记录条数，记录保存在 cookie 中，不宜过多
+const RECEIPT_LINES: usize = 12;
+
+/// 借还台本次会话的一条处理记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptLine {
+    pub code: String,
+    pub success: bool,
+    pub message: String,
+    /// 借出成功时的借阅记录，用于打印借书凭条
+    #[serde(default)]
+    pub borrow_id: Option<i32>,
+}
+
+fn get_receipt(session: &Session) -> Result<Vec<ReceiptLine>, Error> {
+    Ok(session
+        .get::<Vec<ReceiptLine>>("desk_receipt")?
+        .unwrap_or_default())
+}
+
+/// 新记录排在最前面
+fn push_receipt(session: &Session, lines: Vec<ReceiptLine>) -> Result<(), Error> {
+    let mut receipt = get_receipt(session)?;
+    receipt.splice(0..0, lines.into_iter().rev());
+    receipt.truncate(RECEIPT_LINES);
+    session.insert("desk_receipt", receipt)?;
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use book_manager_service::{sea_orm::DatabaseConnection, Calendar, LoanState, Query};
+use actix_multipart::form::{bytes::Bytes, MultipartForm};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDateTime;
+use entity::ItemStatus;
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    filters::{format_due, format_money},
+    handlers::{
+        basic_context,
+        borrow::{borrow_book, return_book, BorrowError},
+        normalize_isbn,
+    },
+    AppState,
+};
+
+#[derive(MultipartForm)]
+pub struct OfflineUploadForm {
+    pub file: Bytes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OfflineAction {
+    Checkout,
+    Return,
+}
+
+/// 脱机记录中的一行：`时间,操作,用户名,条码或ISBN`，归还时用户名可以留空
+#[derive(Debug)]
+struct OfflineEntry {
+    line: usize,
+    time: NaiveDateTime,
+    action: OfflineAction,
+    user_name: String,
+    code: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum OfflineOutcome {
+    /// 处理成功
+    Succeeded,
+    /// 与系统中的数据冲突，例如没有库存或已被他人借出
+    Conflict,
+    /// 记录本身有误，需要人工核对
+    Manual,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OfflineReportLine {
+    pub line: usize,
+    pub content: String,
+    pub outcome: OfflineOutcome,
+    pub message: String,
+}
+
+pub async fn offline_upload_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "脱机流通");
+    let body = template.read().unwrap().render("desk/offline.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+/// 按时间先后重放脱机记录，每行单独处理，返回逐行的处理报告
+pub async fn offline_upload_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    form: MultipartForm<OfflineUploadForm>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let content = String::from_utf8_lossy(&form.file.data).into_owned();
+    let lines: Vec<&str> = content.lines().collect();
+
+    let now = chrono::Local::now().naive_local();
+    let mut report = Vec::new();
+    let mut entries = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_line(line, now) {
+            Ok((time, action, user_name, code)) => entries.push(OfflineEntry {
+                line: index + 1,
+                time,
+                action,
+                user_name,
+                code,
+            }),
+            Err(message) => report.push(OfflineReportLine {
+                line: index + 1,
+                content: line.to_owned(),
+                outcome: OfflineOutcome::Manual,
+                message: message.to_owned(),
+            }),
+        }
+    }
+    // 同一时间的记录保持文件中的顺序
+    entries.sort_by_key(|entry| entry.time);
+    let calendar = Calendar::load(conn).await?;
+    for entry in entries {
+        let (outcome, message) = match replay(conn, staff_id, &entry, &calendar).await? {
+            Ok(message) => (OfflineOutcome::Succeeded, message),
+            Err((outcome, message)) => (outcome, message),
+        };
+        report.push(OfflineReportLine {
+            line: entry.line,
+            content: lines[entry.line - 1].trim().to_owned(),
+            outcome,
+            message,
+        });
+    }
+    report.sort_by_key(|line| line.line);
+
+    let count = |outcome: fn(&OfflineOutcome) -> bool| {
+        report.iter().filter(|line| outcome(&line.outcome)).count()
+    };
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "脱机流通");
+    ctx.insert("succeeded", &count(|o| matches!(o, OfflineOutcome::Succeeded)));
+    ctx.insert("conflicts", &count(|o| matches!(o, OfflineOutcome::Conflict)));
+    ctx.insert("manual", &count(|o| matches!(o, OfflineOutcome::Manual)));
+    ctx.insert("report", &report);
+    let body = template.read().unwrap().render("desk/offline.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+/// 解析一行脱机记录，时间晚于 `now` 的记录视为有误
+fn parse_line(
+    line: &str,
+    now: NaiveDateTime,
+) -> Result<(NaiveDateTime, OfflineAction, String, String), &'static str> {
+    let fields: Vec<&str> = line.split([',', '\t']).map(str::trim).collect();
+    let [time, action, user_name, code] = fields[..] else {
+        return Err("格式应为：时间,操作,用户名,条码或ISBN");
+    };
+    let time = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M"))
+        .map_err(|_| "时间格式应为 YYYY-MM-DD HH:MM")?;
+    if time > now {
+        return Err("时间晚于当前时间");
+    }
+    let action = match action {
+        "checkout" | "借出" => OfflineAction::Checkout,
+        "return" | "归还" => OfflineAction::Return,
+        _ => return Err("操作应为 checkout/借出 或 return/归还"),
+    };
+    if code.is_empty() {
+        return Err("缺少条码或ISBN");
+    }
+    if action == OfflineAction::Checkout && user_name.is_empty() {
+        return Err("借出记录缺少用户名");
+    }
+    Ok((time, action, user_name.to_owned(), code.to_owned()))
+}
+
+type ReplayResult = Result<String, (OfflineOutcome, String)>;
+
+async fn replay(
+    conn: &DatabaseConnection,
+    staff_id: i32,
+    entry: &OfflineEntry,
+    calendar: &Calendar,
+) -> Result<ReplayResult, Error> {
+    let manual = |message: String| Ok(Err((OfflineOutcome::Manual, message)));
+    let conflict = |message: String| Ok(Err((OfflineOutcome::Conflict, message)));
+    let user = match entry.user_name.as_str() {
+        "" => None,
+        user_name => match Query::find_user_by_card(conn, user_name).await? {
+            Some(user) => Some(user),
+            None => return manual(format!("未找到用户 {user_name}")),
+        },
+    };
+    let item = Query::find_book_item_by_barcode(conn, &entry.code).await?;
+    let book = match &item {
+        Some(item) => Query::find_book_by_id(conn, item.book_id).await?,
+        None => Query::find_books_by_isbn(conn, normalize_isbn(&entry.code))
+            .await?
+            .into_iter()
+            .next(),
+    };
+    let Some(book) = book else {
+        return manual(format!("没有条码或 ISBN {}", entry.code));
+    };
+
+    match entry.action {
+        OfflineAction::Checkout => {
+            let Some(user) = user else {
+                return manual("借出记录缺少用户名".to_owned());
+            };
+            let user_nickname = user.nickname.clone();
+            let barcode = item.map(|item| item.barcode);
+            let time = entry.time;
+            match borrow_book(conn, staff_id, user, book.id, barcode, None, time, calendar).await {
+                Ok(loan) => Ok(Ok(format!(
+                    "《{}》借给 {user_nickname}，应还日期 {}",
+                    book.name,
+                    format_due(&loan)
+                ))),
+                Err(BorrowError::Err(message)) => conflict(message),
+                Err(BorrowError::DatabaseError(err)) => Err(err.into()),
+            }
+        }
+        OfflineAction::Return => {
+            // 按条码找到副本的借阅；只有 ISBN 时需要用户名确定是哪一笔借阅
+            let loans = match (&item, &user) {
+                (Some(item), _) => {
+                    Query::find_borrowed_books_by_item_id(conn, item.id, LoanState::Active).await?
+                }
+                (None, Some(user)) => {
+                    Query::find_borrowed_books_by_user_id(conn, user.id, LoanState::Active)
+                        .await?
+                        .into_iter()
+                        .filter(|loan| loan.book_id == book.id)
+                        .collect()
+                }
+                (None, None) => return manual("按 ISBN 归还时需要填写用户名".to_owned()),
+            };
+            let loan = match loans[..] {
+                [ref loan] => loan,
+                [] if item.is_some_and(|item| item.status != ItemStatus::Borrowed) => {
+                    return conflict("该副本未借出，可能已经归还".to_owned())
+                }
+                [] => return conflict("没有对应的借阅记录".to_owned()),
+                _ => return manual("该读者借有多本此书，请使用条码".to_owned()),
+            };
+            if user.is_some_and(|user| user.id != loan.user_id) {
+                return conflict("该副本借给了其他读者".to_owned());
+            }
+            if entry.time.date() < loan.borrow_date {
+                return conflict(format!("归还时间早于借阅日期 {}", loan.borrow_date));
+            }
+            match return_book(conn, loan.id, staff_id, entry.time, calendar).await {
+                Ok(Some(fine)) => Ok(Ok(format!(
+                    "归还《{}》，逾期罚款 {} 元",
+                    book.name,
+                    format_money(fine.amount as i64)
+                ))),
+                Ok(None) => Ok(Ok(format!("归还《{}》", book.name))),
+                Err(err) => manual(format!("归还失败：{err}")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2023-07-10 12:00", "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn parses_comma_and_tab_separated_lines() {
+        let (time, action, user_name, code) =
+            parse_line("2023-07-10 09:30, checkout, bob, 0000010001", now()).unwrap();
+        assert_eq!(time.to_string(), "2023-07-10 09:30:00");
+        assert_eq!(action, OfflineAction::Checkout);
+        assert_eq!((user_name.as_str(), code.as_str()), ("bob", "0000010001"));
+        let (time, action, user_name, _) =
+            parse_line("2023-07-10 09:30:15\t归还\t\t9787535735508", now()).unwrap();
+        assert_eq!(time.to_string(), "2023-07-10 09:30:15");
+        assert_eq!(action, OfflineAction::Return);
+        assert!(user_name.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(parse_line("2023-07-10 09:30,checkout,bob", now()).is_err());
+        assert!(parse_line("2023/07/10 09:30,checkout,bob,1", now()).is_err());
+        assert!(parse_line("2023-07-10 09:30,lend,bob,1", now()).is_err());
+        assert!(parse_line("2023-07-10 09:30,return,bob,", now()).is_err());
+        assert_eq!(
+            parse_line("2023-07-10 09:30,checkout,,1", now()).unwrap_err(),
+            "借出记录缺少用户名"
+        );
+    }
+
+    #[test]
+    fn rejects_future_time() {
+        assert!(parse_line("2023-07-10 12:00,return,,1", now()).is_ok());
+        assert_eq!(
+            parse_line("2023-07-10 12:01,return,,1", now()).unwrap_err(),
+            "时间晚于当前时间"
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use book_manager_service::{LoanState, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, pdf, AppState, flash_error};
+
+use super::get_receipt;
+
+/// 当前读者本次服务中借出的图书，归还的不再列出
+pub async fn desk_receipt_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let patron = match session.get::<String>("desk_patron")? {
+        Some(patron_name) => Query::find_user_by_name(conn, &patron_name).await?,
+        None => None,
+    };
+    let Some(patron) = patron else {
+        flash_error(&session, "请先填写读者")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", "/desk"))
+            .finish());
+    };
+    let borrow_ids: Vec<i32> = get_receipt(&session)?
+        .into_iter()
+        .filter_map(|line| line.borrow_id)
+        .collect();
+    let borrowed_books =
+        Query::find_borrowed_books_detail_by_user_id(conn, patron.id, LoanState::Active).await?;
+    let loans: Vec<_> = borrowed_books
+        .iter()
+        .filter(|loan| borrow_ids.contains(&loan.borrow_id))
+        .collect();
+    if loans.is_empty() {
+        flash_error(&session, "本次服务没有借出图书")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", "/desk"))
+            .finish());
+    }
+    let now = chrono::Local::now().naive_local();
+    let body = pdf::loan_receipt(&patron, &loans, now)?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .append_header((
+            "Content-Disposition",
+            format!("inline; filename=\"receipt-{}.pdf\"", now.format("%Y%m%d%H%M")),
+        ))
+        .body(body))
+}
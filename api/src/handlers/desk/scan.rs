@@ -0,0 +1,144 @@
+use book_manager_service::{sea_orm::DatabaseConnection, Calendar, LoanState, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::{users, ItemStatus};
+use serde::Deserialize;
+
+use crate::{
+    error::Error,
+    filters::{format_due, format_money},
+    handlers::{
+        borrow::{borrow_book, return_book},
+        normalize_isbn,
+    },
+    AppState, flash_error,
+};
+
+use super::{push_receipt, ReceiptLine};
+
+#[derive(Deserialize)]
+pub struct DeskScanForm {
+    /// 借出时的读者用户名或读者证条码，只归还时可留空
+    pub user_name: String,
+    /// 扫描的条码或 ISBN，每行一个
+    pub codes: String,
+}
+
+/// 逐个处理扫描的条码：已借出的副本办理归还，其余副本与 ISBN 借给当前读者
+pub async fn desk_scan_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    post_form: web::Form<DeskScanForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let DeskScanForm { user_name, codes } = post_form.into_inner();
+    let user_name = user_name.trim();
+    let patron = if user_name.is_empty() {
+        session.remove("desk_patron");
+        None
+    } else {
+        match Query::find_user_by_card(conn, user_name).await? {
+            Some(patron) => {
+                session.insert("desk_patron", &patron.name)?;
+                Some(patron)
+            }
+            None => {
+                flash_error(&session, "未找到用户")?;
+                return Ok(HttpResponse::Found()
+                    .append_header(("Location", "/desk"))
+                    .finish());
+            }
+        }
+    };
+    let calendar = Calendar::load(conn).await?;
+    let mut lines = Vec::new();
+    for code in codes.split_whitespace() {
+        let result = process_code(conn, staff_id, patron.as_ref(), code, &calendar).await?;
+        let (success, message, borrow_id) = match result {
+            Ok((message, borrow_id)) => (true, message, borrow_id),
+            Err(message) => (false, message, None),
+        };
+        lines.push(ReceiptLine {
+            code: code.to_owned(),
+            success,
+            message,
+            borrow_id,
+        });
+    }
+    push_receipt(&session, lines)?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/desk"))
+        .finish())
+}
+
+pub async fn desk_clear_handler(session: Session) -> Result<HttpResponse, Error> {
+    session.remove("desk_patron");
+    session.remove("desk_receipt");
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/desk"))
+        .finish())
+}
+
+/// 每次扫描单独处理，返回给借还台显示的结果，借出时附带借阅记录
+async fn process_code(
+    conn: &DatabaseConnection,
+    staff_id: i32,
+    patron: Option<&users::Model>,
+    code: &str,
+    calendar: &Calendar,
+) -> Result<Result<(String, Option<i32>), String>, Error> {
+    let (book_id, barcode) = match Query::find_book_item_by_barcode(conn, code).await? {
+        // 声称已还的副本扫描到时办理归还，同时处理其登记
+        Some(item) if matches!(item.status, ItemStatus::Borrowed | ItemStatus::ClaimedReturned) => {
+            let Some(loan) = Query::find_borrowed_books_by_item_id(conn, item.id, LoanState::Active)
+                .await?
+                .pop()
+            else {
+                return Ok(Err("找不到该副本的借阅记录".to_owned()));
+            };
+            let book_name = book_name(conn, item.book_id).await?;
+            let now = chrono::Local::now().naive_local();
+            let message = match return_book(conn, loan.id, staff_id, now, calendar).await {
+                Ok(Some(fine)) => format!(
+                    "归还《{book_name}》，逾期罚款 {} 元",
+                    format_money(fine.amount as i64)
+                ),
+                Ok(None) => format!("归还《{book_name}》"),
+                Err(err) => return Ok(Err(format!("归还失败：{err}"))),
+            };
+            return Ok(Ok((message, None)));
+        }
+        Some(item) => (item.book_id, Some(code.to_owned())),
+        None => match Query::find_books_by_isbn(conn, normalize_isbn(code)).await?.first() {
+            Some(book) => (book.id, None),
+            None => return Ok(Err("没有这个条码或 ISBN".to_owned())),
+        },
+    };
+    let Some(patron) = patron else {
+        return Ok(Err("借出前请先填写读者".to_owned()));
+    };
+    let book_name = book_name(conn, book_id).await?;
+    let now = chrono::Local::now().naive_local();
+    match borrow_book(conn, staff_id, patron.clone(), book_id, barcode, None, now, calendar).await
+    {
+        Ok(loan) => Ok(Ok((
+            format!(
+                "《{book_name}》借给 {}，应还日期 {}",
+                patron.nickname,
+                format_due(&loan)
+            ),
+            Some(loan.id),
+        ))),
+        Err(err) => Ok(Err(format!("借出《{book_name}》失败：{err}"))),
+    }
+}
+
+async fn book_name(conn: &DatabaseConnection, book_id: i32) -> Result<String, Error> {
+    Ok(Query::find_book_by_id(conn, book_id)
+        .await?
+        .map(|book| book.name)
+        .unwrap_or_default())
+}
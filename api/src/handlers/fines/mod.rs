@@ -0,0 +1,14 @@
+pub mod pay;
+pub mod waive;
+
+pub use pay::*;
+pub use waive::*;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PaymentForm {
+    amount: String,
+    #[serde(default)]
+    note: String,
+}
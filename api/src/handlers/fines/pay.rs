@@ -0,0 +1,70 @@
+use book_manager_service::{
+    sea_orm::{self, TransactionTrait},
+    Mutation, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::PaymentKind;
+
+use crate::{
+    error::Error, filters::{format_money, parse_money}, AppState, flash_error, flash_success,
+};
+
+use super::PaymentForm;
+
+pub async fn record_payment_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+    post_form: web::Form<PaymentForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = user_id.into_inner();
+    let conn = &app_state.conn;
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let PaymentForm { amount, note } = post_form.into_inner();
+    let Some(amount) = parse_money(&amount).filter(|amount| *amount > 0) else {
+        flash_error(&session, "请输入正确的金额")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", format!("/users/{user_id}")))
+            .finish());
+    };
+    // 欠款的读取、检查与收款在同一事务中，避免并发收款超过欠款
+    let result = conn.transaction::<_, Result<(), i64>, sea_orm::DbErr>(|txn| {
+        Box::pin(async move {
+            let balance = Query::find_user_balance(txn, user_id).await?;
+            if amount as i64 > balance {
+                return Ok(Err(balance));
+            }
+            Mutation::create_payment(
+                txn,
+                user_id,
+                None,
+                PaymentKind::Payment,
+                amount,
+                staff_id,
+                note.trim().to_owned(),
+            )
+            .await?;
+            Ok(Ok(()))
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
+    match result {
+        Ok(()) => flash_success(&session, format!("已收款 {} 元", format_money(amount as i64)))?,
+        Err(balance) => {
+            flash_error(&session, format!("缴款金额不能超过欠款 {} 元", format_money(balance)))?
+        }
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/users/{user_id}")))
+        .finish())
+}
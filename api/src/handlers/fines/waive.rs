@@ -0,0 +1,74 @@
+use book_manager_service::{
+    sea_orm::{self, TransactionTrait},
+    Mutation, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::PaymentKind;
+
+use crate::{
+    error::Error, filters::{format_money, parse_money}, AppState, flash_error, flash_success,
+};
+
+use super::PaymentForm;
+
+pub async fn waive_fine_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    fine_id: web::Path<i32>,
+    post_form: web::Form<PaymentForm>,
+) -> Result<HttpResponse, Error> {
+    let fine_id = fine_id.into_inner();
+    let conn = &app_state.conn;
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let fine = Query::find_fine_by_id(conn, fine_id)
+        .await?
+        .ok_or(Error::fine_not_found())?;
+    let user_id = fine.user_id;
+    let PaymentForm { amount, note } = post_form.into_inner();
+    let Some(amount) = parse_money(&amount).filter(|amount| *amount > 0) else {
+        flash_error(&session, "请输入正确的金额")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", format!("/users/{user_id}")))
+            .finish());
+    };
+    let fine_amount = fine.amount as i64;
+    // 单笔罚款减免不超过其尚未减免的部分，也不超过当前欠款。读取、检查与减免在同一事务中
+    let result = conn.transaction::<_, Result<(), i64>, sea_orm::DbErr>(|txn| {
+        Box::pin(async move {
+            let waived = Query::find_fine_waived(txn, fine_id).await?;
+            let balance = Query::find_user_balance(txn, user_id).await?;
+            let limit = (fine_amount - waived).min(balance);
+            if amount as i64 > limit {
+                return Ok(Err(limit));
+            }
+            Mutation::create_payment(
+                txn,
+                user_id,
+                Some(fine_id),
+                PaymentKind::Waiver,
+                amount,
+                staff_id,
+                note.trim().to_owned(),
+            )
+            .await?;
+            Ok(Ok(()))
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
+    match result {
+        Ok(()) => flash_success(&session, format!("已减免 {} 元", format_money(amount as i64)))?,
+        Err(limit) => {
+            flash_error(&session, format!("最多可减免 {} 元", format_money(limit.max(0))))?
+        }
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/users/{user_id}")))
+        .finish())
+}
@@ -0,0 +1,63 @@
+use book_manager_service::{
+    sea_orm::{self, TransactionTrait},
+    Holds, Mutation, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::HoldStatus;
+
+use crate::{
+    error::Error,
+    handlers::{is_admin, DeleteParams},
+    AppState, flash_error, flash_success,
+};
+
+pub async fn cancel_hold_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    hold_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+) -> Result<HttpResponse, Error> {
+    let hold_id = hold_id.into_inner();
+    let conn = &app_state.conn;
+    let user_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let hold = Query::find_hold_by_id(conn, hold_id)
+        .await?
+        .ok_or(Error::hold_not_found())?;
+    if hold.user_id != user_id && !is_admin(&session)? {
+        return Err(Error::unauthorized());
+    }
+    let source = params
+        .into_inner()
+        .source
+        .unwrap_or(format!("/books/{}", hold.book_id));
+    if !hold.status.is_active() {
+        flash_error(&session, "该预约已结束")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", source))
+            .finish());
+    }
+    conn.transaction::<_, (), sea_orm::DbErr>(|txn| {
+        Box::pin(async move {
+            Mutation::update_hold_status_by_id(txn, hold.id, HoldStatus::Cancelled).await?;
+            // 已保留的副本转给下一位预约读者
+            if let Some(item_id) = hold.item_id {
+                if let Some(item) = Query::find_book_item_by_id(txn, item_id).await? {
+                    Holds::release_book_item(txn, &item, user_id).await?;
+                }
+            }
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
+    flash_success(&session, "已取消预约")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", source))
+        .finish())
+}
@@ -0,0 +1,7 @@
+pub mod new;
+pub mod cancel;
+pub mod list;
+
+pub use new::*;
+pub use cancel::*;
+pub use list::*;
@@ -0,0 +1,55 @@
+use book_manager_service::{LoanState, Mutation, PolicyRejection, Query, ILL_ITEM_TYPE};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::ItemStatus;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+pub async fn place_hold_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    book_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let book_id = book_id.into_inner();
+    let conn = &app_state.conn;
+    let user_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    Query::find_book_by_id(conn, book_id)
+        .await?
+        .ok_or(Error::book_not_found())?;
+    let book_items = Query::find_book_items_by_book_id(conn, book_id).await?;
+    let today = chrono::Local::now().naive_local().date();
+    let block = Query::find_active_user_block(conn, user_id, today).await?;
+    let is_borrowing = Query::find_borrowed_books_by_user_id(conn, user_id, LoanState::Active)
+        .await?
+        .iter()
+        .any(|borrowed_book| borrowed_book.book_id == book_id);
+    // 只有全部副本都不在架时才需要预约
+    if let Some(block) = block {
+        flash_error(&session, PolicyRejection::PatronBlocked(block.end_date))?;
+    } else if Query::find_active_hold_by_user_id_and_book_id(conn, user_id, book_id)
+        .await?
+        .is_some()
+    {
+        flash_error(&session, "已预约过这本书")?;
+    } else if is_borrowing {
+        flash_error(&session, "正在借阅这本书，无需预约")?;
+    } else if book_items
+        .iter()
+        .any(|item| item.status == ItemStatus::Available && item.item_type != ILL_ITEM_TYPE)
+    {
+        flash_error(&session, "还有可借副本，无需预约")?;
+    } else if book_items
+        .iter()
+        .all(|item| item.status == ItemStatus::Withdrawn || item.item_type == ILL_ITEM_TYPE)
+    {
+        flash_error(&session, "该书没有可流通的副本，无法预约")?;
+    } else {
+        Mutation::create_hold(conn, user_id, book_id).await?;
+        flash_success(&session, "预约成功，到书后将通过站内信通知")?;
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/books/{book_id}")))
+        .finish())
+}
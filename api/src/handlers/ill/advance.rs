@@ -0,0 +1,183 @@
+use book_manager_service::{
+    sea_orm::{self, TransactionTrait},
+    Calendar, Mutation, Query, HOLD_PICKUP_DAYS, ILL_ITEM_TYPE,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::{book_items, books, IllStatus, Isbn, ItemCondition, ItemStatus};
+use serde::Deserialize;
+
+use crate::{
+    error::Error,
+    handlers::ill::{ill_placeholder_isbn, notify_ill_request, ILL_SHELF_LOCATION},
+    AppState, flash_error, flash_success,
+};
+
+#[derive(Deserialize)]
+pub struct AdvanceIllRequestForm {
+    /// 订购时填写的合作馆
+    pub partner: Option<String>,
+}
+
+/// 把申请推进到下一步：订购、到馆、借出、退还合作馆，每一步都通知读者。
+/// 到馆时建立临时副本并为读者保留，之后按正常流程借出与归还
+pub async fn advance_ill_request_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    request_id: web::Path<i32>,
+    post_form: web::Form<AdvanceIllRequestForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let request_id = request_id.into_inner();
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let request = Query::find_ill_request_by_id(conn, request_id)
+        .await?
+        .ok_or(Error::ill_request_not_found())?;
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", "/ill"))
+            .finish()
+    };
+    let Some(status) = request.status.next() else {
+        flash_error(&session, "该申请已结束")?;
+        return Ok(http_response());
+    };
+    let partner = post_form
+        .into_inner()
+        .partner
+        .map(|partner| partner.trim().to_owned())
+        .filter(|partner| !partner.is_empty())
+        .unwrap_or(request.partner.clone());
+    if status == IllStatus::Ordered && partner.is_empty() {
+        flash_error(&session, "请填写合作馆")?;
+        return Ok(http_response());
+    }
+    // 临时副本须按正常流程借出后才算借出，归还后才能退还合作馆
+    let item = match request.item_id {
+        Some(item_id) => Query::find_book_item_by_id(conn, item_id).await?,
+        None => None,
+    };
+    let item_status = item.as_ref().map(|item| item.status);
+    if status == IllStatus::Loaned && item_status != Some(ItemStatus::Borrowed) {
+        flash_error(&session, "请先在借还台将临时副本借给读者")?;
+        return Ok(http_response());
+    }
+    if status == IllStatus::Returned && item_status == Some(ItemStatus::Borrowed) {
+        flash_error(&session, "临时副本尚未归还")?;
+        return Ok(http_response());
+    }
+    if status == IllStatus::Returned && item_status == Some(ItemStatus::OnHoldShelf) {
+        flash_error(&session, "临时副本仍在预约架上，请先取消预约")?;
+        return Ok(http_response());
+    }
+
+    conn.transaction::<_, (), sea_orm::DbErr>(|txn| {
+        Box::pin(async move {
+            let today = chrono::Local::now().naive_local().date();
+            let (item_id, content) = match status {
+                IllStatus::Ordered => (
+                    request.item_id,
+                    format!("您申请的《{}》已向{partner}借阅，到馆后将通知您。", request.name),
+                ),
+                IllStatus::Received => {
+                    // 馆内已有同一 ISBN 的图书时副本挂在该书下，否则建立临时图书
+                    let existing = match Isbn::parse(&request.isbn) {
+                        Ok(isbn) => Query::find_books_by_isbn(txn, String::from(isbn))
+                            .await?
+                            .into_iter()
+                            .next(),
+                        Err(_) => None,
+                    };
+                    let book = match existing {
+                        Some(book) => book,
+                        None => {
+                            Mutation::create_book(
+                                txn,
+                                books::Model {
+                                    id: 0,
+                                    name: request.name.clone(),
+                                    author: request.author.clone(),
+                                    publisher: request.publisher.clone(),
+                                    publish_year: request.publish_year,
+                                    isbn: ill_placeholder_isbn(request.id),
+                                    call_number: String::new(),
+                                },
+                            )
+                            .await?
+                        }
+                    };
+                    let barcode = Query::next_book_item_barcode(txn, book.id).await?;
+                    let item = Mutation::create_book_item(
+                        txn,
+                        book.id,
+                        book_items::Model {
+                            id: 0,
+                            book_id: book.id,
+                            barcode,
+                            shelf_location: ILL_SHELF_LOCATION.to_owned(),
+                            condition: ItemCondition::Good,
+                            acquisition_date: today,
+                            status: ItemStatus::Available,
+                            item_type: ILL_ITEM_TYPE.to_owned(),
+                        },
+                    )
+                    .await?;
+                    // 临时副本以预约的形式为申请人保留
+                    let pickup_deadline = Calendar::load(txn)
+                        .await?
+                        .next_open_day(today + chrono::Duration::days(HOLD_PICKUP_DAYS));
+                    let hold = Mutation::create_hold(txn, request.user_id, book.id).await?;
+                    Mutation::set_hold_ready_by_id(txn, hold.id, item.id, pickup_deadline).await?;
+                    Mutation::update_book_item_status_by_id(txn, item.id, ItemStatus::OnHoldShelf)
+                        .await?;
+                    (
+                        Some(item.id),
+                        format!(
+                            "您申请的《{}》已到馆（条码 {}），请于 {pickup_deadline} 前到馆借阅。",
+                            request.name, item.barcode
+                        ),
+                    )
+                }
+                IllStatus::Loaned => (
+                    request.item_id,
+                    format!(
+                        "您申请的《{}》已借出，该书需退还{partner}，请按时归还。",
+                        request.name
+                    ),
+                ),
+                IllStatus::Returned => {
+                    // 副本下架，图书与借阅记录保留
+                    if let Some(item) = item {
+                        Mutation::update_book_item_status_by_id(txn, item.id, ItemStatus::Withdrawn)
+                            .await?;
+                    }
+                    (
+                        request.item_id,
+                        format!("您申请的《{}》已退还{partner}，本次馆际互借结束。", request.name),
+                    )
+                }
+                IllStatus::Requested | IllStatus::Cancelled => unreachable!(),
+            };
+            let request = Mutation::update_ill_request_by_id(
+                txn,
+                request.id,
+                status,
+                partner,
+                item_id,
+                staff_id,
+            )
+            .await?;
+            notify_ill_request(txn, staff_id, &request, content).await?;
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
+    flash_success(&session, "已更新馆际互借进度并通知读者")?;
+    Ok(http_response())
+}
@@ -0,0 +1,82 @@
+use book_manager_service::{
+    sea_orm::{self, TransactionTrait},
+    Mutation, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::IllStatus;
+
+use crate::{
+    error::Error,
+    handlers::{ill::notify_ill_request, is_admin, DeleteParams},
+    AppState, flash_error, flash_success,
+};
+
+/// 读者只能取消尚未订购的申请，管理员在到馆前都可以取消
+pub async fn cancel_ill_request_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    request_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+) -> Result<HttpResponse, Error> {
+    let request_id = request_id.into_inner();
+    let conn = &app_state.conn;
+    let user_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let request = Query::find_ill_request_by_id(conn, request_id)
+        .await?
+        .ok_or(Error::ill_request_not_found())?;
+    let is_admin = is_admin(&session)?;
+    if request.user_id != user_id && !is_admin {
+        return Err(Error::unauthorized());
+    }
+    let source = params
+        .into_inner()
+        .source
+        .unwrap_or(format!("/users/{}", request.user_id));
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", source.clone()))
+            .finish()
+    };
+    if !request.status.is_cancellable() {
+        flash_error(&session, "图书已到馆或申请已结束，无法取消")?;
+        return Ok(http_response());
+    }
+    if request.status != IllStatus::Requested && !is_admin {
+        flash_error(&session, "已向合作馆订购，请联系工作人员取消")?;
+        return Ok(http_response());
+    }
+    conn.transaction::<_, (), sea_orm::DbErr>(|txn| {
+        Box::pin(async move {
+            let request = Mutation::update_ill_request_by_id(
+                txn,
+                request.id,
+                IllStatus::Cancelled,
+                request.partner,
+                request.item_id,
+                user_id,
+            )
+            .await?;
+            // 读者自己取消时无需通知
+            if request.user_id != user_id {
+                notify_ill_request(
+                    txn,
+                    user_id,
+                    &request,
+                    format!("您申请的《{}》馆际互借已取消。", request.name),
+                )
+                .await?;
+            }
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
+    flash_success(&session, "已取消馆际互借申请")?;
+    Ok(http_response())
+}
@@ -0,0 +1,42 @@
+pub mod advance;
+pub mod cancel;
+pub mod list;
+pub mod new;
+
+pub use advance::*;
+pub use cancel::*;
+pub use list::*;
+pub use new::*;
+
+use book_manager_service::{
+    sea_orm::{ConnectionTrait, DbErr},
+    Mutation,
+};
+use entity::{ill_requests, EmailCategory};
+
+/// 馆际互借到馆后建立的临时副本使用的馆藏位置
+pub const ILL_SHELF_LOCATION: &str = "馆际互借";
+
+/// 临时图书不占用真实的 ISBN，以申请编号占位，退还合作馆后副本下架，图书与借阅记录保留
+pub fn ill_placeholder_isbn(request_id: i32) -> String {
+    format!("ILL-{request_id}")
+}
+
+/// 申请的每一步进度都以站内信通知读者
+pub async fn notify_ill_request<C: ConnectionTrait>(
+    db: &C,
+    sender_id: i32,
+    request: &ill_requests::Model,
+    content: String,
+) -> Result<(), DbErr> {
+    Mutation::create_email(
+        db,
+        EmailCategory::Regular,
+        sender_id,
+        request.user_id,
+        "馆际互借进度通知".to_owned(),
+        content,
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use book_manager_service::{Mutation, PolicyRejection, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use entity::{ill_requests, IllStatus};
+use serde::Deserialize;
+
+use crate::{
+    error::Error,
+    handlers::{basic_context, normalize_isbn},
+    AppState, flash_error, flash_success,
+};
+
+pub async fn new_ill_request_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "申请馆际互借");
+    let body = template.read().unwrap().render("ill/new.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewIllRequestForm {
+    name: String,
+    author: String,
+    publisher: String,
+    publish_year: NaiveDate,
+    isbn: String,
+    note: String,
+}
+
+pub async fn new_ill_request_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    post_form: web::Form<NewIllRequestForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let user_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let NewIllRequestForm {
+        name,
+        author,
+        publisher,
+        publish_year,
+        isbn,
+        note,
+    } = post_form.into_inner();
+    if name.trim().is_empty() {
+        flash_error(&session, "请填写书名")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", "/ill/new"))
+            .finish());
+    }
+    let now = chrono::Local::now().naive_local();
+    if let Some(block) = Query::find_active_user_block(conn, user_id, now.date()).await? {
+        flash_error(&session, PolicyRejection::PatronBlocked(block.end_date))?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", "/ill/new"))
+            .finish());
+    }
+    let request = ill_requests::Model {
+        id: 0,
+        user_id,
+        name: name.trim().to_owned(),
+        author: author.trim().to_owned(),
+        publisher: publisher.trim().to_owned(),
+        publish_year,
+        isbn: normalize_isbn(&isbn),
+        note: note.trim().to_owned(),
+        status: IllStatus::Requested,
+        partner: String::new(),
+        item_id: None,
+        created_at: now,
+        updated_at: now,
+        updated_by: None,
+    };
+    Mutation::create_ill_request(conn, user_id, request).await?;
+    flash_success(&session, "申请已提交，办理进度将通过站内信通知")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/users/{user_id}")))
+        .finish())
+}
@@ -0,0 +1,7 @@
+pub mod list;
+pub mod new;
+pub mod resolve;
+
+pub use list::*;
+pub use new::*;
+pub use resolve::*;
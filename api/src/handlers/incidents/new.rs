@@ -0,0 +1,173 @@
+use book_manager_service::{
+    sea_orm::{self, TransactionTrait},
+    Calendar, Fines, Mutation, PolicyRejection, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::{IncidentKind, LoanStatus};
+use serde::Deserialize;
+
+use crate::{
+    error::Error,
+    filters::parse_money,
+    handlers::{basic_context, DeleteParams},
+    AppState, flash_error, flash_success,
+};
+
+#[derive(Deserialize)]
+pub struct IncidentForm {
+    pub kind: IncidentKind,
+    /// 赔偿金额（元），留空则不收取
+    pub amount: String,
+    pub note: String,
+}
+
+pub async fn new_incident_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    borrow_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let borrowed_book = Query::find_borrowed_book_by_id(conn, borrow_id.into_inner())
+        .await?
+        .ok_or(Error::borrow_record_not_found())?;
+    let book = Query::find_book_by_id(conn, borrowed_book.book_id)
+        .await?
+        .ok_or(Error::book_not_found())?;
+    let item = Query::find_book_item_by_id(conn, borrowed_book.item_id)
+        .await?
+        .ok_or(Error::item_not_found())?;
+    let user = Query::find_user_by_id(conn, borrowed_book.user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "登记遗失或损坏");
+    ctx.insert("borrowed_book", &borrowed_book);
+    ctx.insert("book", &book);
+    ctx.insert("item", &item);
+    ctx.insert("user", &user);
+    ctx.insert("source", &params.into_inner().source);
+    let body = template.read().unwrap().render("incidents/new.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+/// 登记后结算逾期罚款，副本下架，填写金额时另计一笔赔偿。遗失与损坏时借阅结束，
+/// 声称已还时借阅保留到登记处理为止
+pub async fn new_incident_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    borrow_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+    post_form: web::Form<IncidentForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let borrow_id = borrow_id.into_inner();
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let borrowed_book = Query::find_borrowed_book_by_id(conn, borrow_id)
+        .await?
+        .ok_or(Error::borrow_record_not_found())?;
+    let source = params
+        .into_inner()
+        .source
+        .unwrap_or(format!("/users/{}", borrowed_book.user_id));
+    let http_response = |location: String| {
+        HttpResponse::Found()
+            .append_header(("Location", location))
+            .finish()
+    };
+    if borrowed_book.returned_at.is_some() {
+        flash_error(&session, "该书已归还")?;
+        return Ok(http_response(source));
+    }
+    if borrowed_book.status == LoanStatus::ClaimedReturned {
+        flash_error(&session, "该借阅已登记为声称已还，请在登记列表中处理")?;
+        return Ok(http_response(source));
+    }
+    let IncidentForm { kind, amount, note } = post_form.into_inner();
+    let amount = match amount.trim() {
+        "" => 0,
+        amount => match parse_money(amount) {
+            Some(amount) => amount,
+            _ => {
+                flash_error(&session, "请输入正确的金额")?;
+                let query = serde_urlencoded::to_string([("source", &source)]).unwrap_or_default();
+                return Ok(http_response(format!("/incidents/new/{borrow_id}?{query}")));
+            }
+        },
+    };
+    let note = note.trim().to_owned();
+    let result = conn.transaction::<_, Result<(), PolicyRejection>, sea_orm::DbErr>(|txn| {
+        Box::pin(async move {
+            let now = chrono::Local::now().naive_local();
+            // 遗失结束借阅，声称已还的借阅保留到登记处理为止，损坏的副本已交回。
+            // 借阅在检查之后已被其他请求归还或登记时放弃
+            let borrowed_book = match kind {
+                IncidentKind::Lost => {
+                    let (from, status) = (LoanStatus::OnLoan, LoanStatus::Lost);
+                    Mutation::close_borrowed_book(txn, borrow_id, from, status, staff_id, now)
+                        .await?
+                }
+                IncidentKind::ClaimedReturned => {
+                    Mutation::claim_borrowed_book_returned(txn, borrow_id).await?
+                }
+                IncidentKind::Damaged => {
+                    Mutation::return_borrowed_book(txn, borrow_id, staff_id, now).await?
+                }
+            };
+            let Some(borrowed_book) = borrowed_book else {
+                return Ok(Err(PolicyRejection::LoanChanged));
+            };
+            let today = now.date();
+            let calendar = Calendar::load(txn).await?;
+            Fines::assess_loan(txn, &borrowed_book, now, &calendar).await?;
+            let item = Query::find_book_item_by_id(txn, borrowed_book.item_id)
+                .await?
+                .ok_or(sea_orm::DbErr::Custom("Cannot find book item.".to_owned()))?;
+            Mutation::update_book_item_status_by_id(txn, item.id, kind.item_status()).await?;
+            let fine_id = if amount > 0 {
+                let book_name = Query::find_book_by_id(txn, item.book_id)
+                    .await?
+                    .map(|book| book.name)
+                    .unwrap_or_default();
+                let fine = Mutation::create_fine(
+                    txn,
+                    borrowed_book.user_id,
+                    None,
+                    amount,
+                    today,
+                    format!("赔偿：《{book_name}》（条码 {}）", item.barcode),
+                )
+                .await?;
+                Some(fine.id)
+            } else {
+                None
+            };
+            Mutation::create_item_incident(
+                txn,
+                item.id,
+                Some(borrow_id),
+                Some(borrowed_book.user_id),
+                kind,
+                fine_id,
+                note,
+                staff_id,
+            )
+            .await?;
+            Ok(Ok(()))
+        })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
+    match result {
+        Ok(()) => flash_success(&session, "登记成功")?,
+        Err(err) => flash_error(&session, err)?,
+    }
+    Ok(http_response(source))
+}
@@ -0,0 +1,119 @@
+use book_manager_service::{
+    sea_orm::{self, ConnectionTrait, DbErr, TransactionTrait},
+    Holds, Mutation, Query,
+};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::{item_incidents, IncidentStatus, ItemStatus, LoanStatus, PaymentKind};
+use serde::Deserialize;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+#[derive(Deserialize)]
+pub struct ResolveIncidentForm {
+    pub status: IncidentStatus,
+    pub note: String,
+}
+
+/// 找回（或修复）的副本重新流通并减免未缴的赔偿；已赔偿的收取剩余赔偿，核销的减免剩余赔偿，二者副本均注销
+pub async fn resolve_incident_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    incident_id: web::Path<i32>,
+    post_form: web::Form<ResolveIncidentForm>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let incident_id = incident_id.into_inner();
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let incident = Query::find_item_incident_by_id(conn, incident_id)
+        .await?
+        .ok_or(Error::incident_not_found())?;
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", "/incidents"))
+            .finish()
+    };
+    let ResolveIncidentForm { status, note } = post_form.into_inner();
+    if incident.status != IncidentStatus::Open {
+        flash_error(&session, "该登记已处理")?;
+        return Ok(http_response());
+    }
+    if status == IncidentStatus::Open {
+        flash_error(&session, "请选择处理结果")?;
+        return Ok(http_response());
+    }
+    let note = note.trim().to_owned();
+    conn.transaction::<_, (), DbErr>(|txn| {
+        Box::pin(async move { resolve_incident(txn, incident, status, note, staff_id).await })
+    })
+    .await
+    .map_err(|err| match err {
+        sea_orm::TransactionError::Connection(err) => Error::DbErr(err),
+        sea_orm::TransactionError::Transaction(err) => Error::DbErr(err),
+    })?;
+    flash_success(&session, "处理成功")?;
+    Ok(http_response())
+}
+
+/// 处理登记：副本重新流通或注销，结清赔偿。声称已还的借阅在此时结束，
+/// 找回视为归还，赔偿或核销视为遗失
+pub(crate) async fn resolve_incident<C: ConnectionTrait>(
+    db: &C,
+    incident: item_incidents::Model,
+    status: IncidentStatus,
+    note: String,
+    staff_id: i32,
+) -> Result<(), DbErr> {
+    let item = Query::find_book_item_by_id(db, incident.item_id)
+        .await?
+        .ok_or(DbErr::Custom("Cannot find book item.".to_owned()))?;
+    if status == IncidentStatus::Found {
+        Holds::release_book_item(db, &item, staff_id).await?;
+    } else {
+        Mutation::update_book_item_status_by_id(db, item.id, ItemStatus::Withdrawn).await?;
+    }
+    // 结清赔偿的剩余部分，不超过读者当前欠款
+    let fine = match incident.fine_id {
+        Some(fine_id) => Query::find_fine_by_id(db, fine_id).await?,
+        None => None,
+    };
+    if let Some(fine) = fine {
+        let remaining = Query::find_fine_remaining(db, &fine)
+            .await?
+            .min(Query::find_user_balance(db, fine.user_id).await?);
+        if remaining > 0 {
+            let kind = match status {
+                IncidentStatus::Paid => PaymentKind::Payment,
+                _ => PaymentKind::Waiver,
+            };
+            Mutation::create_payment(
+                db,
+                fine.user_id,
+                Some(fine.id),
+                kind,
+                i32::try_from(remaining)
+                    .map_err(|_| DbErr::Custom("Fine amount out of range.".to_owned()))?,
+                staff_id,
+                note.clone(),
+            )
+            .await?;
+        }
+    }
+    let loan = match incident.borrow_id {
+        Some(borrow_id) => Query::find_borrowed_book_by_id(db, borrow_id).await?,
+        None => None,
+    };
+    if let Some(loan) = loan.filter(|loan| loan.status == LoanStatus::ClaimedReturned) {
+        let loan_status = match status {
+            IncidentStatus::Found => LoanStatus::Returned,
+            _ => LoanStatus::Lost,
+        };
+        let now = chrono::Local::now().naive_local();
+        let from = LoanStatus::ClaimedReturned;
+        Mutation::close_borrowed_book(db, loan.id, from, loan_status, staff_id, now).await?;
+    }
+    Mutation::resolve_item_incident_by_id(db, incident.id, status, note, staff_id).await?;
+    Ok(())
+}
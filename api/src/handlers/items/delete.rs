@@ -0,0 +1,39 @@
+use book_manager_service::{LoanState, Mutation, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::ItemStatus;
+
+use crate::{error::Error, AppState, handlers::DeleteParams, flash_error, flash_success};
+
+pub async fn delete_book_item_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    item_id: web::Path<i32>,
+    params: web::Query<DeleteParams>,
+) -> Result<HttpResponse, Error> {
+    let item_id = item_id.into_inner();
+    let conn = &app_state.conn;
+    let item = Query::find_book_item_by_id(conn, item_id)
+        .await?
+        .ok_or(Error::item_not_found())?;
+    let source = params
+        .into_inner()
+        .source
+        .unwrap_or(format!("/books/{}", item.book_id));
+    if item.status == ItemStatus::Borrowed {
+        flash_error(&session, "副本已借出，无法删除")?;
+    } else if item.status == ItemStatus::OnHoldShelf {
+        flash_error(&session, "副本已为预约读者保留，无法删除")?;
+    } else if !Query::find_borrowed_books_by_item_id(conn, item_id, LoanState::All)
+        .await?
+        .is_empty()
+    {
+        flash_error(&session, "副本存在借阅记录，无法删除，可将其状态改为已注销")?;
+    } else {
+        Mutation::delete_book_item(conn, item_id).await?;
+        flash_success(&session, "删除成功")?;
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", source))
+        .finish())
+}
@@ -0,0 +1,87 @@
+use book_manager_service::{Mutation, Query, DEFAULT_CATEGORY};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::{book_items, ItemStatus};
+
+use crate::{error::Error, AppState, handlers::basic_context, flash_error, flash_success};
+
+pub async fn edit_book_item_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let id = id.into_inner();
+    let conn = &app_state.conn;
+    let item = Query::find_book_item_by_id(conn, id)
+        .await?
+        .ok_or(Error::item_not_found())?;
+    let book = Query::find_book_by_id(conn, item.book_id)
+        .await?
+        .ok_or(Error::book_not_found())?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "编辑副本");
+    ctx.insert("item", &item);
+    ctx.insert("book", &book);
+    let body = template.read().unwrap().render("items/edit.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+pub async fn edit_book_item_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+    post_form: web::Form<book_items::Model>,
+) -> Result<HttpResponse, Error> {
+    let post_form = post_form.into_inner();
+    let id = id.into_inner();
+    let conn = &app_state.conn;
+    let item = Query::find_book_item_by_id(conn, id)
+        .await?
+        .ok_or(Error::item_not_found())?;
+    let location = format!("/books/{}", item.book_id);
+    // 借出、预约保留与遗失损坏状态只能通过借阅、归还、预约与登记处理改变
+    let in_circulation = |status: ItemStatus| {
+        matches!(
+            status,
+            ItemStatus::Borrowed
+                | ItemStatus::OnHoldShelf
+                | ItemStatus::Lost
+                | ItemStatus::ClaimedReturned
+                | ItemStatus::Damaged
+        )
+    };
+    if (in_circulation(item.status) || in_circulation(post_form.status))
+        && item.status != post_form.status
+    {
+        flash_error(&session, "不能直接修改借出、预约保留或遗失损坏状态")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", format!("/items/edit/{id}")))
+            .finish());
+    }
+    let barcode = post_form.barcode.trim().to_owned();
+    if barcode != item.barcode && Query::find_book_item_by_barcode(conn, &barcode).await?.is_some() {
+        flash_error(&session, "条码已存在")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", format!("/items/edit/{id}")))
+            .finish());
+    }
+    let item_type = match post_form.item_type.trim() {
+        "" => DEFAULT_CATEGORY.to_owned(),
+        item_type => item_type.to_owned(),
+    };
+    Mutation::update_book_item_by_id(
+        conn,
+        id,
+        book_items::Model {
+            barcode,
+            item_type,
+            ..post_form
+        },
+    )
+    .await?;
+    flash_success(&session, "修改成功")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", location))
+        .finish())
+}
@@ -0,0 +1,7 @@
+pub mod new;
+pub mod edit;
+pub mod delete;
+
+pub use new::*;
+pub use edit::*;
+pub use delete::*;
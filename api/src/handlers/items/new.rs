@@ -0,0 +1,72 @@
+use book_manager_service::{Holds, Mutation, Query, DEFAULT_CATEGORY};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use entity::{book_items, ItemCondition, ItemStatus};
+use serde::Deserialize;
+
+use crate::{
+    error::Error, AppState, flash_error, flash_success,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct NewItemForm {
+    barcode: String,
+    shelf_location: String,
+    condition: ItemCondition,
+    acquisition_date: NaiveDate,
+    #[serde(default)]
+    item_type: String,
+}
+
+pub async fn new_book_item_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    book_id: web::Path<i32>,
+    post_form: web::Form<NewItemForm>,
+) -> Result<HttpResponse, Error> {
+    let NewItemForm {
+        barcode,
+        shelf_location,
+        condition,
+        acquisition_date,
+        item_type,
+    } = post_form.into_inner();
+    let book_id = book_id.into_inner();
+    let conn = &app_state.conn;
+    Query::find_book_by_id(conn, book_id)
+        .await?
+        .ok_or(Error::book_not_found())?;
+    // 未填写条码时自动生成
+    let barcode = match barcode.trim() {
+        "" => Query::next_book_item_barcode(conn, book_id).await?,
+        barcode => barcode.to_owned(),
+    };
+    if Query::find_book_item_by_barcode(conn, &barcode).await?.is_some() {
+        flash_error(&session, "条码已存在")?;
+    } else {
+        let item = book_items::Model {
+            id: 0,
+            book_id,
+            barcode,
+            shelf_location: shelf_location.trim().to_owned(),
+            condition,
+            acquisition_date,
+            status: ItemStatus::Available,
+            item_type: match item_type.trim() {
+                "" => DEFAULT_CATEGORY.to_owned(),
+                item_type => item_type.to_owned(),
+            },
+        };
+        let item = Mutation::create_book_item(conn, book_id, item).await?;
+        // 新副本先满足排队中的预约
+        let staff_id = session
+            .get::<i32>("user_id")?
+            .ok_or(Error::unauthorized())?;
+        Holds::release_book_item(conn, &item, staff_id).await?;
+        flash_success(&session, "添加成功")?;
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/books/{book_id}")))
+        .finish())
+}
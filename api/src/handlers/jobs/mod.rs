@@ -0,0 +1,5 @@
+pub mod list;
+pub mod run;
+
+pub use list::*;
+pub use run::*;
@@ -0,0 +1,16 @@
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, scheduler, AppState, flash_success};
+
+/// 立即运行全部定时任务，已发送过的提醒不会重复发送
+pub async fn run_jobs_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    scheduler::run_jobs(&app_state.conn, &app_state.scheduler_config, true).await;
+    flash_success(&session, "定时任务已运行")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/control/jobs"))
+        .finish())
+}
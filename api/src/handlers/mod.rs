@@ -1,17 +1,30 @@
 use actix_session::Session;
 use actix_web::{error, web, HttpRequest, HttpResponse};
-use entity::AccessPermission;
+use entity::{AccessPermission, Isbn};
 use serde::Deserialize;
 
 use crate::{error::Error, AppState};
 
+pub mod authors;
 pub mod books;
 pub mod borrow;
+pub mod calendar;
+pub mod categories;
+pub mod courses;
+pub mod desk;
 pub mod emails;
+pub mod fines;
+pub mod holds;
+pub mod ill;
+pub mod incidents;
 pub mod index;
+pub mod items;
+pub mod jobs;
 pub mod login;
 pub mod logout;
+pub mod policies;
 pub mod search;
+pub mod statistics;
 pub mod users;
 pub mod background;
 
@@ -85,3 +98,8 @@ fn is_admin(session: &Session) -> Result<bool, Error> {
         .unwrap_or(AccessPermission::Guest)
         .is_admin())
 }
+
+/// 按 ISBN 查找图书前先规范化，无效的 ISBN 原样使用
+fn normalize_isbn(isbn: &str) -> String {
+    Isbn::parse(isbn).map_or_else(|_| isbn.trim().to_owned(), String::from)
+}
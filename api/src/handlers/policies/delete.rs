@@ -0,0 +1,18 @@
+use book_manager_service::Mutation;
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, AppState, flash_success};
+
+pub async fn delete_policy_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    Mutation::delete_circulation_policy(conn, id.into_inner()).await?;
+    flash_success(&session, "删除成功")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/policies"))
+        .finish())
+}
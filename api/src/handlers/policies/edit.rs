@@ -0,0 +1,59 @@
+use book_manager_service::{Mutation, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::circulation_policies;
+
+use crate::{error::Error, AppState, handlers::basic_context, flash_error, flash_success};
+
+use super::normalize_policy_form;
+
+pub async fn edit_policy_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let policy = Query::find_circulation_policy_by_id(conn, id.into_inner())
+        .await?
+        .ok_or(Error::policy_not_found())?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "编辑流通规则");
+    ctx.insert("policy", &policy);
+    let body = template.read().unwrap().render("policies/edit.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
+
+pub async fn edit_policy_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    id: web::Path<i32>,
+    post_form: web::Form<circulation_policies::Model>,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let conn = &app_state.conn;
+    let policy = match normalize_policy_form(post_form.into_inner()) {
+        Ok(policy) => policy,
+        Err(err) => {
+            flash_error(&session, err)?;
+            return Ok(HttpResponse::Found()
+                .append_header(("Location", format!("/policies/edit/{id}")))
+                .finish());
+        }
+    };
+    let duplicate =
+        Query::find_circulation_policy_by_key(conn, &policy.patron_category, &policy.item_type)
+            .await?
+            .is_some_and(|existing| existing.id != id);
+    if duplicate {
+        flash_error(&session, "该读者类别与副本类型的规则已存在")?;
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", format!("/policies/edit/{id}")))
+            .finish());
+    }
+    Mutation::update_circulation_policy_by_id(conn, id, policy).await?;
+    flash_success(&session, "修改成功")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/policies"))
+        .finish())
+}
@@ -0,0 +1,20 @@
+use book_manager_service::{Query, ANY};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+
+use crate::{error::Error, AppState, handlers::basic_context};
+
+pub async fn list_policies_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let policies = Query::find_circulation_policies(conn).await?;
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "流通规则");
+    ctx.insert("policies", &policies);
+    ctx.insert("any", ANY);
+    let body = template.read().unwrap().render("policies/list.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
@@ -0,0 +1,38 @@
+pub mod list;
+pub mod new;
+pub mod edit;
+pub mod delete;
+
+pub use list::*;
+pub use new::*;
+pub use edit::*;
+pub use delete::*;
+
+use entity::circulation_policies;
+
+/// 整理表单中的类别与类型，检查数值是否合法
+fn normalize_policy_form(
+    mut form_data: circulation_policies::Model,
+) -> Result<circulation_policies::Model, &'static str> {
+    form_data.patron_category = form_data.patron_category.trim().to_owned();
+    form_data.item_type = form_data.item_type.trim().to_owned();
+    if form_data.patron_category.is_empty() || form_data.item_type.is_empty() {
+        return Err("读者类别与副本类型不能为空");
+    }
+    if [
+        form_data.max_loans,
+        form_data.loan_days,
+        form_data.max_renewals,
+        form_data.renewal_days,
+        form_data.grace_days,
+        form_data.fine_per_day,
+        form_data.max_fine,
+        form_data.max_balance,
+    ]
+    .iter()
+    .any(|&n| n < 0)
+    {
+        return Err("数值不能为负数");
+    }
+    Ok(form_data)
+}
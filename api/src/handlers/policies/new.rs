@@ -0,0 +1,33 @@
+use book_manager_service::{Mutation, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use entity::circulation_policies;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+use super::normalize_policy_form;
+
+pub async fn new_policy_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    post_form: web::Form<circulation_policies::Model>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    match normalize_policy_form(post_form.into_inner()) {
+        Ok(policy) => {
+            if Query::find_circulation_policy_by_key(conn, &policy.patron_category, &policy.item_type)
+                .await?
+                .is_some()
+            {
+                flash_error(&session, "该读者类别与副本类型的规则已存在")?;
+            } else {
+                Mutation::create_circulation_policy(conn, policy).await?;
+                flash_success(&session, "添加成功")?;
+            }
+        }
+        Err(err) => flash_error(&session, err)?,
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", "/policies"))
+        .finish())
+}
@@ -3,9 +3,18 @@ use actix_web::{web, HttpResponse};
 use book_manager_service::Query;
 use serde::Deserialize;
 
-use crate::{error::Error, AppState, flash_error};
+use crate::{
+    error::Error,
+    export::{export, ExportFormat, ExportParams},
+    marc,
+    AppState, flash_error,
+};
 
-use super::{basic_context, PageParams, DEFAULT_NUMBER_PER_PAGE};
+use super::{
+    basic_context,
+    categories::{category_filter_ids, category_options, CategoryFilter},
+    PageParams, DEFAULT_NUMBER_PER_PAGE,
+};
 
 const MIN_KEYWORD_LENGTH: usize = 3;
 
@@ -22,6 +31,7 @@ pub async fn search_handler(
     let template = &app_state.templates;
     let mut ctx = basic_context(&session)?;
     ctx.insert("title", "搜索");
+    ctx.insert("category_options", &category_options(&app_state.conn).await?);
     let body = template.read().unwrap().render("search.html.tera", &ctx)?;
     Ok(HttpResponse::Ok().content_type("text/html").body(body))
 }
@@ -31,6 +41,7 @@ pub async fn search_get_handler(
     session: Session,
     search_params: web::Query<SearchParams>,
     page_params: web::Query<PageParams>,
+    filter: web::Query<CategoryFilter>,
 ) -> Result<HttpResponse, Error> {
     let template = &app_state.templates;
     let conn = &app_state.conn;
@@ -54,6 +65,17 @@ pub async fn search_get_handler(
     }
     ctx.insert("no_title", &true);
     ctx.insert("result_type", &search_type);
+    let category = filter.category_id()?;
+    let category_ids = category_filter_ids(conn, category).await?;
+    let category_param = category.map(|id| id.to_string()).unwrap_or_default();
+    let export_query = serde_urlencoded::to_string([
+        ("keyword", keyword.as_str()),
+        ("search_type", search_type.as_str()),
+        ("category", category_param.as_str()),
+    ])
+    .map_err(Error::new)?;
+    ctx.insert("page_path", &format!("/search/s?{export_query}&"));
+    ctx.insert("export_path", &format!("/search/export?{export_query}&"));
     ctx.insert("page", &page);
     ctx.insert("number_per_page", &number_per_page);
     let body = match search_type.as_str() {
@@ -67,7 +89,14 @@ pub async fn search_get_handler(
         },
         "books" => {
             let (books, num_pages) =
-                Query::find_books_by_keyword_in_page(conn, &keyword, page, number_per_page).await?;
+                Query::find_books_by_keyword_in_page(
+                    conn,
+                    &keyword,
+                    category_ids.as_deref(),
+                    page,
+                    number_per_page,
+                )
+                .await?;
             ctx.insert("title", "图书搜索结果");
             ctx.insert("books", &books);
             ctx.insert("num_pages", &num_pages);
@@ -77,3 +106,58 @@ pub async fn search_get_handler(
     };
     Ok(HttpResponse::Ok().content_type("text/html").body(body))
 }
+
+/// 按相同的条件导出全部搜索结果
+pub async fn search_export_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    search_params: web::Query<SearchParams>,
+    export_params: web::Query<ExportParams>,
+    filter: web::Query<CategoryFilter>,
+) -> Result<HttpResponse, Error> {
+    let SearchParams {
+        keyword,
+        search_type,
+    } = search_params.into_inner();
+    if keyword.len() < MIN_KEYWORD_LENGTH {
+        flash_error(&session, "搜索内容过短")?;
+        return Ok(HttpResponse::Found().append_header(("Location", "/search")).finish());
+    }
+    let conn = app_state.conn.clone();
+    let category_ids = category_filter_ids(&conn, filter.category_id()?).await?;
+    match search_type.as_str() {
+        "users" => {
+            export(export_params.format, "users", move |page, number_per_page| {
+                let conn = conn.clone();
+                let keyword = keyword.clone();
+                async move {
+                    Query::find_users_by_keyword_in_page(&conn, &keyword, page, number_per_page)
+                        .await
+                }
+            })
+            .await
+        }
+        "books" => {
+            let fetch_page = move |page, number_per_page| {
+                let conn = conn.clone();
+                let keyword = keyword.clone();
+                let category_ids = category_ids.clone();
+                async move {
+                    Query::find_books_by_keyword_in_page(
+                        &conn,
+                        &keyword,
+                        category_ids.as_deref(),
+                        page,
+                        number_per_page,
+                    )
+                    .await
+                }
+            };
+            match export_params.format {
+                ExportFormat::Marcxml => marc::export_marcxml("books", fetch_page).await,
+                format => export(format, "books", fetch_page).await,
+            }
+        }
+        _ => Err(Error::bad_request("Invalid search type")),
+    }
+}
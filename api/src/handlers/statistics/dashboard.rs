@@ -0,0 +1,90 @@
+use book_manager_service::{Granularity, Statistics};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::{charts, error::Error, handlers::basic_context, AppState};
+
+/// 未指定起始日期时统计最近 30 天
+const DEFAULT_RANGE_DAYS: i64 = 30;
+/// 最多统计 10 年，更早的起始日期从结束日期往前截取
+const MAX_RANGE_DAYS: i64 = 3653;
+/// 按日统计最多 92 天，跨度更长时改为按月
+const MAX_DAY_PERIODS: i64 = 92;
+
+#[derive(Debug, Deserialize)]
+pub struct StatisticsParams {
+    start: Option<String>,
+    end: Option<String>,
+    /// `day` 或 `month`，默认按日
+    granularity: Option<String>,
+}
+
+pub async fn statistics_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    params: web::Query<StatisticsParams>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let conn = &app_state.conn;
+    let now = chrono::Local::now().naive_local();
+    let parse_date = |date: &Option<String>| {
+        date.as_deref()
+            .and_then(|date| date.trim().parse::<NaiveDate>().ok())
+    };
+    let end = parse_date(&params.end).unwrap_or(now.date());
+    let start = parse_date(&params.start)
+        .unwrap_or(end - chrono::Duration::days(DEFAULT_RANGE_DAYS - 1));
+    let (start, end) = if start <= end { (start, end) } else { (end, start) };
+    let start = start.max(end - chrono::Duration::days(MAX_RANGE_DAYS - 1));
+    let granularity = match params.granularity.as_deref() {
+        Some("month") => Granularity::Month,
+        _ if (end - start).num_days() >= MAX_DAY_PERIODS => Granularity::Month,
+        _ => Granularity::Day,
+    };
+
+    let statistics = Statistics::circulation(conn, start, end, granularity, now).await?;
+    let loans_per_period: Vec<_> = statistics
+        .periods
+        .iter()
+        .map(|period| (period.period.clone(), period.loans))
+        .collect();
+    let registrations_per_period: Vec<_> = statistics
+        .periods
+        .iter()
+        .map(|period| (period.period.clone(), period.registrations))
+        .collect();
+    let top_books: Vec<_> = statistics
+        .top_books
+        .iter()
+        .map(|book| (book.name.clone(), book.loans))
+        .collect();
+    let top_authors: Vec<_> = statistics
+        .top_authors
+        .iter()
+        .map(|author| (author.author.clone(), author.loans))
+        .collect();
+
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "流通统计");
+    ctx.insert("start", &start);
+    ctx.insert("end", &end);
+    ctx.insert("granularity", if granularity == Granularity::Month { "month" } else { "day" });
+    ctx.insert("total_loans", &statistics.total_loans);
+    ctx.insert("overdue_loans", &statistics.overdue_loans);
+    ctx.insert("overdue_rate", &format!("{:.1}", statistics.overdue_rate));
+    ctx.insert("active_patrons", &statistics.active_patrons);
+    ctx.insert("new_users", &statistics.new_users);
+    ctx.insert("top_books", &statistics.top_books);
+    ctx.insert("top_authors", &statistics.top_authors);
+    ctx.insert("loans_chart", &charts::bar_chart(&loans_per_period, "#0d6efd"));
+    ctx.insert(
+        "registrations_chart",
+        &charts::bar_chart(&registrations_per_period, "#198754"),
+    );
+    ctx.insert("top_books_chart", &charts::ranking_chart(&top_books, "#0d6efd"));
+    ctx.insert("top_authors_chart", &charts::ranking_chart(&top_authors, "#6f42c1"));
+    let body = template.read().unwrap().render("statistics/dashboard.html.tera", &ctx)?;
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
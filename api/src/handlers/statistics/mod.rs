@@ -0,0 +1,3 @@
+pub mod dashboard;
+
+pub use dashboard::*;
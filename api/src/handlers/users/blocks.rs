@@ -0,0 +1,93 @@
+use book_manager_service::{Mutation, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use chrono::NaiveDate;
+use entity::BlockReason;
+use serde::Deserialize;
+
+use crate::{error::Error, AppState, flash_error, flash_success};
+
+#[derive(Debug, Deserialize)]
+pub struct UserBlockForm {
+    reason: BlockReason,
+    note: String,
+    start_date: NaiveDate,
+    /// 留空表示直到手动解除
+    end_date: Option<String>,
+}
+
+pub async fn new_user_block_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+    post_form: web::Form<UserBlockForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = user_id.into_inner();
+    let conn = &app_state.conn;
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let http_response = || {
+        HttpResponse::Found()
+            .append_header(("Location", format!("/users/{user_id}")))
+            .finish()
+    };
+    let UserBlockForm {
+        reason,
+        note,
+        start_date,
+        end_date,
+    } = post_form.into_inner();
+    let end_date = match end_date.as_deref().map(str::trim).filter(|date| !date.is_empty()) {
+        Some(date) => match date.parse::<NaiveDate>() {
+            Ok(date) => Some(date),
+            Err(_) => {
+                flash_error(&session, "结束日期格式错误")?;
+                return Ok(http_response());
+            }
+        },
+        None => None,
+    };
+    if end_date.is_some_and(|end_date| end_date < start_date) {
+        flash_error(&session, "结束日期不能早于开始日期")?;
+        return Ok(http_response());
+    }
+    Mutation::create_user_block(
+        conn,
+        user_id,
+        reason,
+        note.trim().to_owned(),
+        start_date,
+        end_date,
+        staff_id,
+    )
+    .await?;
+    flash_success(&session, "已停借该读者")?;
+    Ok(http_response())
+}
+
+pub async fn lift_user_block_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    block_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let staff_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    let block = Query::find_user_block_by_id(conn, block_id.into_inner())
+        .await?
+        .ok_or(Error::user_block_not_found())?;
+    if block.lifted_at.is_some() {
+        flash_error(&session, "该停借已解除")?;
+    } else {
+        Mutation::lift_user_block_by_id(conn, block.id, staff_id).await?;
+        flash_success(&session, "已解除停借")?;
+    }
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/users/{}", block.user_id)))
+        .finish())
+}
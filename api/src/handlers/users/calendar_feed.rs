@@ -0,0 +1,72 @@
+use book_manager_service::{LoanState, Mutation, Query};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use rand::{distributions::Alphanumeric, Rng};
+
+use crate::{error::Error, handlers::is_admin, ical, AppState, flash_success};
+
+const CALENDAR_TOKEN_LENGTH: usize = 32;
+
+/// 订阅地址不需要登录，只凭令牌识别读者
+pub async fn calendar_feed_handler(
+    app_state: web::Data<AppState>,
+    token: web::Path<String>,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let user = Query::find_user_by_calendar_token(conn, token.into_inner())
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let loans =
+        Query::find_borrowed_books_detail_by_user_id(conn, user.id, LoanState::Active).await?;
+    let now = chrono::Local::now().naive_local();
+    Ok(HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(ical::loans_calendar(&user.nickname, &loans, now)))
+}
+
+/// 生成新的订阅令牌，旧地址随之失效
+pub async fn reset_calendar_token_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let token = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(CALENDAR_TOKEN_LENGTH)
+        .map(char::from)
+        .collect();
+    let user_id = user_id.into_inner();
+    update_calendar_token(app_state, session, user_id, Some(token), "已生成新的订阅地址").await
+}
+
+pub async fn revoke_calendar_token_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    update_calendar_token(app_state, session, user_id.into_inner(), None, "已撤销订阅地址").await
+}
+
+async fn update_calendar_token(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: i32,
+    token: Option<String>,
+    message: &str,
+) -> Result<HttpResponse, Error> {
+    let conn = &app_state.conn;
+    let cache_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    if cache_id != user_id && !is_admin(&session)? {
+        return Err(Error::unauthorized());
+    }
+    Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    Mutation::update_user_calendar_token_by_id(conn, user_id, token).await?;
+    flash_success(&session, message)?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/users/{user_id}")))
+        .finish())
+}
@@ -0,0 +1,38 @@
+use book_manager_service::{library_card_number, Query};
+use actix_session::Session;
+use actix_web::{
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web, HttpResponse,
+};
+
+use crate::{error::Error, handlers::is_admin, pdf, AppState};
+
+pub async fn library_card_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let user_id = user_id.into_inner();
+    let cache_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::unauthorized())?;
+    if cache_id != user_id && !is_admin(&session)? {
+        return Err(Error::unauthorized());
+    }
+    let user = Query::find_user_by_id(&app_state.conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let body = pdf::library_card(&user)?;
+    // 用户名可能含有非 ASCII 字符，文件名使用读者证条码
+    let disposition = ContentDisposition {
+        disposition: DispositionType::Inline,
+        parameters: vec![DispositionParam::Filename(format!(
+            "card-{}.pdf",
+            library_card_number(user.id)
+        ))],
+    };
+    Ok(HttpResponse::Ok()
+        .content_type("application/pdf")
+        .insert_header(disposition)
+        .body(body))
+}
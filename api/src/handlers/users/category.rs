@@ -0,0 +1,33 @@
+use book_manager_service::{Mutation, Query, DEFAULT_CATEGORY};
+use actix_session::Session;
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+
+use crate::{error::Error, AppState, flash_success};
+
+#[derive(Debug, Deserialize)]
+pub struct UserCategoryForm {
+    patron_category: String,
+}
+
+pub async fn update_user_category_post_handler(
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+    post_form: web::Form<UserCategoryForm>,
+) -> Result<HttpResponse, Error> {
+    let user_id = user_id.into_inner();
+    let conn = &app_state.conn;
+    Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let patron_category = match post_form.patron_category.trim() {
+        "" => DEFAULT_CATEGORY.to_owned(),
+        patron_category => patron_category.to_owned(),
+    };
+    Mutation::update_user_patron_category_by_id(conn, user_id, patron_category).await?;
+    flash_success(&session, "修改成功")?;
+    Ok(HttpResponse::Found()
+        .append_header(("Location", format!("/users/{user_id}")))
+        .finish())
+}
@@ -1,36 +1,67 @@
-use book_manager_service::Query;
-use actix_session::Session;
-use actix_web::{web, HttpResponse};
-
-use crate::{
-    error::Error,
-    handlers::{basic_context, is_admin},
-    AppState,
-};
-
-pub async fn user_detail_handler(
-    app_state: web::Data<AppState>,
-    session: Session,
-    user_id: web::Path<i32>,
-) -> Result<HttpResponse, Error> {
-    let template = &app_state.templates;
-    let user_id = user_id.into_inner();
-    let conn = &app_state.conn;
-    let cache_id = session
-        .get::<i32>("user_id")?
-        .ok_or(Error::user_not_found())?;
-    let is_admin = is_admin(&session)?;
-    if cache_id != user_id && !is_admin {
-        return Err(Error::unauthorized());
-    }
-    let user = Query::find_user_by_id(conn, user_id)
-        .await?
-        .ok_or(Error::user_not_found())?;
-    let borrowed_books_info = Query::find_borrowed_books_detail_by_user_id(conn, user_id).await?;
-    let mut ctx = basic_context(&session)?;
-    ctx.insert("title", "用户详情");
-    ctx.insert("user", &user);
-    ctx.insert("borrowed_books_info", &borrowed_books_info);
-    let body = template.read().unwrap().render("users/detail.html.tera", &ctx).unwrap();
-    Ok(HttpResponse::Ok().content_type("text/html").body(body))
-}
+use book_manager_service::{LoanState, Query};
+use actix_session::Session;
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use crate::{
+    error::Error,
+    handlers::{basic_context, is_admin},
+    AppState,
+};
+
+pub async fn user_detail_handler(
+    req: HttpRequest,
+    app_state: web::Data<AppState>,
+    session: Session,
+    user_id: web::Path<i32>,
+) -> Result<HttpResponse, Error> {
+    let template = &app_state.templates;
+    let user_id = user_id.into_inner();
+    let conn = &app_state.conn;
+    let cache_id = session
+        .get::<i32>("user_id")?
+        .ok_or(Error::user_not_found())?;
+    let is_admin = is_admin(&session)?;
+    if cache_id != user_id && !is_admin {
+        return Err(Error::unauthorized());
+    }
+    let user = Query::find_user_by_id(conn, user_id)
+        .await?
+        .ok_or(Error::user_not_found())?;
+    let borrowed_books_info =
+        Query::find_borrowed_books_detail_by_user_id(conn, user_id, LoanState::Active).await?;
+    let holds = Query::find_holds_detail_by_user_id(conn, user_id).await?;
+    let today = chrono::Local::now().naive_local().date();
+    let fines = Query::find_fines_detail_by_user_id(conn, user_id).await?;
+    let payments = Query::find_payments_by_user_id(conn, user_id).await?;
+    let balance = Query::find_user_balance(conn, user_id).await?;
+    let incidents = Query::find_item_incidents_detail_by_user_id(conn, user_id).await?;
+    let ill_requests = Query::find_ill_requests_detail_by_user_id(conn, user_id).await?;
+    let blocks = Query::find_user_blocks_by_user_id(conn, user_id).await?;
+    let active_block = blocks.iter().find(|block| block.is_active(today));
+    let mut ctx = basic_context(&session)?;
+    ctx.insert("title", "用户详情");
+    ctx.insert("user", &user);
+    ctx.insert("borrowed_books_info", &borrowed_books_info);
+    ctx.insert("holds", &holds);
+    ctx.insert("fines", &fines);
+    ctx.insert("payments", &payments);
+    ctx.insert("balance", &balance);
+    ctx.insert("incidents", &incidents);
+    ctx.insert("ill_requests", &ill_requests);
+    if let Some(block) = active_block {
+        ctx.insert("active_block", block);
+    }
+    ctx.insert("blocks", &blocks);
+    ctx.insert("today", &today);
+    if let Some(token) = &user.calendar_token {
+        let connection_info = req.connection_info();
+        let feed_url = format!(
+            "{}://{}/feeds/{token}.ics",
+            connection_info.scheme(),
+            connection_info.host()
+        );
+        ctx.insert("calendar_feed_url", &feed_url);
+    }
+    let body = template.read().unwrap().render("users/detail.html.tera", &ctx).unwrap();
+    Ok(HttpResponse::Ok().content_type("text/html").body(body))
+}
@@ -0,0 +1,20 @@
+use actix_web::{web, HttpResponse};
+use book_manager_service::Query;
+
+use crate::{
+    error::Error,
+    export::{export, ExportParams},
+    AppState,
+};
+
+pub async fn export_users_handler(
+    app_state: web::Data<AppState>,
+    params: web::Query<ExportParams>,
+) -> Result<HttpResponse, Error> {
+    let conn = app_state.conn.clone();
+    export(params.format, "users", move |page, number_per_page| {
+        let conn = conn.clone();
+        async move { Query::find_users_in_page(&conn, page, number_per_page).await }
+    })
+    .await
+}
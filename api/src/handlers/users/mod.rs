@@ -1,9 +1,19 @@
-pub mod detail;
-pub mod list;
-pub mod new;
-pub mod delete;
-
-pub use detail::*;
-pub use list::*;
-pub use new::*;
-pub use delete::*;
\ No newline at end of file
+pub mod detail;
+pub mod list;
+pub mod new;
+pub mod delete;
+pub mod category;
+pub mod blocks;
+pub mod export;
+pub mod calendar_feed;
+pub mod card;
+
+pub use detail::*;
+pub use list::*;
+pub use new::*;
+pub use delete::*;
+pub use category::*;
+pub use blocks::*;
+pub use export::*;
+pub use calendar_feed::*;
+pub use card::*;
\ No newline at end of file
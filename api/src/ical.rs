@@ -0,0 +1,103 @@
+//! 读者借阅到期日的 iCalendar 订阅源（RFC 5545），由日历应用定期拉取。
+
+use chrono::{Duration, NaiveDateTime};
+use entity::BorrowedBooksResultForBook;
+
+/// 单行最多 75 字节，超出部分折到下一行
+const MAX_LINE_OCTETS: usize = 75;
+
+/// 每笔在借记录生成一个全天事件，前一天和当天上午各提醒一次。
+/// UID 只与借阅记录有关，续借后日历应用会更新原事件而不是新建
+pub fn loans_calendar(
+    name: &str,
+    loans: &[BorrowedBooksResultForBook],
+    now: NaiveDateTime,
+) -> String {
+    let mut calendar = String::new();
+    let dtstamp = now
+        .and_local_timezone(chrono::Local)
+        .single()
+        .map(|now| now.naive_utc())
+        .unwrap_or(now)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string();
+    push_line(&mut calendar, "BEGIN:VCALENDAR");
+    push_line(&mut calendar, "VERSION:2.0");
+    push_line(&mut calendar, "PRODID:-//Book Manager//Loans//ZH");
+    push_line(&mut calendar, "CALSCALE:GREGORIAN");
+    push_line(&mut calendar, "METHOD:PUBLISH");
+    push_line(&mut calendar, &format!("X-WR-CALNAME:{}", escape(&format!("{name} 的借阅"))));
+    for loan in loans {
+        let mut description = format!("条码 {}，ISBN {}", loan.barcode, loan.isbn);
+        if let Some(due_at) = loan.due_at {
+            description.push_str(&format!("，请于 {} 前归还", due_at.format("%H:%M")));
+        }
+        if loan.renewals > 0 {
+            description.push_str(&format!("，已续借 {} 次", loan.renewals));
+        }
+        push_line(&mut calendar, "BEGIN:VEVENT");
+        push_line(&mut calendar, &format!("UID:loan-{}@book-manager", loan.borrow_id));
+        push_line(&mut calendar, &format!("DTSTAMP:{dtstamp}"));
+        push_line(
+            &mut calendar,
+            &format!("DTSTART;VALUE=DATE:{}", loan.return_date.format("%Y%m%d")),
+        );
+        push_line(
+            &mut calendar,
+            &format!(
+                "DTEND;VALUE=DATE:{}",
+                (loan.return_date + Duration::days(1)).format("%Y%m%d")
+            ),
+        );
+        push_line(
+            &mut calendar,
+            &format!("SUMMARY:{}", escape(&format!("归还《{}》", loan.book_name))),
+        );
+        push_line(&mut calendar, &format!("DESCRIPTION:{}", escape(&description)));
+        push_line(&mut calendar, "TRANSP:TRANSPARENT");
+        // 全天事件从当天零点起算：前一天 9 点、当天 9 点
+        for trigger in ["-PT15H", "PT9H"] {
+            push_line(&mut calendar, "BEGIN:VALARM");
+            push_line(&mut calendar, "ACTION:DISPLAY");
+            push_line(&mut calendar, &format!("TRIGGER:{trigger}"));
+            push_line(
+                &mut calendar,
+                &format!("DESCRIPTION:{}", escape(&format!("《{}》即将到期", loan.book_name))),
+            );
+            push_line(&mut calendar, "END:VALARM");
+        }
+        push_line(&mut calendar, "END:VEVENT");
+    }
+    push_line(&mut calendar, "END:VCALENDAR");
+    calendar
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 按字节折行，不拆开多字节字符，续行以一个空格开头
+fn push_line(calendar: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            calendar.push_str("\r\n ");
+            width = 1;
+        }
+        calendar.push(c);
+        width += c.len_utf8();
+    }
+    calendar.push_str("\r\n");
+}
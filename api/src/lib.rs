@@ -1,8 +1,16 @@
+pub mod barcode;
+pub mod charts;
 pub mod error;
+pub mod export;
 pub mod filters;
 pub mod handlers;
+pub mod marc;
+pub mod ical;
+pub mod pdf;
 pub mod permission;
 pub mod routes;
+pub mod scheduler;
+pub mod sip2;
 
 use actix_session::{storage::CookieSessionStore, Session, SessionMiddleware};
 use actix_web::{cookie::Key, middleware, web, App, HttpServer};
@@ -16,12 +24,15 @@ use std::{env, fs::File, io::BufReader};
 use tera::Tera;
 use std::sync::RwLock;
 
-use crate::routes::general_routes;
+use crate::{routes::general_routes, scheduler::SchedulerConfig, sip2::Sip2Config};
 
 #[derive(Debug)]
 pub struct AppState {
     templates: RwLock<Tera>,
     conn: DatabaseConnection,
+    scheduler_config: SchedulerConfig,
+    /// 是否允许读者自助借阅
+    self_checkout: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -86,14 +97,28 @@ async fn start() -> std::io::Result<()> {
 
     let mut templates = Tera::new(&template_dir).unwrap();
     templates.register_filter("is_overdue", filters::is_overdue);
+    templates.register_filter("money", filters::money);
     // templates.register_filter("format_date", filters::format_date);
 
+    let self_checkout = env::var("SELF_CHECKOUT")
+        .map(|val| matches!(val.as_str(), "1" | "true"))
+        .unwrap_or(false);
+
+    // start background jobs
+    let scheduler_config = SchedulerConfig::from_env();
+    scheduler::spawn(conn.clone(), scheduler_config.clone());
+    if let Some(sip2_config) = Sip2Config::from_env() {
+        sip2::spawn(conn.clone(), sip2_config);
+    }
+
     // create server and try to serve over socket if possible
     let mut listenfd = ListenFd::from_env();
     let mut server = HttpServer::new(move || {
         let state = AppState {
             templates: RwLock::new(templates.clone()),
             conn: conn.clone(),
+            scheduler_config: scheduler_config.clone(),
+            self_checkout,
         };
 
         App::new()
@@ -134,13 +159,11 @@ fn load_ssl_config() -> rustls::ServerConfig {
     let key_der = PrivateKey(key);
 
     // 配置 SSL
-    let config = rustls::ServerConfig::builder()
+    rustls::ServerConfig::builder()
         .with_safe_defaults()
         .with_no_client_auth()
         .with_single_cert(cert_chain, key_der)
-        .unwrap();
-
-    config
+        .unwrap()
 }
 
 pub fn main() {
@@ -0,0 +1,479 @@
+//! MARC21 书目记录：读取 ISO 2709 与 MARCXML 并映射为图书，或把图书导出为 MARCXML。
+//! 只处理图书表中有的字段：245 题名、100/700 著者、260/264 出版者与出版日期、020 ISBN。
+
+use std::future::Future;
+
+use actix_web::{
+    http::header::{ContentDisposition, DispositionParam, DispositionType},
+    web::Bytes,
+    HttpResponse,
+};
+use book_manager_service::sea_orm::DbErr;
+use chrono::{Datelike, NaiveDate};
+use entity::{
+    books, format_contributors, parse_contributors, BooksResult, ContributorRole, Isbn,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+
+use crate::{error::Error, export::EXPORT_PAGE_SIZE};
+
+const RECORD_TERMINATOR: u8 = 0x1D;
+const FIELD_TERMINATOR: u8 = 0x1E;
+const SUBFIELD_DELIMITER: u8 = 0x1F;
+const LEADER_LENGTH: usize = 24;
+const MARCXML_NAMESPACE: &str = "http://www.loc.gov/MARC21/slim";
+
+#[derive(Debug, Clone)]
+pub enum Field {
+    /// 001–009 控制字段，没有指示符和子字段
+    Control { tag: String, value: String },
+    Data {
+        tag: String,
+        indicators: [char; 2],
+        subfields: Vec<(char, String)>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub leader: String,
+    pub fields: Vec<Field>,
+}
+
+impl Record {
+    fn control(&self, tag: &str) -> Option<&str> {
+        self.fields.iter().find_map(|field| match field {
+            Field::Control { tag: field_tag, value } if field_tag == tag => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// 第一个带有该子字段的 `tag` 字段中的子字段，`indicator2` 用于区分 264 的不同职能
+    fn subfield(&self, tag: &str, indicator2: Option<char>, code: char) -> Option<&str> {
+        self.fields.iter().find_map(|field| match field {
+            Field::Data {
+                tag: field_tag,
+                indicators,
+                subfields,
+            } if field_tag == tag && indicator2.is_none_or(|ind| indicators[1] == ind) => {
+                subfields
+                    .iter()
+                    .find(|(subfield_code, _)| *subfield_code == code)
+                    .map(|(_, value)| value.as_str())
+            }
+            _ => None,
+        })
+    }
+
+    /// 245 $a 题名，无法导入的记录也用它在报告中标明是哪一条
+    pub fn title(&self) -> Option<String> {
+        self.subfield("245", None, 'a')
+            .map(clean)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// 1XX 主要款目与 7XX 附加款目中的著者，职责取自 $4 关系代码或 $e 关系词，缺省为著者
+    fn contributors(&self) -> Vec<(String, ContributorRole)> {
+        let mut contributors = Vec::new();
+        for field in &self.fields {
+            let Field::Data { tag, subfields, .. } = field else {
+                continue;
+            };
+            if !matches!(tag.as_str(), "100" | "110" | "111" | "700" | "710" | "711") {
+                continue;
+            }
+            let Some(name) = subfields
+                .iter()
+                .find(|(code, _)| *code == 'a')
+                .map(|(_, value)| clean(value))
+                .filter(|name| !name.is_empty())
+            else {
+                continue;
+            };
+            let role = subfields
+                .iter()
+                .filter(|(code, _)| matches!(code, '4' | 'e'))
+                .find_map(|(_, value)| relator_role(value))
+                .unwrap_or(ContributorRole::Author);
+            if !contributors.contains(&(name.clone(), role)) {
+                contributors.push((name, role));
+            }
+        }
+        contributors
+    }
+
+    /// 映射为图书，缺少题名、ISBN 或出版日期的记录无法导入
+    pub fn to_book(&self) -> Result<books::Model, String> {
+        let name = self.title().ok_or("缺少 245 $a 题名")?;
+        let name = match self.subfield("245", None, 'b').map(clean) {
+            Some(remainder) if !remainder.is_empty() => format!("{name}: {remainder}"),
+            _ => name,
+        };
+        let author = format_contributors(&self.contributors());
+        // 264 第二指示符为 1 表示出版，旧记录使用 260
+        let publication = |code| {
+            self.subfield("264", Some('1'), code)
+                .or_else(|| self.subfield("260", None, code))
+        };
+        let publisher = publication('b').map(clean).unwrap_or_default();
+        let publish_year = publication('c')
+            .and_then(first_year)
+            .or_else(|| self.control("008").and_then(|value| value.get(7..11)?.parse().ok()))
+            .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+            .ok_or("缺少出版日期（260/264 $c 或 008）")?;
+        // 020 $a 可能带有装帧等限定说明，例如 "9787536692930 (pbk.)"
+        let isbn = self
+            .subfield("020", None, 'a')
+            .and_then(|isbn| isbn.split_whitespace().next())
+            .ok_or("缺少 020 $a ISBN")?;
+        let isbn = Isbn::parse(isbn).map_err(|err| err.to_string())?.into();
+        // 本馆索书号，$a 为分类号，$b 为书次号
+        let call_number = match (self.subfield("090", None, 'a'), self.subfield("090", None, 'b')) {
+            (Some(class), Some(item)) => format!("{}/{}", class.trim(), item.trim()),
+            (Some(class), None) => class.trim().to_owned(),
+            _ => String::new(),
+        };
+        Ok(books::Model {
+            id: 0,
+            name,
+            author,
+            publisher,
+            publish_year,
+            isbn,
+            call_number,
+        })
+    }
+
+    /// 由图书生成的最简书目记录，头标第 9 位为 a 表示 UTF-8
+    pub fn from_book(book: &BooksResult) -> Self {
+        let today = chrono::Local::now().naive_local().date();
+        let year = book.publish_year.year();
+        let fixed = format!("{}s{year:04}    xx {:17}und d", today.format("%y%m%d"), "");
+        let mut fields = vec![
+            Field::Control {
+                tag: "001".to_owned(),
+                value: book.id.to_string(),
+            },
+            Field::Control {
+                tag: "008".to_owned(),
+                value: fixed,
+            },
+            Field::Data {
+                tag: "020".to_owned(),
+                indicators: [' ', ' '],
+                subfields: vec![('a', book.isbn.clone())],
+            },
+        ];
+        // 第一位著者作为主要款目，其余著者与译者等作为附加款目
+        let mut contributors = parse_contributors(&book.author);
+        let main_entry = contributors
+            .iter()
+            .position(|(_, role)| *role == ContributorRole::Author)
+            .map(|index| contributors.remove(index));
+        if let Some((name, _)) = &main_entry {
+            fields.push(Field::Data {
+                tag: "100".to_owned(),
+                indicators: ['1', ' '],
+                subfields: vec![('a', name.clone())],
+            });
+        }
+        if let Some((class, item)) = book.call_number.split_once('/') {
+            fields.push(Field::Data {
+                tag: "090".to_owned(),
+                indicators: [' ', ' '],
+                subfields: vec![('a', class.to_owned()), ('b', item.to_owned())],
+            });
+        } else if !book.call_number.is_empty() {
+            fields.push(Field::Data {
+                tag: "090".to_owned(),
+                indicators: [' ', ' '],
+                subfields: vec![('a', book.call_number.clone())],
+            });
+        }
+        fields.push(Field::Data {
+            tag: "245".to_owned(),
+            indicators: [if main_entry.is_some() { '1' } else { '0' }, '0'],
+            subfields: vec![('a', book.name.clone())],
+        });
+        fields.push(Field::Data {
+            tag: "264".to_owned(),
+            indicators: [' ', '1'],
+            subfields: vec![('b', book.publisher.clone()), ('c', year.to_string())],
+        });
+        for (name, role) in contributors {
+            let (term, code) = relator(role);
+            fields.push(Field::Data {
+                tag: "700".to_owned(),
+                indicators: ['1', ' '],
+                subfields: vec![('a', name), ('e', term.to_owned()), ('4', code.to_owned())],
+            });
+        }
+        Self {
+            leader: "00000nam a2200000 i 4500".to_owned(),
+            fields,
+        }
+    }
+}
+
+/// MARC 关系词与关系代码
+fn relator(role: ContributorRole) -> (&'static str, &'static str) {
+    match role {
+        ContributorRole::Author => ("author", "aut"),
+        ContributorRole::Editor => ("editor", "edt"),
+        ContributorRole::Translator => ("translator", "trl"),
+        ContributorRole::Illustrator => ("illustrator", "ill"),
+    }
+}
+
+/// 识别英文关系词、关系代码以及中文记录中常见的 "译" "编" 等
+fn relator_role(value: &str) -> Option<ContributorRole> {
+    match clean(value).to_lowercase().as_str() {
+        "aut" | "author" | "著" => Some(ContributorRole::Author),
+        "edt" | "editor" | "编" | "主编" => Some(ContributorRole::Editor),
+        "trl" | "translator" | "译" => Some(ContributorRole::Translator),
+        "ill" | "illustrator" | "绘" => Some(ContributorRole::Illustrator),
+        _ => None,
+    }
+}
+
+/// 去掉著录用的结尾标点，例如 "三体 /" 和 "刘慈欣,"
+fn clean(value: &str) -> String {
+    value
+        .trim()
+        .trim_end_matches([' ', '/', ':', ';', ',', '=', '.'])
+        .trim()
+        .to_owned()
+}
+
+/// 出版日期常写作 "2008." "c2008" "[2008?]"，取第一个四位数字
+fn first_year(value: &str) -> Option<i32> {
+    value
+        .as_bytes()
+        .windows(4)
+        .find(|window| window.iter().all(u8::is_ascii_digit))
+        .and_then(|window| std::str::from_utf8(window).ok()?.parse().ok())
+}
+
+/// 是否为 ISO 2709 格式：含有记录结束符
+pub fn is_iso2709(data: &[u8]) -> bool {
+    data.contains(&RECORD_TERMINATOR)
+}
+
+/// 逐条解析 ISO 2709 文件，单条记录出错不影响其他记录
+pub fn read_iso2709(data: &[u8]) -> Vec<Result<Record, String>> {
+    data.split(|byte| *byte == RECORD_TERMINATOR)
+        .map(|record| record.trim_ascii())
+        .filter(|record| !record.is_empty())
+        .map(parse_iso2709_record)
+        .collect()
+}
+
+fn parse_iso2709_record(data: &[u8]) -> Result<Record, String> {
+    let leader = data
+        .get(..LEADER_LENGTH)
+        .and_then(|leader| std::str::from_utf8(leader).ok())
+        .filter(|leader| leader.is_ascii())
+        .ok_or("头标不完整")?;
+    // 头标第 9 位为 a 表示 UTF-8，其余为 MARC-8，只能处理其中的纯 ASCII 记录
+    if leader.as_bytes()[9] != b'a' && !data.is_ascii() {
+        return Err("仅支持 UTF-8 编码的记录（头标第 9 位为 a）".to_owned());
+    }
+    let base_address: usize = leader[12..17].parse().map_err(|_| "头标中的数据起始地址无效")?;
+    let directory = data
+        .get(LEADER_LENGTH..base_address.saturating_sub(1))
+        .ok_or("目录超出记录范围")?;
+    let mut fields = Vec::new();
+    for entry in directory.chunks(12) {
+        let entry = std::str::from_utf8(entry)
+            .ok()
+            .filter(|entry| entry.len() == 12)
+            .ok_or("目录项不完整")?;
+        let (tag, length, start) = (&entry[..3], &entry[3..7], &entry[7..]);
+        let (Ok(length), Ok(start)) = (length.parse::<usize>(), start.parse::<usize>()) else {
+            return Err(format!("字段 {tag} 的目录项无效"));
+        };
+        let value = data
+            .get(base_address + start..base_address + start + length)
+            .ok_or_else(|| format!("字段 {tag} 超出记录范围"))?;
+        let value = value.strip_suffix(&[FIELD_TERMINATOR]).unwrap_or(value);
+        let value = String::from_utf8_lossy(value);
+        if tag.starts_with("00") {
+            fields.push(Field::Control {
+                tag: tag.to_owned(),
+                value: value.into_owned(),
+            });
+            continue;
+        }
+        let mut parts = value.split(SUBFIELD_DELIMITER as char);
+        let mut indicators = parts.next().unwrap_or_default().chars();
+        fields.push(Field::Data {
+            tag: tag.to_owned(),
+            indicators: [
+                indicators.next().unwrap_or(' '),
+                indicators.next().unwrap_or(' '),
+            ],
+            subfields: parts
+                .filter_map(|part| {
+                    let mut chars = part.chars();
+                    Some((chars.next()?, chars.as_str().to_owned()))
+                })
+                .collect(),
+        });
+    }
+    Ok(Record {
+        leader: leader.to_owned(),
+        fields,
+    })
+}
+
+/// 解析 MARCXML，`collection` 中的每个 `record` 单独校验，带不带命名空间前缀都可以
+pub fn read_marcxml(text: &str) -> Result<Vec<Result<Record, String>>, String> {
+    let document = roxmltree::Document::parse(text).map_err(|err| format!("无法解析 XML：{err}"))?;
+    Ok(document
+        .descendants()
+        .filter(|node| node.tag_name().name() == "record")
+        .map(|record| {
+            let mut leader = String::new();
+            let mut fields = Vec::new();
+            for node in record.children().filter(|node| node.is_element()) {
+                let tag = || {
+                    node.attribute("tag")
+                        .map(str::to_owned)
+                        .ok_or("字段缺少 tag 属性".to_owned())
+                };
+                match node.tag_name().name() {
+                    "leader" => leader = node.text().unwrap_or_default().to_owned(),
+                    "controlfield" => fields.push(Field::Control {
+                        tag: tag()?,
+                        value: node.text().unwrap_or_default().to_owned(),
+                    }),
+                    "datafield" => {
+                        let indicator = |name| {
+                            node.attribute(name)
+                                .and_then(|value| value.chars().next())
+                                .unwrap_or(' ')
+                        };
+                        fields.push(Field::Data {
+                            tag: tag()?,
+                            indicators: [indicator("ind1"), indicator("ind2")],
+                            subfields: node
+                                .children()
+                                .filter(|node| node.tag_name().name() == "subfield")
+                                .filter_map(|subfield| {
+                                    let code = subfield.attribute("code")?.chars().next()?;
+                                    Some((code, subfield.text().unwrap_or_default().to_owned()))
+                                })
+                                .collect(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Record { leader, fields })
+        })
+        .collect())
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            // XML 1.0 不允许的控制字符
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn write_record(xml: &mut String, record: &Record) {
+    xml.push_str("<record>");
+    xml.push_str(&format!("<leader>{}</leader>", escape(&record.leader)));
+    for field in &record.fields {
+        match field {
+            Field::Control { tag, value } => {
+                xml.push_str(&format!(
+                    r#"<controlfield tag="{}">{}</controlfield>"#,
+                    escape(tag),
+                    escape(value)
+                ));
+            }
+            Field::Data {
+                tag,
+                indicators,
+                subfields,
+            } => {
+                xml.push_str(&format!(
+                    r#"<datafield tag="{}" ind1="{}" ind2="{}">"#,
+                    escape(tag),
+                    escape(&indicators[0].to_string()),
+                    escape(&indicators[1].to_string())
+                ));
+                for (code, value) in subfields {
+                    xml.push_str(&format!(
+                        r#"<subfield code="{}">{}</subfield>"#,
+                        escape(&code.to_string()),
+                        escape(value)
+                    ));
+                }
+                xml.push_str("</datafield>");
+            }
+        }
+    }
+    xml.push_str("</record>\n");
+}
+
+fn collection_header() -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<collection xmlns=\"{MARCXML_NAMESPACE}\">\n"
+    )
+}
+
+const COLLECTION_FOOTER: &str = "</collection>\n";
+
+pub fn to_marcxml<'a>(records: impl IntoIterator<Item = &'a Record>) -> String {
+    let mut xml = collection_header();
+    for record in records {
+        write_record(&mut xml, record);
+    }
+    xml.push_str(COLLECTION_FOOTER);
+    xml
+}
+
+/// 与 `export::export` 相同，逐页查询并边查边发送，导出为 `name-日期.xml`
+pub async fn export_marcxml<F, Fut>(name: &str, fetch_page: F) -> Result<HttpResponse, Error>
+where
+    F: Fn(u64, u64) -> Fut + 'static,
+    Fut: Future<Output = Result<(Vec<BooksResult>, u64), DbErr>> + 'static,
+{
+    let today = chrono::Local::now().naive_local().date();
+    let disposition = ContentDisposition {
+        disposition: DispositionType::Attachment,
+        parameters: vec![DispositionParam::Filename(format!("{name}-{today}.xml"))],
+    };
+    let pages = stream::try_unfold(1, move |page| {
+        let rows = fetch_page(page, EXPORT_PAGE_SIZE);
+        async move {
+            let (rows, _) = rows.await?;
+            Ok::<_, Error>((!rows.is_empty()).then_some((rows, page + 1)))
+        }
+    });
+    let records = pages.map_ok(|books| {
+        let mut xml = String::new();
+        for book in &books {
+            write_record(&mut xml, &Record::from_book(book));
+        }
+        Bytes::from(xml)
+    });
+    let body = stream::once(async { Ok(Bytes::from(collection_header())) })
+        .chain(records)
+        .chain(stream::once(async { Ok(Bytes::from_static(COLLECTION_FOOTER.as_bytes())) }));
+    Ok(HttpResponse::Ok()
+        .content_type("application/marcxml+xml; charset=utf-8")
+        .insert_header(disposition)
+        .streaming(body))
+}
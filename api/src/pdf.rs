@@ -0,0 +1,264 @@
+//! 借书凭条与读者证的 PDF。中文使用阅读器自带的 STSong-Light（Adobe-GB1），
+//! 不嵌入字体文件，生成的文件只有几 KB。
+
+use book_manager_service::library_card_number;
+use chrono::NaiveDateTime;
+use entity::{users, BorrowedBooksResultForBook};
+use lopdf::{
+    content::{Content, Operation},
+    dictionary, Document, Object, Stream, StringFormat,
+};
+
+use crate::{barcode, error::Error, filters::format_due_date};
+
+/// 1 毫米对应的 PDF 点数
+const MM: f32 = 72.0 / 25.4;
+/// 热敏小票纸宽度
+const RECEIPT_WIDTH: f32 = 80.0;
+const RECEIPT_MARGIN: f32 = 5.0;
+/// 标准卡片尺寸（ISO/IEC 7810 ID-1）
+const CARD_WIDTH: f32 = 85.6;
+const CARD_HEIGHT: f32 = 54.0;
+
+/// 以毫米为单位、原点在左下角的单页画布
+struct Page {
+    width: f32,
+    height: f32,
+    operations: Vec<Operation>,
+}
+
+impl Page {
+    fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            operations: Vec::new(),
+        }
+    }
+
+    /// `y` 为基线位置，字号以磅为单位
+    fn text(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        let encoded = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        self.operations.extend([
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), size.into()]),
+            Operation::new("Td", vec![(x * MM).into(), (y * MM).into()]),
+            Operation::new("Tj", vec![Object::String(encoded, StringFormat::Hexadecimal)]),
+            Operation::new("ET", vec![]),
+        ]);
+    }
+
+    fn centered_text(&mut self, y: f32, size: f32, text: &str) {
+        let x = (self.width - text_width(text, size)) / 2.0;
+        self.text(x, y, size, text);
+    }
+
+    fn rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.operations.extend([
+            Operation::new(
+                "re",
+                vec![(x * MM).into(), (y * MM).into(), (width * MM).into(), (height * MM).into()],
+            ),
+            Operation::new("f", vec![]),
+        ]);
+    }
+
+    fn line(&mut self, y: f32, from: f32, to: f32) {
+        self.operations.extend([
+            Operation::new("w", vec![(0.2 * MM).into()]),
+            Operation::new("m", vec![(from * MM).into(), (y * MM).into()]),
+            Operation::new("l", vec![(to * MM).into(), (y * MM).into()]),
+            Operation::new("S", vec![]),
+        ]);
+    }
+
+    /// 条码底边在 `y`，水平居中并尽量占满 `max_width`，模块宽度不超过 0.5 毫米
+    fn barcode(&mut self, y: f32, height: f32, max_width: f32, widths: &[u8]) {
+        let modules: u32 = widths.iter().map(|&width| u32::from(width)).sum();
+        let module = (max_width / modules as f32).min(0.5);
+        let mut x = (self.width - module * modules as f32) / 2.0;
+        for (index, &width) in widths.iter().enumerate() {
+            let width = module * f32::from(width);
+            // 偶数位是条，奇数位是空
+            if index % 2 == 0 {
+                self.rect(x, y, width, height);
+            }
+            x += width;
+        }
+    }
+}
+
+/// 估算文字宽度（毫米）：ASCII 按半角，其余按全角
+fn text_width(text: &str, size: f32) -> f32 {
+    let ems: f32 = text
+        .chars()
+        .map(|c| if c.is_ascii() { 0.5 } else { 1.0 })
+        .sum();
+    ems * size / MM
+}
+
+/// 按宽度折行，书名较长时不至于超出纸边
+fn wrap(text: &str, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for c in text.chars() {
+        let line = lines.last_mut().unwrap();
+        line.push(c);
+        if text_width(line, size) > max_width && line.chars().count() > 1 {
+            line.pop();
+            lines.push(c.to_string());
+        }
+    }
+    lines
+}
+
+/// 借出后交给读者的凭条，页面高度随借阅数量变化
+pub fn loan_receipt(
+    patron: &users::Model,
+    loans: &[&BorrowedBooksResultForBook],
+    printed_at: NaiveDateTime,
+) -> Result<Vec<u8>, Error> {
+    const LINE: f32 = 5.0;
+    let text_width = RECEIPT_WIDTH - RECEIPT_MARGIN * 2.0;
+    let lines: Vec<Vec<String>> = loans
+        .iter()
+        .map(|loan| wrap(&format!("《{}》", loan.book_name), 10.0, text_width))
+        .collect();
+    // 抬头与结尾共 44 毫米，每笔借阅为书名行数加 4.8 行
+    let body: f32 = lines.iter().map(|name| (name.len() as f32 + 4.8) * LINE).sum();
+    let height = 44.0 + body;
+    let mut page = Page::new(RECEIPT_WIDTH, height);
+
+    let mut y = height - 12.0;
+    page.centered_text(y, 14.0, "借书凭条");
+    y -= LINE * 2.0;
+    page.text(RECEIPT_MARGIN, y, 9.0, &format!("读者：{}（{}）", patron.nickname, patron.name));
+    y -= LINE;
+    let printed_at = printed_at.format("%Y-%m-%d %H:%M");
+    page.text(RECEIPT_MARGIN, y, 9.0, &format!("打印时间：{printed_at}"));
+    y -= LINE * 0.6;
+    page.line(y, RECEIPT_MARGIN, RECEIPT_WIDTH - RECEIPT_MARGIN);
+    for (loan, name) in loans.iter().zip(lines) {
+        y -= LINE * 1.2;
+        for line in name {
+            page.text(RECEIPT_MARGIN, y, 10.0, &line);
+            y -= LINE;
+        }
+        let codes = format!("ISBN：{}  条码：{}", loan.isbn, loan.barcode);
+        page.text(RECEIPT_MARGIN, y, 9.0, &codes);
+        y -= LINE;
+        page.text(RECEIPT_MARGIN, y, 9.0, &format!("借阅日期：{}", loan.borrow_date));
+        y -= LINE;
+        page.text(
+            RECEIPT_MARGIN,
+            y,
+            9.0,
+            &format!("应还日期：{}", format_due_date(loan.return_date, loan.due_at)),
+        );
+        y -= LINE * 0.6;
+        page.line(y, RECEIPT_MARGIN, RECEIPT_WIDTH - RECEIPT_MARGIN);
+    }
+    y -= LINE * 1.2;
+    page.text(RECEIPT_MARGIN, y, 9.0, &format!("共 {} 册，请按时归还。", loans.len()));
+    render("借书凭条", page)
+}
+
+/// 读者证，条码内容为借还台与自助机识别读者用的读者证条码
+pub fn library_card(user: &users::Model) -> Result<Vec<u8>, Error> {
+    let card_number = library_card_number(user.id);
+    let widths = barcode::code128(&card_number)
+        .ok_or_else(|| Error::bad_request("读者证条码无法编码"))?;
+    let mut page = Page::new(CARD_WIDTH, CARD_HEIGHT);
+    page.rect(0.0, CARD_HEIGHT - 11.0, CARD_WIDTH, 11.0);
+    page.operations.push(Operation::new("g", vec![1.into()]));
+    page.centered_text(CARD_HEIGHT - 7.5, 12.0, "读者证");
+    page.operations.push(Operation::new("g", vec![0.into()]));
+    page.text(6.0, CARD_HEIGHT - 18.0, 10.0, &format!("姓名：{}", user.nickname));
+    page.text(6.0, CARD_HEIGHT - 23.5, 8.0, &format!("读者类别：{}", user.patron_category));
+    page.text(
+        CARD_WIDTH / 2.0,
+        CARD_HEIGHT - 23.5,
+        8.0,
+        &format!("注册日期：{}", user.registration_date),
+    );
+    page.barcode(9.0, 14.0, CARD_WIDTH - 12.0, &widths);
+    page.centered_text(4.5, 8.0, &card_number);
+    render("读者证", page)
+}
+
+fn render(title: &str, page: Page) -> Result<Vec<u8>, Error> {
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type0",
+        "BaseFont" => "STSong-Light",
+        "Encoding" => "UniGB-UTF16-H",
+        "DescendantFonts" => vec![dictionary! {
+            "Type" => "Font",
+            "Subtype" => "CIDFontType0",
+            "BaseFont" => "STSong-Light",
+            "CIDSystemInfo" => dictionary! {
+                "Registry" => Object::string_literal("Adobe"),
+                "Ordering" => Object::string_literal("GB1"),
+                "Supplement" => 4,
+            },
+            "FontDescriptor" => dictionary! {
+                "Type" => "FontDescriptor",
+                "FontName" => "STSong-Light",
+                "Flags" => 6,
+                "FontBBox" => vec![(-25).into(), (-254).into(), 1000.into(), 880.into()],
+                "ItalicAngle" => 0,
+                "Ascent" => 880,
+                "Descent" => -120,
+                "CapHeight" => 880,
+                "StemV" => 93,
+            },
+            // 与 text_width 一致：ASCII 半角，其余全角
+            "DW" => 1000,
+            "W" => vec![1.into(), 95.into(), 500.into()],
+        }.into()],
+    });
+    let resources_id = doc.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font_id },
+    });
+    let media_box = vec![0.into(), 0.into(), (page.width * MM).into(), (page.height * MM).into()];
+    let content = Content {
+        operations: page.operations,
+    };
+    let content_id =
+        doc.add_object(Stream::new(dictionary! {}, content.encode().map_err(Error::new)?));
+    let page_id = doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => content_id,
+        "MediaBox" => media_box,
+    });
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page_id.into()],
+            "Count" => 1,
+            "Resources" => resources_id,
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    // 文本字符串以 BOM 开头表示 UTF-16BE
+    let title = "\u{feff}".encode_utf16().chain(title.encode_utf16());
+    let info_id = doc.add_object(dictionary! {
+        "Title" => Object::String(
+            title.flat_map(u16::to_be_bytes).collect(),
+            StringFormat::Hexadecimal,
+        ),
+        "Producer" => Object::string_literal("Book Manager"),
+    });
+    doc.trailer.set("Root", catalog_id);
+    doc.trailer.set("Info", info_id);
+    doc.compress();
+    let mut bytes = Vec::new();
+    doc.save_to(&mut bytes).map_err(Error::new)?;
+    Ok(bytes)
+}
@@ -1,6 +1,6 @@
 use crate::{
     handlers::{
-        books::*, borrow::*, emails::*, index::*, login::*, logout::*, not_found, search::*,
+        authors::*, books::*, borrow::*, calendar::*, categories::*, courses::*, desk::*, emails::*, fines::*, holds::*, ill::*, incidents::*, index::*, items::*, jobs::*, login::*, logout::*, not_found, policies::*, search::*, statistics::*,
         users::*, reload_templates, background::background_handler,
     },
     permission::Permission,
@@ -24,14 +24,23 @@ pub fn general_routes(cfg: &mut web::ServiceConfig) {
             web::scope("/search")
                 .wrap(Permission::new(AccessPermission::User))
                 .route("", web::get().to(search_handler))
-                .route("/s", web::get().to(search_get_handler)),
+                .route("/s", web::get().to(search_get_handler))
+                .service(
+                    web::resource("/export")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(search_export_handler)),
+                ),
         )
         .service(
             web::scope("/control")
                 .wrap(Permission::new(AccessPermission::Admin))
-                .route("/reload_templates", web::get().to(reload_templates)),
+                .route("/reload_templates", web::get().to(reload_templates))
+                .route("/jobs", web::get().to(list_job_runs_handler))
+                .route("/jobs/run", web::post().to(run_jobs_post_handler))
+                .route("/statistics", web::get().to(statistics_handler)),
         )
         .route("/logout", web::get().to(logout_handler))
+        .route("/feeds/{token}.ics", web::get().to(calendar_feed_handler))
         .service(
             web::resource("/register")
                 .route(web::get().to(register_handler))
@@ -44,25 +53,82 @@ pub fn general_routes(cfg: &mut web::ServiceConfig) {
                         .wrap(Permission::new(AccessPermission::Admin))
                         .route(web::get().to(list_users_handler)),
                 )
+                .service(
+                    web::resource("/export")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(export_users_handler)),
+                )
                 .service(
                     web::resource("/delete/{user_id}")
                         .wrap(Permission::new(AccessPermission::Admin))
                         .route(web::get().to(delete_user_handler)),
                 )
+                .service(
+                    web::resource("/{user_id}/category")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(update_user_category_post_handler)),
+                )
+                .service(
+                    web::resource("/{user_id}/blocks")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(new_user_block_post_handler)),
+                )
+                .service(
+                    web::resource("/blocks/lift/{block_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(lift_user_block_handler)),
+                )
                 .wrap(Permission::new(AccessPermission::User))
-                .route("/{user_id}", web::get().to(user_detail_handler)),
+                .route("/{user_id}", web::get().to(user_detail_handler))
+                .route("/{user_id}/calendar_token", web::post().to(reset_calendar_token_handler))
+                .route(
+                    "/{user_id}/calendar_token/revoke",
+                    web::post().to(revoke_calendar_token_handler),
+                )
+                .route("/{user_id}/history", web::get().to(user_loan_history_handler))
+                .route("/{user_id}/card", web::get().to(library_card_handler)),
         )
         .service(
             web::scope("/borrow")
-                .wrap(Permission::new(AccessPermission::Admin))
-                .route("", web::get().to(list_borrowed_books_handler))
-                .route("/delete/{borrow_id}", web::get().to(return_book_handler))
-                .route("/{book_id}", web::post().to(borrow_book_post_handler)),
+                .service(
+                    web::resource("")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(list_borrowed_books_handler)),
+                )
+                .service(
+                    web::resource("/export")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(export_borrowed_books_handler)),
+                )
+                .service(
+                    web::resource("/delete/{borrow_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(return_book_handler)),
+                )
+                .service(
+                    web::resource("/{book_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(borrow_book_post_handler)),
+                )
+                .wrap(Permission::new(AccessPermission::User))
+                .route("/renew/{borrow_id}", web::get().to(renew_book_handler))
+                .route("/request/{book_id}", web::post().to(self_checkout_post_handler)),
         )
         .service(
             web::scope("/books")
                 .wrap(Permission::new(AccessPermission::User))
                 .route("", web::get().to(list_books_handler))
+                .service(
+                    web::resource("/export")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(export_books_handler)),
+                )
+                .service(
+                    web::resource("/import")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(import_books_handler))
+                        .route(web::post().to(import_books_post_handler)),
+                )
                 .service(
                     web::resource("/edit/{book_id}")
                         .wrap(Permission::new(AccessPermission::Admin))
@@ -80,8 +146,157 @@ pub fn general_routes(cfg: &mut web::ServiceConfig) {
                         .route(web::get().to(new_book_handler))
                         .route(web::post().to(new_book_post_handler)),
                 )
+                .service(
+                    web::resource("/{book_id}/history")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(book_loan_history_handler)),
+                )
                 .route("/{book_id}", web::get().to(book_detail_handler)),
         )
+        .service(
+            web::scope("/authors")
+                .wrap(Permission::new(AccessPermission::User))
+                .route("", web::get().to(list_authors_handler))
+                .route("/{author_id}", web::get().to(author_detail_handler)),
+        )
+        .service(
+            web::scope("/categories")
+                .wrap(Permission::new(AccessPermission::User))
+                .service(
+                    web::resource("/new")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(new_category_post_handler)),
+                )
+                .service(
+                    web::resource("/delete/{category_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(delete_category_handler)),
+                )
+                .route("", web::get().to(list_categories_handler))
+                .route("/{category_id}", web::get().to(category_detail_handler)),
+        )
+        .service(
+            web::scope("/items")
+                .wrap(Permission::new(AccessPermission::Admin))
+                .route("/new/{book_id}", web::post().to(new_book_item_post_handler))
+                .service(
+                    web::resource("/edit/{item_id}")
+                        .route(web::get().to(edit_book_item_handler))
+                        .route(web::post().to(edit_book_item_post_handler)),
+                )
+                .route("/delete/{item_id}", web::get().to(delete_book_item_handler)),
+        )
+        .service(
+            web::scope("/policies")
+                .wrap(Permission::new(AccessPermission::Admin))
+                .route("", web::get().to(list_policies_handler))
+                .route("/new", web::post().to(new_policy_post_handler))
+                .service(
+                    web::resource("/edit/{policy_id}")
+                        .route(web::get().to(edit_policy_handler))
+                        .route(web::post().to(edit_policy_post_handler)),
+                )
+                .route("/delete/{policy_id}", web::get().to(delete_policy_handler)),
+        )
+        .service(
+            web::scope("/desk")
+                .wrap(Permission::new(AccessPermission::Admin))
+                .route("", web::get().to(desk_handler))
+                .route("/scan", web::post().to(desk_scan_post_handler))
+                .route("/clear", web::get().to(desk_clear_handler))
+                .route("/receipt", web::get().to(desk_receipt_handler))
+                .service(
+                    web::resource("/offline")
+                        .route(web::get().to(offline_upload_handler))
+                        .route(web::post().to(offline_upload_post_handler)),
+                ),
+        )
+        .service(
+            web::scope("/incidents")
+                .wrap(Permission::new(AccessPermission::Admin))
+                .route("", web::get().to(list_incidents_handler))
+                .service(
+                    web::resource("/new/{borrow_id}")
+                        .route(web::get().to(new_incident_handler))
+                        .route(web::post().to(new_incident_post_handler)),
+                )
+                .route("/resolve/{incident_id}", web::post().to(resolve_incident_post_handler)),
+        )
+        .service(
+            web::scope("/calendar")
+                .wrap(Permission::new(AccessPermission::Admin))
+                .route("", web::get().to(calendar_handler))
+                .route("/hours/{weekday}", web::post().to(update_opening_hours_post_handler))
+                .route("/closures/new", web::post().to(new_closure_post_handler))
+                .route("/closures/delete/{closure_id}", web::get().to(delete_closure_handler)),
+        )
+        .service(
+            web::scope("/fines")
+                .wrap(Permission::new(AccessPermission::Admin))
+                .route("/pay/{user_id}", web::post().to(record_payment_post_handler))
+                .route("/waive/{fine_id}", web::post().to(waive_fine_post_handler)),
+        )
+        .service(
+            web::scope("/holds")
+                .service(
+                    web::resource("")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(list_holds_handler)),
+                )
+                .wrap(Permission::new(AccessPermission::User))
+                .route("/new/{book_id}", web::post().to(place_hold_post_handler))
+                .route("/cancel/{hold_id}", web::get().to(cancel_hold_handler)),
+        )
+        .service(
+            web::scope("/ill")
+                .service(
+                    web::resource("")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(list_ill_requests_handler)),
+                )
+                .service(
+                    web::resource("/advance/{request_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(advance_ill_request_post_handler)),
+                )
+                .wrap(Permission::new(AccessPermission::User))
+                .service(
+                    web::resource("/new")
+                        .route(web::get().to(new_ill_request_handler))
+                        .route(web::post().to(new_ill_request_post_handler)),
+                )
+                .route("/cancel/{request_id}", web::get().to(cancel_ill_request_handler)),
+        )
+        .service(
+            web::scope("/courses")
+                .service(
+                    web::resource("/new")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(new_course_post_handler)),
+                )
+                .service(
+                    web::resource("/edit/{course_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(edit_course_post_handler)),
+                )
+                .service(
+                    web::resource("/delete/{course_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(delete_course_handler)),
+                )
+                .service(
+                    web::resource("/{course_id}/reserves")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::post().to(new_course_reserve_post_handler)),
+                )
+                .service(
+                    web::resource("/{course_id}/reserves/delete/{book_id}")
+                        .wrap(Permission::new(AccessPermission::Admin))
+                        .route(web::get().to(delete_course_reserve_handler)),
+                )
+                .route("", web::get().to(list_courses_handler))
+                .route("/{course_id}", web::get().to(course_detail_handler)),
+        )
         .service(
             web::scope("/emails")
                 .wrap(Permission::new(AccessPermission::User))
@@ -0,0 +1,214 @@
+//! 进程内的定时任务：到期提醒与逾期通知、过期预约处理、逾期罚款结算、课程参考书到期、
+//! 解除到期的停借。
+//! 运行记录保存在 `job_runs` 表，已发送的提醒保存在 `loan_notices` 表，重启后不会重复发送。
+
+use std::{env, time::Duration};
+
+use book_manager_service::{
+    sea_orm::{DatabaseConnection, DbErr, TransactionError, TransactionTrait},
+    Fines, Holds, LoanState, Mutation, Query,
+};
+use chrono::NaiveDate;
+use entity::{EmailCategory, JobStatus, LoanStatus};
+
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// 检查间隔
+    pub interval: Duration,
+    /// 到期前几天发送提醒
+    pub days_before: i64,
+    /// 逾期后第几天发送通知，逐级递增
+    pub overdue_days: Vec<i64>,
+}
+
+impl SchedulerConfig {
+    pub fn from_env() -> Self {
+        let interval = env::var("SCHEDULER_INTERVAL_SECS")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(3600);
+        let days_before = env::var("NOTICE_DAYS_BEFORE")
+            .ok()
+            .and_then(|days| days.parse().ok())
+            .unwrap_or(3);
+        let mut overdue_days: Vec<i64> = env::var("OVERDUE_NOTICE_DAYS")
+            .ok()
+            .map(|days| days.split(',').filter_map(|day| day.trim().parse().ok()).collect())
+            .unwrap_or_else(|| vec![1, 7, 14, 30]);
+        overdue_days.retain(|&day| day > 0);
+        overdue_days.sort_unstable();
+        overdue_days.dedup();
+        Self {
+            interval: Duration::from_secs(interval),
+            days_before,
+            overdue_days,
+        }
+    }
+
+    /// 所有提醒相对应还日期的天数，从小到大
+    fn notice_offsets(&self) -> Vec<i64> {
+        let mut offsets = vec![-self.days_before, 0];
+        offsets.extend(&self.overdue_days);
+        offsets.sort_unstable();
+        offsets.dedup();
+        offsets
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Job {
+    LoanNotices,
+    ExpireHolds,
+    AssessFines,
+    ExpireReserves,
+    LiftBlocks,
+}
+
+impl Job {
+    pub const ALL: [Job; 5] = [
+        Job::LoanNotices,
+        Job::ExpireHolds,
+        Job::AssessFines,
+        Job::ExpireReserves,
+        Job::LiftBlocks,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Job::LoanNotices => "loan_notices",
+            Job::ExpireHolds => "expire_holds",
+            Job::AssessFines => "assess_fines",
+            Job::ExpireReserves => "expire_reserves",
+            Job::LiftBlocks => "lift_blocks",
+        }
+    }
+}
+
+pub fn spawn(conn: DatabaseConnection, config: SchedulerConfig) {
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(config.interval);
+        loop {
+            interval.tick().await;
+            run_jobs(&conn, &config, false).await;
+        }
+    });
+}
+
+/// 每个任务每天只成功运行一次，`force` 为真时立即重新运行
+pub async fn run_jobs(conn: &DatabaseConnection, config: &SchedulerConfig, force: bool) {
+    let today = chrono::Local::now().naive_local().date();
+    for job in Job::ALL {
+        if !force {
+            match Query::find_last_job_run_by_name(conn, job.name(), JobStatus::Succeeded).await {
+                Ok(Some(run)) if run.started_at.date() == today => continue,
+                Ok(_) => {}
+                Err(err) => {
+                    tracing::error!("failed to load job runs: {err}");
+                    continue;
+                }
+            }
+        }
+        run_job(conn, config, job, today).await;
+    }
+}
+
+async fn run_job(conn: &DatabaseConnection, config: &SchedulerConfig, job: Job, today: NaiveDate) {
+    let run = match Mutation::create_job_run(conn, job.name()).await {
+        Ok(run) => run,
+        Err(err) => {
+            tracing::error!("failed to record job {}: {err}", job.name());
+            return;
+        }
+    };
+    let result = match job {
+        Job::LoanNotices => send_loan_notices(conn, config, today).await,
+        Job::ExpireHolds => Holds::expire_holds(conn, today).await,
+        // 按小时借出的课程参考书精确到当前时间
+        Job::AssessFines => Fines::assess_all(conn, chrono::Local::now().naive_local()).await,
+        Job::ExpireReserves => Mutation::expire_courses(conn, today).await,
+        Job::LiftBlocks => Mutation::lift_expired_user_blocks(conn, today).await,
+    };
+    let (status, processed, message) = match result {
+        Ok(processed) => (JobStatus::Succeeded, processed as i32, String::new()),
+        Err(err) => {
+            tracing::error!("job {} failed: {err}", job.name());
+            (JobStatus::Failed, 0, err.to_string())
+        }
+    };
+    if let Err(err) = Mutation::finish_job_run_by_id(conn, run.id, status, processed, message).await
+    {
+        tracing::error!("failed to record job {}: {err}", job.name());
+    }
+}
+
+/// 对每笔未归还的借阅，只发送当前已到期的最后一级提醒，已发送过的不再重复
+/// 续借会清除已发送记录，按新的归还日期重新提醒
+async fn send_loan_notices(
+    conn: &DatabaseConnection,
+    config: &SchedulerConfig,
+    today: NaiveDate,
+) -> Result<u32, DbErr> {
+    let sender_id = *Query::find_admin_ids(conn)
+        .await?
+        .first()
+        .ok_or(DbErr::Custom("没有管理员账号，无法发送通知".to_owned()))?;
+    let offsets = config.notice_offsets();
+    let mut sent = 0;
+    for loan in Query::find_borrowed_books_by_state(conn, LoanState::Active).await? {
+        if loan.status != LoanStatus::OnLoan {
+            continue;
+        }
+        let days = (today - loan.return_date).num_days();
+        let Some(&offset) = offsets.iter().rev().find(|&&offset| offset <= days) else {
+            continue;
+        };
+        let last_sent = Query::find_last_loan_notice_offset(conn, loan.id).await?;
+        if last_sent.is_some_and(|last| last as i64 >= offset) {
+            continue;
+        }
+        let book_name = Query::find_book_by_id(conn, loan.book_id)
+            .await?
+            .map(|book| book.name)
+            .unwrap_or_default();
+        let (subject, content) = match days {
+            days if days < 0 => (
+                "到期提醒",
+                format!("您借阅的《{book_name}》将于 {} 到期，请按时归还或办理续借。", loan.return_date),
+            ),
+            0 => (
+                "到期提醒",
+                format!("您借阅的《{book_name}》今天到期，请按时归还或办理续借。"),
+            ),
+            days => (
+                "逾期通知",
+                format!(
+                    "您借阅的《{book_name}》已逾期 {days} 天（应还日期 {}），请尽快归还。",
+                    loan.return_date
+                ),
+            ),
+        };
+        let (borrow_id, user_id) = (loan.id, loan.user_id);
+        conn.transaction::<_, (), DbErr>(|txn| {
+            Box::pin(async move {
+                Mutation::create_email(
+                    txn,
+                    EmailCategory::Regular,
+                    sender_id,
+                    user_id,
+                    subject.to_owned(),
+                    content,
+                )
+                .await?;
+                Mutation::create_loan_notice(txn, borrow_id, offset as i32).await?;
+                Ok(())
+            })
+        })
+        .await
+        .map_err(|err| match err {
+            TransactionError::Connection(err) => err,
+            TransactionError::Transaction(err) => err,
+        })?;
+        sent += 1;
+    }
+    Ok(sent)
+}
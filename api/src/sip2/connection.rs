@@ -0,0 +1,426 @@
+use actix_web::rt::net::TcpStream;
+use book_manager_service::{
+    sea_orm::DatabaseConnection, Calendar, LoanState, Policy, Query, DEFAULT_CATEGORY,
+};
+use chrono::NaiveDateTime;
+use entity::{book_items, borrowed_books, users, ItemStatus, LoanStatus};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+use crate::{
+    error::Error,
+    filters::{format_due, format_money},
+    handlers::borrow::{borrow_book, renew_loan, return_book, BorrowError},
+};
+
+use super::{
+    message::{self, format_date, yes_no, ParseError, Request, Response},
+    Sip2Config,
+};
+
+/// 98 回复中的 BX 字段，按 SIP2 规范的顺序标出支持的请求：
+/// 读者状态、借出、归还、状态、重发、登录、物品信息与续借
+const SUPPORTED_MESSAGES: &str = "YYYNYYYNNNYNNNYN";
+/// 单条报文的最大长度，超过时断开连接
+const MAX_MESSAGE_LEN: u64 = 4096;
+/// 登录失败达到此次数后断开连接
+const MAX_FAILED_LOGINS: u32 = 3;
+
+struct Connection<'a> {
+    conn: &'a DatabaseConnection,
+    config: &'a Sip2Config,
+    /// 登录后流通记录的经办人
+    staff_id: Option<i32>,
+    failed_logins: u32,
+    /// 最近一次回复，收到 97 时重发
+    last_response: Option<String>,
+}
+
+/// 逐条读取以 `\r` 结尾的报文并回复，未登录时收到登录以外的请求、
+/// 报文超长或多次登录失败即断开
+pub(super) async fn handle(
+    stream: TcpStream,
+    conn: &DatabaseConnection,
+    config: &Sip2Config,
+) -> Result<(), Error> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut connection = Connection {
+        conn,
+        config,
+        staff_id: None,
+        failed_logins: 0,
+        last_response: None,
+    };
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let read = (&mut reader)
+            .take(MAX_MESSAGE_LEN)
+            .read_until(b'\r', &mut buf)
+            .await
+            .map_err(Error::new)?;
+        if read == 0 || (read as u64 == MAX_MESSAGE_LEN && buf.last() != Some(&b'\r')) {
+            return Ok(());
+        }
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_matches(['\r', '\n']);
+        if line.is_empty() {
+            continue;
+        }
+        let Some(response) = connection.respond(line).await? else {
+            return Ok(());
+        };
+        writer
+            .write_all(response.as_bytes())
+            .await
+            .map_err(Error::new)?;
+        if connection.failed_logins >= MAX_FAILED_LOGINS {
+            return Ok(());
+        }
+    }
+}
+
+impl Connection<'_> {
+    /// 返回 `None` 时断开连接
+    async fn respond(&mut self, line: &str) -> Result<Option<String>, Error> {
+        let request = match message::parse(line) {
+            Ok(request) => request,
+            Err(ParseError::Checksum | ParseError::Malformed) => {
+                return Ok(Some(Response::new("96").finish(None)))
+            }
+        };
+        let response = match request.code.as_str() {
+            "97" => {
+                return Ok(Some(
+                    self.last_response
+                        .clone()
+                        .unwrap_or_else(|| Response::new("96").finish(None)),
+                ))
+            }
+            "93" => self.login(&request).await?,
+            _ if self.staff_id.is_none() => return Ok(None),
+            "99" => self.status(),
+            "23" => self.patron_status(&request).await?,
+            "17" => self.item_information(&request).await?,
+            "11" => self.checkout(&request).await?,
+            "09" => self.checkin(&request).await?,
+            "29" => self.renew(&request).await?,
+            _ => return Ok(Some(Response::new("96").finish(None))),
+        };
+        let response = response.finish(request.sequence);
+        self.last_response = Some(response.clone());
+        Ok(Some(response))
+    }
+
+    /// 只接受配置的借还机账号，流通记录以第一位管理员为经办人
+    async fn login(&mut self, request: &Request) -> Result<Response, Error> {
+        let valid = request.field_or_empty("CN") == self.config.login_user
+            && request.field_or_empty("CO") == self.config.login_password;
+        self.staff_id = if valid {
+            Query::find_admin_ids(self.conn).await?.first().copied()
+        } else {
+            None
+        };
+        if self.staff_id.is_none() {
+            self.failed_logins += 1;
+        }
+        Ok(Response::new("94").fixed(if self.staff_id.is_some() { "1" } else { "0" }))
+    }
+
+    fn status(&self) -> Response {
+        Response::new("98")
+            .fixed("YYYYNN")
+            .fixed("030003")
+            .fixed(format_date(now()))
+            .fixed("2.00")
+            .field("AO", &self.config.institution)
+            .field("AM", &self.config.institution)
+            .field("BX", SUPPORTED_MESSAGES)
+    }
+
+    async fn patron_status(&self, request: &Request) -> Result<Response, Error> {
+        let language = request.fixed.get(..3).unwrap_or("000");
+        let patron_id = request.field_or_empty("AA");
+        let response = Response::new("24");
+        let Some(patron) = Query::find_user_by_card(self.conn, patron_id).await? else {
+            return Ok(response
+                .fixed(" ".repeat(14))
+                .fixed(language)
+                .fixed(format_date(now()))
+                .field("AO", &self.config.institution)
+                .field("AA", patron_id)
+                .field("AE", "")
+                .field("BL", "N")
+                .field("AF", "读者不存在"));
+        };
+        let valid_password = request
+            .field("AD")
+            .filter(|password| !password.is_empty())
+            .map(|password| bcrypt::verify(password, &patron.password_hash))
+            .transpose()?
+            .unwrap_or(false);
+        let balance = Query::find_user_balance(self.conn, patron.id).await?;
+        Ok(response
+            .fixed(self.patron_flags(&patron, balance).await?)
+            .fixed(language)
+            .fixed(format_date(now()))
+            .field("AO", &self.config.institution)
+            .field("AA", &patron.name)
+            .field("AE", &patron.nickname)
+            .field("BL", "Y")
+            .field("CQ", yes_no(valid_password).to_string())
+            .field("BH", "CNY")
+            .field("BV", format_money(balance)))
+    }
+
+    /// 14 位读者状态，按普通副本的流通规则判断借阅限制
+    async fn patron_flags(&self, patron: &users::Model, balance: i64) -> Result<String, Error> {
+        let policy =
+            Policy::find_policy(self.conn, &patron.patron_category, DEFAULT_CATEGORY).await?;
+        let loans =
+            Query::find_borrowed_books_by_user_id(self.conn, patron.id, LoanState::Active).await?;
+        let today = now().date();
+        let calendar = Calendar::load(self.conn).await?;
+        let too_many_loans = loans.len() >= policy.max_loans as usize;
+        let overdue = policy.overdue_blocks
+            && loans
+                .iter()
+                .filter(|loan| loan.status == LoanStatus::OnLoan)
+                .any(|loan| Policy::is_overdue(&policy, loan, now(), &calendar));
+        let fines = balance > policy.max_balance as i64;
+        let blocked = Query::find_active_user_block(self.conn, patron.id, today)
+            .await?
+            .is_some();
+        let denied = too_many_loans || overdue || fines || blocked;
+        let mut flags = [' '; 14];
+        let mut set = |index: usize, value: bool| {
+            if value {
+                flags[index] = 'Y';
+            }
+        };
+        set(0, denied);
+        set(1, overdue || fines || blocked);
+        set(3, blocked);
+        set(5, too_many_loans);
+        set(6, overdue);
+        set(10, fines);
+        Ok(flags.iter().collect())
+    }
+
+    async fn item_information(&self, request: &Request) -> Result<Response, Error> {
+        let barcode = request.field_or_empty("AB");
+        let Some(item) = Query::find_book_item_by_barcode(self.conn, barcode).await? else {
+            return Ok(Response::new("18")
+                .fixed("010001")
+                .fixed(format_date(now()))
+                .field("AB", barcode)
+                .field("AJ", "")
+                .field("AF", "没有这个条码"));
+        };
+        let circulation_status = match item.status {
+            ItemStatus::Available => "03",
+            ItemStatus::Borrowed => "04",
+            ItemStatus::OnHoldShelf => "08",
+            ItemStatus::ClaimedReturned => "11",
+            ItemStatus::Lost => "12",
+            ItemStatus::Withdrawn | ItemStatus::Damaged => "01",
+        };
+        let due_date = self
+            .active_loan(&item)
+            .await?
+            .map(|loan| loan.return_date.to_string());
+        Ok(Response::new("18")
+            .fixed(circulation_status)
+            .fixed("0001")
+            .fixed(format_date(now()))
+            .field("AB", &item.barcode)
+            .field("AJ", self.book_name(item.book_id).await?)
+            .optional_field("AH", due_date)
+            .field("AQ", &item.shelf_location))
+    }
+
+    async fn checkout(&self, request: &Request) -> Result<Response, Error> {
+        let patron_id = request.field_or_empty("AA");
+        let barcode = request.field_or_empty("AB");
+        let response = |ok: bool| {
+            Response::new("12")
+                .fixed(if ok { "1" } else { "0" })
+                .fixed("N")
+                .fixed("U")
+                .fixed(yes_no(ok).to_string())
+                .fixed(format_date(now()))
+                .field("AO", &self.config.institution)
+                .field("AA", patron_id)
+                .field("AB", barcode)
+        };
+        let patron = match self.verify_patron(request).await? {
+            Ok(patron) => patron,
+            Err(message) => return Ok(response(false).field("AJ", "").field("AF", message)),
+        };
+        let Some(item) = Query::find_book_item_by_barcode(self.conn, barcode).await? else {
+            return Ok(response(false).field("AJ", "").field("AF", "没有这个条码"));
+        };
+        let book_name = self.book_name(item.book_id).await?;
+        let calendar = Calendar::load(self.conn).await?;
+        match borrow_book(
+            self.conn,
+            self.staff_id(),
+            patron,
+            item.book_id,
+            Some(item.barcode),
+            None,
+            now(),
+            &calendar,
+        )
+        .await
+        {
+            Ok(loan) => Ok(response(true)
+                .field("AJ", book_name)
+                .field("AH", format_due(&loan))),
+            Err(BorrowError::Err(message)) => {
+                Ok(response(false).field("AJ", book_name).field("AF", message))
+            }
+            Err(BorrowError::DatabaseError(err)) => Err(err.into()),
+        }
+    }
+
+    async fn checkin(&self, request: &Request) -> Result<Response, Error> {
+        let barcode = request.field_or_empty("AB");
+        let response = |ok: bool, alert: bool| {
+            Response::new("10")
+                .fixed(if ok { "1" } else { "0" })
+                .fixed(yes_no(ok && !alert).to_string())
+                .fixed("U")
+                .fixed(yes_no(alert).to_string())
+                .fixed(format_date(now()))
+                .field("AO", &self.config.institution)
+                .field("AB", barcode)
+        };
+        let Some(item) = Query::find_book_item_by_barcode(self.conn, barcode).await? else {
+            return Ok(response(false, false).field("AQ", "").field("AF", "没有这个条码"));
+        };
+        let book_name = self.book_name(item.book_id).await?;
+        let Some(loan) = self.active_loan(&item).await? else {
+            return Ok(response(false, false)
+                .field("AQ", &item.shelf_location)
+                .field("AJ", book_name)
+                .field("AF", "该副本未借出"));
+        };
+        let patron = Query::find_user_by_id(self.conn, loan.user_id)
+            .await?
+            .map(|patron| patron.name)
+            .unwrap_or_default();
+        let calendar = Calendar::load(self.conn).await?;
+        let fine = match return_book(self.conn, loan.id, self.staff_id(), now(), &calendar).await {
+            Ok(fine) => fine,
+            Err(Error::Other(message)) => {
+                return Ok(response(false, false)
+                    .field("AQ", &item.shelf_location)
+                    .field("AJ", book_name)
+                    .field("AF", message))
+            }
+            Err(err) => return Err(err),
+        };
+        // 归还后转为预约保留的副本需要工作人员放到预约架
+        let on_hold_shelf = Query::find_book_item_by_id(self.conn, item.id)
+            .await?
+            .is_some_and(|item| item.status == ItemStatus::OnHoldShelf);
+        let message = match fine {
+            Some(fine) => format!("归还成功，逾期罚款 {} 元", format_money(fine.amount as i64)),
+            None => "归还成功".to_owned(),
+        };
+        let response = response(true, on_hold_shelf)
+            .field("AQ", &item.shelf_location)
+            .field("AJ", book_name)
+            .field("AA", patron);
+        Ok(if on_hold_shelf {
+            response
+                .field("CV", "01")
+                .field("AF", format!("{message}，该书已有读者预约，请交给工作人员"))
+        } else {
+            response.field("AF", message)
+        })
+    }
+
+    async fn renew(&self, request: &Request) -> Result<Response, Error> {
+        let patron_id = request.field_or_empty("AA");
+        let barcode = request.field_or_empty("AB");
+        let response = |ok: bool| {
+            Response::new("30")
+                .fixed(if ok { "1" } else { "0" })
+                .fixed(yes_no(ok).to_string())
+                .fixed("U")
+                .fixed("N")
+                .fixed(format_date(now()))
+                .field("AO", &self.config.institution)
+                .field("AA", patron_id)
+                .field("AB", barcode)
+        };
+        let patron = match self.verify_patron(request).await? {
+            Ok(patron) => patron,
+            Err(message) => return Ok(response(false).field("AJ", "").field("AF", message)),
+        };
+        let Some(item) = Query::find_book_item_by_barcode(self.conn, barcode).await? else {
+            return Ok(response(false).field("AJ", "").field("AF", "没有这个条码"));
+        };
+        let book_name = self.book_name(item.book_id).await?;
+        let loan = match self.active_loan(&item).await? {
+            Some(loan) if loan.user_id == patron.id => loan,
+            _ => {
+                return Ok(response(false)
+                    .field("AJ", book_name)
+                    .field("AF", "该读者没有借阅这本书"))
+            }
+        };
+        Ok(match renew_loan(self.conn, &loan, self.staff_id()).await? {
+            Ok(loan) => response(true)
+                .field("AJ", book_name)
+                .field("AH", format_due(&loan)),
+            Err(rejection) => response(false)
+                .field("AJ", book_name)
+                .field("AF", format!("续借失败：{rejection}")),
+        })
+    }
+
+    /// 查找 `AA` 对应的读者，请求带有读者密码（`AD`）时一并校验
+    async fn verify_patron(
+        &self,
+        request: &Request,
+    ) -> Result<Result<users::Model, &'static str>, Error> {
+        let Some(patron) = Query::find_user_by_card(self.conn, request.field_or_empty("AA")).await?
+        else {
+            return Ok(Err("读者不存在"));
+        };
+        match request.field("AD").filter(|password| !password.is_empty()) {
+            Some(password) if !bcrypt::verify(password, &patron.password_hash)? => {
+                Ok(Err("读者密码错误"))
+            }
+            _ => Ok(Ok(patron)),
+        }
+    }
+
+    async fn active_loan(
+        &self,
+        item: &book_items::Model,
+    ) -> Result<Option<borrowed_books::Model>, Error> {
+        Ok(Query::find_borrowed_books_by_item_id(self.conn, item.id, LoanState::Active)
+            .await?
+            .pop())
+    }
+
+    async fn book_name(&self, book_id: i32) -> Result<String, Error> {
+        Ok(Query::find_book_by_id(self.conn, book_id)
+            .await?
+            .map(|book| book.name)
+            .unwrap_or_default())
+    }
+
+    /// 只有登录后才会处理流通请求
+    fn staff_id(&self) -> i32 {
+        self.staff_id.unwrap_or_default()
+    }
+}
+
+fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
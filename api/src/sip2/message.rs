@@ -0,0 +1,160 @@
+//! SIP2 报文的解析与生成，字段以 `|` 结尾，可选的 `AY` 序号与 `AZ` 校验和位于报文末尾。
+
+use chrono::NaiveDateTime;
+
+/// SIP2 规定的日期格式，时区留空
+const DATE_FORMAT: &str = "%Y%m%d    %H%M%S";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub code: String,
+    pub fixed: String,
+    pub fields: Vec<(String, String)>,
+    /// `AY` 序号，请求带有校验时回复也带上
+    pub sequence: Option<char>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// 校验和不正确，需要请对方重发
+    Checksum,
+    /// 报文过短或不是已知的格式
+    Malformed,
+}
+
+impl Request {
+    pub fn field(&self, code: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == code)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 缺少的字段按空字符串处理
+    pub fn field_or_empty(&self, code: &str) -> &str {
+        self.field(code).unwrap_or_default()
+    }
+}
+
+/// 各类请求定长部分的长度
+fn fixed_len(code: &str) -> Option<usize> {
+    Some(match code {
+        "93" => 2,
+        "99" => 8,
+        "23" => 21,
+        "17" => 18,
+        "11" => 38,
+        "09" => 37,
+        "29" => 38,
+        "97" => 0,
+        _ => return None,
+    })
+}
+
+pub fn parse(message: &str) -> Result<Request, ParseError> {
+    let message = message.trim_end_matches(['\r', '\n']);
+    let (body, sequence) = match split_error_detection(message) {
+        Some((body, sequence, checksum)) => {
+            if checksum_of(&message[..message.len() - 4]) != checksum.to_ascii_uppercase() {
+                return Err(ParseError::Checksum);
+            }
+            (body, Some(sequence))
+        }
+        None => (message, None),
+    };
+    let code = body.get(..2).ok_or(ParseError::Malformed)?;
+    let len = fixed_len(code).ok_or(ParseError::Malformed)?;
+    let fixed = body.get(2..2 + len).ok_or(ParseError::Malformed)?;
+    let fields = body[2 + len..]
+        .split('|')
+        .filter(|field| field.len() >= 2 && field.is_char_boundary(2))
+        .map(|field| (field[..2].to_owned(), field[2..].to_owned()))
+        .collect();
+    Ok(Request {
+        code: code.to_owned(),
+        fixed: fixed.to_owned(),
+        fields,
+        sequence,
+    })
+}
+
+/// 拆出末尾的 `AY<序号>AZ<校验和>`，返回去掉这部分的报文
+fn split_error_detection(message: &str) -> Option<(&str, char, &str)> {
+    let bytes = message.as_bytes();
+    let len = bytes.len();
+    if len < 9 || &bytes[len - 9..len - 7] != b"AY" || &bytes[len - 6..len - 4] != b"AZ" {
+        return None;
+    }
+    let sequence = bytes[len - 7] as char;
+    sequence
+        .is_ascii_digit()
+        .then(|| (&message[..len - 9], sequence, &message[len - 4..]))
+}
+
+/// 报文所有字节之和取反加一，取低 16 位
+pub fn checksum_of(message: &str) -> String {
+    let sum = message
+        .bytes()
+        .fold(0u16, |sum, byte| sum.wrapping_add(byte as u16));
+    format!("{:04X}", (!sum).wrapping_add(1))
+}
+
+pub fn format_date(date: NaiveDateTime) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+pub fn yes_no(value: bool) -> char {
+    if value {
+        'Y'
+    } else {
+        'N'
+    }
+}
+
+/// 生成回复报文
+#[derive(Debug, Clone)]
+pub struct Response {
+    message: String,
+}
+
+impl Response {
+    pub fn new(code: &str) -> Self {
+        Self {
+            message: code.to_owned(),
+        }
+    }
+
+    pub fn fixed(mut self, value: impl AsRef<str>) -> Self {
+        self.message.push_str(value.as_ref());
+        self
+    }
+
+    /// 字段中的 `|` 会破坏报文结构，替换为空格
+    pub fn field(mut self, code: &str, value: impl AsRef<str>) -> Self {
+        self.message.push_str(code);
+        self.message.push_str(&value.as_ref().replace('|', " "));
+        self.message.push('|');
+        self
+    }
+
+    pub fn optional_field(self, code: &str, value: Option<impl AsRef<str>>) -> Self {
+        match value {
+            Some(value) => self.field(code, value),
+            None => self,
+        }
+    }
+
+    /// 请求带有序号时附上序号与校验和，最后加上报文结束符
+    pub fn finish(self, sequence: Option<char>) -> String {
+        let mut message = self.message;
+        if let Some(sequence) = sequence {
+            message.push_str("AY");
+            message.push(sequence);
+            message.push_str("AZ");
+            let checksum = checksum_of(&message);
+            message.push_str(&checksum);
+        }
+        message.push('\r');
+        message
+    }
+}
@@ -0,0 +1,73 @@
+//! 供自助借还机使用的 SIP2 服务，设置 `SIP2_ADDR` 与 `SIP2_PASSWORD` 后启动。
+//! 借还机须先用配置的借还机账号登录（93），该账号只能办理流通，不能登录网页。
+//! 之后的借出、归还、续借与网页端共用同一套流通逻辑，经办人记为第一位管理员。
+
+mod connection;
+pub mod message;
+
+use std::env;
+
+use actix_web::rt::net::TcpListener;
+use book_manager_service::sea_orm::DatabaseConnection;
+
+#[derive(Debug, Clone)]
+pub struct Sip2Config {
+    /// 监听地址
+    pub addr: String,
+    /// 回复中的机构编号（AO）
+    pub institution: String,
+    /// 借还机登录（93）使用的账号与密码，与网页账号无关
+    pub login_user: String,
+    pub login_password: String,
+}
+
+impl Sip2Config {
+    /// 未设置 `SIP2_ADDR` 时不启动 SIP2 服务；未设置 `SIP2_PASSWORD` 时借还机无法登录，
+    /// 同样不启动
+    pub fn from_env() -> Option<Self> {
+        let addr = env::var("SIP2_ADDR").ok().filter(|addr| !addr.trim().is_empty())?;
+        let institution = env::var("SIP2_INSTITUTION").unwrap_or_else(|_| "BookManager".to_owned());
+        let login_user = env::var("SIP2_USER").unwrap_or_else(|_| "kiosk".to_owned());
+        let login_password = env::var("SIP2_PASSWORD").unwrap_or_default();
+        if login_password.is_empty() {
+            tracing::error!("SIP2_PASSWORD is not set, SIP2 server at {addr} is not started");
+            return None;
+        }
+        Some(Self {
+            addr,
+            institution,
+            login_user,
+            login_password,
+        })
+    }
+}
+
+pub fn spawn(conn: DatabaseConnection, config: Sip2Config) {
+    actix_web::rt::spawn(async move {
+        match TcpListener::bind(&config.addr).await {
+            Ok(listener) => {
+                println!("Starting SIP2 server at {}", config.addr);
+                serve(listener, conn, config).await;
+            }
+            Err(err) => tracing::error!("failed to bind SIP2 server at {}: {err}", config.addr),
+        }
+    });
+}
+
+/// 每个借还机连接单独处理
+pub async fn serve(listener: TcpListener, conn: DatabaseConnection, config: Sip2Config) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let conn = conn.clone();
+                let config = config.clone();
+                actix_web::rt::spawn(async move {
+                    if let Err(err) = connection::handle(stream, &conn, &config).await {
+                        tracing::error!("SIP2 connection from {peer} failed: {err}");
+                    }
+                });
+            }
+            Err(err) => tracing::error!("failed to accept SIP2 connection: {err}"),
+        }
+    }
+}
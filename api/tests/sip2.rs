@@ -0,0 +1,221 @@
+use actix_web::rt::net::{TcpListener, TcpStream};
+use book_manager_api::sip2::{
+    self,
+    message::{checksum_of, format_date},
+    Sip2Config,
+};
+use book_manager_service::{
+    sea_orm::{Database, DatabaseConnection},
+    Mutation,
+};
+use entity::{books, AccessPermission};
+use migration::{Migrator, MigratorTrait};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+struct Library {
+    addr: String,
+    barcode: String,
+}
+
+/// 在临时 SQLite 数据库中准备借还机账号、读者和一本书，在随机端口启动 SIP2 服务
+async fn start_library(name: &str) -> Library {
+    let path = std::env::temp_dir().join(format!("sip2-{name}-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let conn = Database::connect(format!("sqlite://{}?mode=rwc", path.display()))
+        .await
+        .unwrap();
+    Migrator::up(&conn, None).await.unwrap();
+    create_user(&conn, "admin", AccessPermission::Admin).await;
+    create_user(&conn, "reader", AccessPermission::User).await;
+    let book = Mutation::create_book(
+        &conn,
+        books::Model {
+            id: 0,
+            name: "三体".to_owned(),
+            author: "刘慈欣".to_owned(),
+            publisher: "重庆出版社".to_owned(),
+            publish_year: chrono::NaiveDate::from_ymd_opt(2008, 1, 1).unwrap(),
+            isbn: "9787536692930".to_owned(),
+            call_number: String::new(),
+        },
+    )
+    .await
+    .unwrap();
+    let item = Mutation::create_book_items(&conn, book.id, 1, "A1".to_owned())
+        .await
+        .unwrap()
+        .remove(0);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let config = Sip2Config {
+        addr: addr.clone(),
+        institution: "BookManager".to_owned(),
+        login_user: "kiosk".to_owned(),
+        login_password: "123456".to_owned(),
+    };
+    actix_web::rt::spawn(sip2::serve(listener, conn, config));
+    Library {
+        addr,
+        barcode: item.barcode,
+    }
+}
+
+async fn create_user(conn: &DatabaseConnection, name: &str, permission: AccessPermission) {
+    let password_hash = bcrypt::hash("123456", 4).unwrap();
+    Mutation::create_user(conn, name.to_owned(), name.to_owned(), password_hash, permission)
+        .await
+        .unwrap();
+}
+
+struct Client {
+    stream: BufReader<TcpStream>,
+    sequence: u32,
+}
+
+impl Client {
+    async fn connect(library: &Library) -> Self {
+        Self {
+            stream: BufReader::new(TcpStream::connect(&library.addr).await.unwrap()),
+            sequence: 0,
+        }
+    }
+
+    /// 附上序号与校验和发送，并检查回复的校验和
+    async fn send(&mut self, body: &str) -> String {
+        let mut message = format!("{body}AY{}AZ", self.sequence % 10);
+        message.push_str(&checksum_of(&message));
+        self.sequence += 1;
+        let response = self.send_raw(&message).await;
+        let (checked, checksum) = response.split_at(response.len() - 4);
+        assert_eq!(checksum_of(checked), checksum, "bad checksum in {response}");
+        response
+    }
+
+    async fn send_raw(&mut self, message: &str) -> String {
+        self.stream
+            .write_all(format!("{message}\r").as_bytes())
+            .await
+            .unwrap();
+        let mut buf = Vec::new();
+        self.stream.read_until(b'\r', &mut buf).await.unwrap();
+        String::from_utf8(buf).unwrap().trim_end().to_owned()
+    }
+
+    async fn login(&mut self, password: &str) -> String {
+        self.send(&format!("9300CNkiosk|CO{password}|CPLobby|")).await
+    }
+}
+
+fn now() -> String {
+    format_date(chrono::Local::now().naive_local())
+}
+
+#[actix_rt::test]
+async fn login_is_required() {
+    let library = start_library("login").await;
+    let mut client = Client::connect(&library).await;
+    assert!(client.login("wrong").await.starts_with("940"));
+    assert!(client.login("123456").await.starts_with("941"));
+    assert!(client.send("9900302.00").await.starts_with("98YYYYNN"));
+
+    // 网页账号不能登录借还机
+    let mut client = Client::connect(&library).await;
+    assert!(client.send("9300CNadmin|CO123456|CPLobby|").await.starts_with("940"));
+
+    // 未登录时的流通请求直接断开连接
+    let mut client = Client::connect(&library).await;
+    let response = client
+        .send_raw(&format!("23000{}AOBookManager|AAreader|AC|", now()))
+        .await;
+    assert!(response.is_empty());
+}
+
+#[actix_rt::test]
+async fn bad_checksum_requests_resend() {
+    let library = start_library("checksum").await;
+    let mut client = Client::connect(&library).await;
+    assert!(client.login("123456").await.starts_with("941"));
+    assert_eq!(client.send_raw("9900302.00AY1AZ0000").await, "96");
+    // 重发上一次的回复
+    assert!(client.send_raw("97").await.starts_with("941"));
+}
+
+#[actix_rt::test]
+async fn checkout_renew_and_checkin() {
+    let library = start_library("circulation").await;
+    let barcode = &library.barcode;
+    let mut client = Client::connect(&library).await;
+    assert!(client.login("123456").await.starts_with("941"));
+
+    let response = client
+        .send(&format!("23019{}AOBookManager|AAreader|AC|AD123456|", now()))
+        .await;
+    assert!(response.starts_with(&format!("24{}019", " ".repeat(14))));
+    assert!(response.contains("|BLY|CQY|"));
+
+    let response = client
+        .send(&format!("17{}AOBookManager|AB{barcode}|AC|", now()))
+        .await;
+    assert!(response.starts_with("1803"));
+    assert!(response.contains("|AJ三体|"));
+
+    // 读者密码错误时拒绝借出
+    let response = client
+        .send(&format!("11YN{}{}AOBookManager|AAreader|AB{barcode}|AC|ADwrong|", now(), now()))
+        .await;
+    assert!(response.starts_with("120"));
+
+    let response = client
+        .send(&format!("11YN{}{}AOBookManager|AAreader|AB{barcode}|AC|AD123456|", now(), now()))
+        .await;
+    assert!(response.starts_with("121NUY"));
+    assert!(response.contains("|AH"));
+
+    let response = client
+        .send(&format!("17{}AOBookManager|AB{barcode}|AC|", now()))
+        .await;
+    assert!(response.starts_with("1804"));
+
+    // 只能由借阅人续借
+    let response = client
+        .send(&format!("29NN{}{}AOBookManager|AAkiosk|AB{barcode}|AC|", now(), now()))
+        .await;
+    assert!(response.starts_with("300N"));
+    let response = client
+        .send(&format!("29NN{}{}AOBookManager|AAreader|AB{barcode}|AC|", now(), now()))
+        .await;
+    assert!(response.starts_with("301Y"));
+
+    let response = client
+        .send(&format!("09N{}{}APLobby|AOBookManager|AB{barcode}|AC|", now(), now()))
+        .await;
+    assert!(response.starts_with("101YUN"));
+    assert!(response.contains("|AAreader|"));
+
+    let response = client
+        .send(&format!("09N{}{}APLobby|AOBookManager|AB{barcode}|AC|", now(), now()))
+        .await;
+    assert!(response.starts_with("100"));
+}
+
+#[actix_rt::test]
+async fn repeated_login_failures_disconnect() {
+    let library = start_library("failures").await;
+    let mut client = Client::connect(&library).await;
+    for _ in 0..3 {
+        assert!(client.login("wrong").await.starts_with("940"));
+    }
+    assert!(client.send_raw("9300CNkiosk|CO123456|CPLobby|").await.is_empty());
+}
+
+#[actix_rt::test]
+async fn oversized_message_disconnects() {
+    let library = start_library("oversized").await;
+    let mut stream = BufReader::new(TcpStream::connect(&library.addr).await.unwrap());
+    // 服务端读到上限即断开，不等报文结束
+    let _ = stream.write_all("0".repeat(10_000).as_bytes()).await;
+    let mut buf = Vec::new();
+    let read = stream.read_until(b'\r', &mut buf).await.unwrap_or(0);
+    assert_eq!(read, 0);
+}
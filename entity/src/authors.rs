@@ -0,0 +1,26 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// 著者，同名视为同一人。图书通过 `book_contributors` 关联著者及其职责
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "authors")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::book_contributors::Entity")]
+    BookContributors,
+}
+
+impl Related<super::book_contributors::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookContributors.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
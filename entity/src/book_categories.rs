@@ -0,0 +1,46 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// 图书的主题类目，一本书可以归入多个类目
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "book_categories")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub book_id: i32,
+    pub category_id: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::books::Entity",
+        from = "Column::BookId",
+        to = "super::books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Books,
+    #[sea_orm(
+        belongs_to = "super::categories::Entity",
+        from = "Column::CategoryId",
+        to = "super::categories::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Categories,
+}
+
+impl Related<super::books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Books.def()
+    }
+}
+
+impl Related<super::categories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Categories.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
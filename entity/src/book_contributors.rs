@@ -0,0 +1,50 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use crate::ContributorRole;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "book_contributors")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub book_id: i32,
+    pub author_id: i32,
+    pub role: ContributorRole,
+    /// 在著者行中的顺序，从 0 开始
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::books::Entity",
+        from = "Column::BookId",
+        to = "super::books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Books,
+    #[sea_orm(
+        belongs_to = "super::authors::Entity",
+        from = "Column::AuthorId",
+        to = "super::authors::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Authors,
+}
+
+impl Related<super::books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Books.def()
+    }
+}
+
+impl Related<super::authors::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Authors.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
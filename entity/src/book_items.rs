@@ -0,0 +1,50 @@
+use chrono::NaiveDate;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ItemCondition, ItemStatus};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "book_items")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    #[serde(skip_deserializing)]
+    pub book_id: i32,
+    #[sea_orm(unique)]
+    pub barcode: String,
+    pub shelf_location: String,
+    pub condition: ItemCondition,
+    pub acquisition_date: NaiveDate,
+    pub status: ItemStatus,
+    pub item_type: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::books::Entity",
+        from = "Column::BookId",
+        to = "super::books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Books,
+    #[sea_orm(has_many = "super::borrowed_books::Entity")]
+    BorrowedBooks,
+}
+
+impl Related<super::books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Books.def()
+    }
+}
+
+impl Related<super::borrowed_books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BorrowedBooks.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
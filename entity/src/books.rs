@@ -13,15 +13,41 @@ pub struct Model {
     pub publisher: String,
     pub publish_year: NaiveDate,
     pub isbn: String,
-    pub copies: i32,
+    /// 索书号，通常为分类号加书次号，例如 "TP312C/123"
+    #[serde(default)]
+    pub call_number: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::book_categories::Entity")]
+    BookCategories,
+    #[sea_orm(has_many = "super::book_contributors::Entity")]
+    BookContributors,
+    #[sea_orm(has_many = "super::book_items::Entity")]
+    BookItems,
     #[sea_orm(has_many = "super::borrowed_books::Entity")]
     BorrowedBooks,
 }
 
+impl Related<super::book_categories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookCategories.def()
+    }
+}
+
+impl Related<super::book_contributors::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookContributors.def()
+    }
+}
+
+impl Related<super::book_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookItems.def()
+    }
+}
+
 impl Related<super::borrowed_books::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::BorrowedBooks.def()
@@ -1,9 +1,11 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use sea_orm::entity::prelude::*;
 use serde::Serialize;
 
+use crate::LoanStatus;
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
 #[sea_orm(table_name = "borrowed_books")]
 pub struct Model {
@@ -11,8 +13,16 @@ pub struct Model {
     pub id: i32,
     pub user_id: i32,
     pub book_id: i32,
+    pub item_id: i32,
     pub borrow_date: NaiveDate,
     pub return_date: NaiveDate,
+    /// 借阅结束的时间，归还或登记遗失时记录
+    pub returned_at: Option<NaiveDateTime>,
+    pub returned_by: Option<i32>,
+    pub renewals: i32,
+    /// 课程参考书按小时计算借期时的应还时间
+    pub due_at: Option<NaiveDateTime>,
+    pub status: LoanStatus,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -25,6 +35,14 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     Books,
+    #[sea_orm(
+        belongs_to = "super::book_items::Entity",
+        from = "Column::ItemId",
+        to = "super::book_items::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Restrict"
+    )]
+    BookItems,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -33,6 +51,8 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     Users,
+    #[sea_orm(has_many = "super::loan_renewals::Entity")]
+    LoanRenewals,
 }
 
 impl Related<super::books::Entity> for Entity {
@@ -41,10 +61,22 @@ impl Related<super::books::Entity> for Entity {
     }
 }
 
+impl Related<super::book_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookItems.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
     }
 }
 
+impl Related<super::loan_renewals::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LoanRenewals.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
\ No newline at end of file
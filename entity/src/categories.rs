@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use crate::CategoryScheme;
+
+/// 分类法中的类目，例如中图法的 "TP312 程序设计语言"。同一分类法中类号唯一，
+/// 顶级类目没有上级
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "categories")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub scheme: CategoryScheme,
+    pub code: String,
+    pub name: String,
+    pub parent_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::book_categories::Entity")]
+    BookCategories,
+}
+
+impl Related<super::book_categories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookCategories.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
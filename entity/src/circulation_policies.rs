@@ -0,0 +1,27 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 流通规则，`patron_category` 与 `item_type` 为 `*` 时匹配任意值，金额以分为单位
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "circulation_policies")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub patron_category: String,
+    pub item_type: String,
+    pub max_loans: i32,
+    pub loan_days: i32,
+    pub max_renewals: i32,
+    pub renewal_days: i32,
+    pub grace_days: i32,
+    pub overdue_blocks: bool,
+    pub fine_per_day: i32,
+    pub max_fine: i32,
+    pub max_balance: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,19 @@
+use chrono::NaiveDate;
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// 节假日等闭馆日期，包含起止两天
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "closures")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
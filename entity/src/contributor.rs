@@ -0,0 +1,117 @@
+//! 著者行与结构化著者之间的转换。著者行即 `books.author` 中保存的显示文本，
+//! 例如 "刘慈欣 / 刘宇昆、张三 译"。
+
+use crate::ContributorRole;
+
+/// 职责说明，较长的写在前面以免 "主编" 被当作 "编"
+const ROLE_SUFFIXES: [(&str, ContributorRole); 12] = [
+    ("主编", ContributorRole::Editor),
+    ("编著", ContributorRole::Author),
+    ("合著", ContributorRole::Author),
+    ("编译", ContributorRole::Translator),
+    ("翻译", ContributorRole::Translator),
+    ("合译", ContributorRole::Translator),
+    ("绘图", ContributorRole::Illustrator),
+    ("插图", ContributorRole::Illustrator),
+    ("著", ContributorRole::Author),
+    ("译", ContributorRole::Translator),
+    ("编", ContributorRole::Editor),
+    ("绘", ContributorRole::Illustrator),
+];
+
+/// 拆分著者行。各段用 "/"、分号或逗号分隔，段末可带职责说明，
+/// 同一职责的多人在段内用 "、" 分隔。两侧都是西文的英文逗号视为
+/// "Hawking, Stephen" 这样的倒置姓名，不拆开
+pub fn parse_contributors(text: &str) -> Vec<(String, ContributorRole)> {
+    let mut segments: Vec<String> = Vec::new();
+    for segment in text.split(['/', ';', '；', '，']) {
+        let mut pieces = segment.split(',');
+        let mut current = pieces.next().unwrap_or_default().to_owned();
+        for piece in pieces {
+            if current.is_ascii() && piece.is_ascii() {
+                current.push(',');
+                current.push_str(piece);
+            } else {
+                segments.push(std::mem::replace(&mut current, piece.to_owned()));
+            }
+        }
+        segments.push(current);
+    }
+
+    let mut contributors = Vec::new();
+    for segment in &segments {
+        let segment = segment.trim();
+        let (names, role) = ROLE_SUFFIXES
+            .iter()
+            .find_map(|(suffix, role)| {
+                let names = segment.strip_suffix(suffix)?.trim_end();
+                (!names.is_empty()).then_some((names, *role))
+            })
+            .unwrap_or((segment, ContributorRole::Author));
+        for name in names.split('、').map(str::trim).filter(|name| !name.is_empty()) {
+            let contributor = (name.to_owned(), role);
+            if !contributors.contains(&contributor) {
+                contributors.push(contributor);
+            }
+        }
+    }
+    contributors
+}
+
+/// 生成著者行：著者各占一段，相邻的同一职责的其他人合为一段后加职责说明
+pub fn format_contributors(contributors: &[(String, ContributorRole)]) -> String {
+    let mut segments: Vec<(Vec<&str>, ContributorRole)> = Vec::new();
+    for (name, role) in contributors {
+        match segments.last_mut() {
+            Some((names, last)) if last == role && *role != ContributorRole::Author => {
+                names.push(name)
+            }
+            _ => segments.push((vec![name], *role)),
+        }
+    }
+    segments
+        .into_iter()
+        .map(|(names, role)| match role.suffix() {
+            "" => names.join("、"),
+            suffix => format!("{} {suffix}", names.join("、")),
+        })
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ContributorRole::*;
+
+    fn owned(contributors: &[(&str, ContributorRole)]) -> Vec<(String, ContributorRole)> {
+        contributors
+            .iter()
+            .map(|(name, role)| (name.to_string(), *role))
+            .collect()
+    }
+
+    #[test]
+    fn parses_roles() {
+        assert_eq!(
+            parse_contributors("刘慈欣, 刘宇昆、张三译；李四 主编"),
+            owned(&[("刘慈欣", Author), ("刘宇昆", Translator), ("张三", Translator), ("李四", Editor)]),
+        );
+    }
+
+    #[test]
+    fn keeps_inverted_names() {
+        assert_eq!(
+            parse_contributors("Hawking, Stephen / 许明贤，吴忠超 译"),
+            owned(&[("Hawking, Stephen", Author), ("许明贤", Author), ("吴忠超", Translator)]),
+        );
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        let line = "[美] Harold Abelson / [美] Gerald Jay Sussman / 裘宗燕 译";
+        assert_eq!(format_contributors(&parse_contributors(line)), line);
+        let line = "几米 / 张三、李四 译 / 王五 绘";
+        assert_eq!(format_contributors(&parse_contributors(line)), line);
+    }
+}
@@ -0,0 +1,37 @@
+use chrono::NaiveDate;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 课程参考书的课程，起止日期内列出的图书按课程的借期与续借次数流通
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "courses")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub teacher: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// 借期，单位为小时
+    pub loan_hours: i32,
+    pub max_renewals: i32,
+    /// 课程结束后由定时任务标记，不再影响借阅
+    #[serde(skip_deserializing)]
+    pub expired: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::course_reserves::Entity")]
+    CourseReserves,
+}
+
+impl Related<super::course_reserves::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CourseReserves.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
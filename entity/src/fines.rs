@@ -0,0 +1,60 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// 罚款记录，金额以分为单位
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "fines")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    #[sea_orm(unique)]
+    pub borrow_id: Option<i32>,
+    pub amount: i32,
+    pub created_at: NaiveDateTime,
+    pub assessed_on: NaiveDate,
+    pub note: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Restrict"
+    )]
+    Users,
+    #[sea_orm(
+        belongs_to = "super::borrowed_books::Entity",
+        from = "Column::BorrowId",
+        to = "super::borrowed_books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    BorrowedBooks,
+    #[sea_orm(has_many = "super::payments::Entity")]
+    Payments,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl Related<super::borrowed_books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BorrowedBooks.def()
+    }
+}
+
+impl Related<super::payments::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Payments.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,66 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use crate::HoldStatus;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "holds")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub book_id: i32,
+    pub user_id: i32,
+    pub created_at: NaiveDateTime,
+    pub status: HoldStatus,
+    pub item_id: Option<i32>,
+    pub pickup_deadline: Option<NaiveDate>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::books::Entity",
+        from = "Column::BookId",
+        to = "super::books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Books,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+    #[sea_orm(
+        belongs_to = "super::book_items::Entity",
+        from = "Column::ItemId",
+        to = "super::book_items::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    BookItems,
+}
+
+impl Related<super::books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Books.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl Related<super::book_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookItems.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
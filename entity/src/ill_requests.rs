@@ -0,0 +1,62 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use crate::IllStatus;
+
+/// 馆际互借申请，到馆后以临时副本借给申请的读者
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "ill_requests")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub author: String,
+    pub publisher: String,
+    pub publish_year: NaiveDate,
+    pub isbn: String,
+    pub note: String,
+    pub status: IllStatus,
+    /// 提供图书的合作馆
+    pub partner: String,
+    /// 到馆后建立的临时副本
+    pub item_id: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub updated_by: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+    #[sea_orm(
+        belongs_to = "super::book_items::Entity",
+        from = "Column::ItemId",
+        to = "super::book_items::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    BookItems,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl Related<super::book_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookItems.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,188 @@
+//! ISBN 校验与规范化。库中统一保存不带连字符的 13 位 ISBN，
+//! 这样 `978-7-...` 与 `9787...` 在唯一索引上是同一本书。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Isbn(String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IsbnError {
+    Empty,
+    /// 去掉连字符和空格后的位数
+    Length(usize),
+    Character(char),
+    /// 13 位 ISBN 只能以 978 或 979 开头
+    Prefix,
+    /// 正确的校验位
+    Checksum(char),
+}
+
+impl fmt::Display for IsbnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "ISBN 不能为空"),
+            Self::Length(len) => write!(f, "ISBN 应为 10 位或 13 位，实际为 {len} 位"),
+            Self::Character(c) => write!(f, "ISBN 含有无效字符“{c}”"),
+            Self::Prefix => write!(f, "13 位 ISBN 应以 978 或 979 开头"),
+            Self::Checksum(expected) => write!(f, "ISBN 校验位错误，应为 {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for IsbnError {}
+
+impl Isbn {
+    /// 接受带或不带连字符、空格和 "ISBN" 前缀的 10 位或 13 位 ISBN，10 位的转换为 13 位
+    pub fn parse(text: &str) -> Result<Self, IsbnError> {
+        let text = text.trim();
+        let text = text
+            .strip_prefix("ISBN")
+            .or_else(|| text.strip_prefix("isbn"))
+            .unwrap_or(text)
+            .trim_start_matches([':', '：', ' ']);
+        let chars: Vec<char> = text.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+        match chars.len() {
+            0 => Err(IsbnError::Empty),
+            10 => {
+                let digits = digits(&chars[..9])?;
+                let expected = isbn10_check(&digits);
+                match chars[9].to_ascii_uppercase() {
+                    c if c == expected => Ok(Self::from_digits978(&digits)),
+                    c if c == 'X' || c.is_ascii_digit() => Err(IsbnError::Checksum(expected)),
+                    c => Err(IsbnError::Character(c)),
+                }
+            }
+            13 => {
+                let digits = digits(&chars)?;
+                if digits[..3] != [9, 7, 8] && digits[..3] != [9, 7, 9] {
+                    return Err(IsbnError::Prefix);
+                }
+                let expected = isbn13_check(&digits[..12]);
+                if digits[12] != expected {
+                    return Err(IsbnError::Checksum(char::from(b'0' + expected)));
+                }
+                Ok(Self(chars.into_iter().collect()))
+            }
+            len => Err(IsbnError::Length(len)),
+        }
+    }
+
+    /// 9 位 ISBN-10 主体加上 978 前缀与新的校验位
+    fn from_digits978(body: &[u8]) -> Self {
+        let mut digits = vec![9, 7, 8];
+        digits.extend_from_slice(body);
+        digits.push(isbn13_check(&digits));
+        Self(digits.into_iter().map(|digit| char::from(b'0' + digit)).collect())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 978 开头的 ISBN 才有对应的 10 位形式
+    pub fn to_isbn10(&self) -> Option<String> {
+        let body = self.0.strip_prefix("978")?;
+        let digits: Vec<u8> = body[..9].bytes().map(|byte| byte - b'0').collect();
+        Some(format!("{}{}", &body[..9], isbn10_check(&digits)))
+    }
+}
+
+fn digits(chars: &[char]) -> Result<Vec<u8>, IsbnError> {
+    chars
+        .iter()
+        .map(|&c| c.to_digit(10).map(|digit| digit as u8).ok_or(IsbnError::Character(c)))
+        .collect()
+}
+
+/// 权重依次为 10 到 2，余数为 10 时校验位写作 X
+fn isbn10_check(digits: &[u8]) -> char {
+    let sum: u32 = digits
+        .iter()
+        .zip((2..=10).rev())
+        .map(|(&digit, weight)| u32::from(digit) * weight)
+        .sum();
+    match (11 - sum % 11) % 11 {
+        10 => 'X',
+        check => char::from(b'0' + check as u8),
+    }
+}
+
+/// 权重交替为 1 和 3
+fn isbn13_check(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .zip([1, 3].into_iter().cycle())
+        .map(|(&digit, weight)| u32::from(digit) * weight)
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+impl std::str::FromStr for Isbn {
+    type Err = IsbnError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+impl TryFrom<String> for Isbn {
+    type Error = IsbnError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        Self::parse(&text)
+    }
+}
+
+impl From<Isbn> for String {
+    fn from(isbn: Isbn) -> Self {
+        isbn.0
+    }
+}
+
+impl fmt::Display for Isbn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_hyphens_and_prefix() {
+        let isbn = Isbn::parse("ISBN 978-7-5357-3550-8").unwrap();
+        assert_eq!(isbn.as_str(), "9787535735508");
+        assert_eq!(Isbn::parse(" 978 7535735508 ").unwrap(), isbn);
+    }
+
+    #[test]
+    fn converts_isbn10() {
+        assert_eq!(Isbn::parse("7-5357-3550-9").unwrap().as_str(), "9787535735508");
+        // 校验位为 X 的 ISBN-10，小写也接受
+        let isbn = Isbn::parse("0-8044-2957-x").unwrap();
+        assert_eq!(isbn.as_str(), "9780804429573");
+        assert_eq!(isbn.to_isbn10().as_deref(), Some("080442957X"));
+    }
+
+    #[test]
+    fn isbn979_has_no_isbn10() {
+        let isbn = Isbn::parse("979-10-90636-07-1").unwrap();
+        assert_eq!(isbn.to_isbn10(), None);
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        assert_eq!(Isbn::parse("  "), Err(IsbnError::Empty));
+        assert_eq!(Isbn::parse("978753573550"), Err(IsbnError::Length(12)));
+        assert_eq!(Isbn::parse("978753573550A"), Err(IsbnError::Character('A')));
+        assert_eq!(Isbn::parse("9777535735508"), Err(IsbnError::Prefix));
+        assert_eq!(Isbn::parse("9787535735509"), Err(IsbnError::Checksum('8')));
+        assert_eq!(Isbn::parse("7535735503"), Err(IsbnError::Checksum('9')));
+        assert_eq!(Isbn::parse("753573550Y"), Err(IsbnError::Character('Y')));
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use crate::{IncidentKind, IncidentStatus};
+
+/// 副本遗失、声称已还与损坏的登记与处理记录
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "item_incidents")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub item_id: i32,
+    pub borrow_id: Option<i32>,
+    pub user_id: Option<i32>,
+    pub kind: IncidentKind,
+    pub status: IncidentStatus,
+    pub fine_id: Option<i32>,
+    pub note: String,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub resolved_at: Option<NaiveDateTime>,
+    pub resolved_by: Option<i32>,
+    pub resolution_note: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::book_items::Entity",
+        from = "Column::ItemId",
+        to = "super::book_items::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    BookItems,
+    #[sea_orm(
+        belongs_to = "super::borrowed_books::Entity",
+        from = "Column::BorrowId",
+        to = "super::borrowed_books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    BorrowedBooks,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Users,
+    #[sea_orm(
+        belongs_to = "super::fines::Entity",
+        from = "Column::FineId",
+        to = "super::fines::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Fines,
+}
+
+impl Related<super::book_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BookItems.def()
+    }
+}
+
+impl Related<super::borrowed_books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BorrowedBooks.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl Related<super::fines::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Fines.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,119 +1,474 @@
-use chrono::{NaiveDate, NaiveDateTime};
-use sea_orm::{FromQueryResult, DeriveActiveEnum, EnumIter};
-use serde::{Serialize, Deserialize};
-
-pub mod post;
-pub mod prelude;
-
-pub mod books;
-pub mod borrowed_books;
-pub mod emails;
-pub mod users;
-
-
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
-#[sea_orm(rs_type = "u8", db_type = "Integer")]
-pub enum AccessPermission {
-    Admin = 0,
-    User = 1,
-    Guest = 2,
-}
-
-impl AccessPermission {
-
-    pub fn is_admin(&self) -> bool {
-        matches!(self, Self::Admin)
-    }
-}
-
-
-#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
-#[sea_orm(rs_type = "u8", db_type = "Integer")]
-pub enum EmailCategory {
-    Regular = 0,
-    ToAdminBroadcast = 1,
-    ToUserBroadcast = 2,
-}
-
-#[derive(FromQueryResult, Serialize)]
-pub struct IdResult {
-    pub id: i32,
-}
-
-#[derive(FromQueryResult, Serialize)]
-pub struct BorrowedBooksResult {
-    pub borrow_id: i32,
-    pub user_name: String,
-    pub user_nickname: String,
-    // pub book_id: i32,
-    pub book_name: String,
-    // pub book_author: String,
-    pub isbn: String,
-    // pub user_id: i32,
-    pub borrow_date: NaiveDate,
-    pub return_date: NaiveDate,
-}
-
-#[derive(Debug, FromQueryResult, Serialize)]
-pub struct BorrowedBooksResultForBook {
-    pub borrow_id: i32,
-    pub book_id: i32,
-    pub book_name: String,
-    pub isbn: String,
-    pub book_author: String,
-    pub borrow_date: NaiveDate,
-    pub return_date: NaiveDate,
-}
-
-#[derive(FromQueryResult, Serialize)]
-pub struct BorrowedBooksResultForUser {
-    pub borrow_id: i32,
-    pub user_id: i32,
-    pub user_name: String,
-    pub user_nickname: String,
-    pub borrow_date: NaiveDate,
-    pub return_date: NaiveDate,
-}
-
-#[derive(FromQueryResult, Serialize, Clone)]
-pub struct Email {
-    pub id: i32,
-    pub category: EmailCategory,
-    pub sender_id: i32,
-    pub sender_name: String,
-    pub recipient_id: i32,
-    pub recipient_name: String,
-    pub subject: String,
-    pub content: String,
-    pub date_time: NaiveDateTime,
-    pub deleted_by_sender: bool,
-    pub deleted_by_recipient: bool,
-}
-
-impl Into<emails::Model> for Email {
-    fn into(self) -> emails::Model {
-        let Email {
-            id,
-            category,
-            sender_id,
-            recipient_id,
-            subject,
-            content,
-            date_time,
-            deleted_by_sender,
-            deleted_by_recipient,
-            ..
-        } = self;
-        emails::Model {
-            id,
-            category,
-            sender_id,
-            recipient_id,
-            subject,
-            content,
-            date_time,
-            deleted_by_sender,
-            deleted_by_recipient,
-        }
-    }
-}
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::{FromQueryResult, DeriveActiveEnum, EnumIter};
+use serde::{Serialize, Deserialize};
+
+pub mod post;
+pub mod prelude;
+pub mod contributor;
+pub mod isbn;
+
+pub use contributor::{format_contributors, parse_contributors};
+pub use isbn::{Isbn, IsbnError};
+
+pub mod authors;
+pub mod book_categories;
+pub mod book_contributors;
+pub mod book_items;
+pub mod books;
+pub mod borrowed_books;
+pub mod categories;
+pub mod circulation_policies;
+pub mod closures;
+pub mod course_reserves;
+pub mod courses;
+pub mod emails;
+pub mod fines;
+pub mod holds;
+pub mod ill_requests;
+pub mod item_incidents;
+pub mod job_runs;
+pub mod loan_notices;
+pub mod loan_renewals;
+pub mod opening_hours;
+pub mod payments;
+pub mod user_blocks;
+pub mod users;
+
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum AccessPermission {
+    Admin = 0,
+    User = 1,
+    Guest = 2,
+}
+
+impl AccessPermission {
+
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Self::Admin)
+    }
+}
+
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum EmailCategory {
+    Regular = 0,
+    ToAdminBroadcast = 1,
+    ToUserBroadcast = 2,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum ItemStatus {
+    Available = 0,
+    Borrowed = 1,
+    Withdrawn = 2,
+    OnHoldShelf = 3,
+    Lost = 4,
+    ClaimedReturned = 5,
+    Damaged = 6,
+}
+
+/// 借阅的状态：登记遗失后借阅结束，读者声称已还时借阅保留到登记处理为止
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum LoanStatus {
+    OnLoan = 0,
+    Returned = 1,
+    Lost = 2,
+    ClaimedReturned = 3,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum HoldStatus {
+    Waiting = 0,
+    Ready = 1,
+    Fulfilled = 2,
+    Expired = 3,
+    Cancelled = 4,
+}
+
+impl HoldStatus {
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Waiting | Self::Ready)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum JobStatus {
+    Running = 0,
+    Succeeded = 1,
+    Failed = 2,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum PaymentKind {
+    Payment = 0,
+    Waiver = 1,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum IncidentKind {
+    Lost = 0,
+    ClaimedReturned = 1,
+    Damaged = 2,
+}
+
+impl IncidentKind {
+    /// 登记后副本所处的状态
+    pub fn item_status(&self) -> ItemStatus {
+        match self {
+            Self::Lost => ItemStatus::Lost,
+            Self::ClaimedReturned => ItemStatus::ClaimedReturned,
+            Self::Damaged => ItemStatus::Damaged,
+        }
+    }
+}
+
+/// 处理结果：找回（损坏则为修复）后重新上架，赔偿或核销后副本注销
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum IncidentStatus {
+    Open = 0,
+    Found = 1,
+    Paid = 2,
+    WrittenOff = 3,
+}
+
+/// 馆际互借的进度：申请、已向合作馆订购、到馆、借出、已还合作馆，到馆前可以取消
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum IllStatus {
+    Requested = 0,
+    Ordered = 1,
+    Received = 2,
+    Loaned = 3,
+    Returned = 4,
+    Cancelled = 5,
+}
+
+impl IllStatus {
+    /// 按顺序推进到的下一个状态
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Self::Requested => Some(Self::Ordered),
+            Self::Ordered => Some(Self::Received),
+            Self::Received => Some(Self::Loaned),
+            Self::Loaned => Some(Self::Returned),
+            Self::Returned | Self::Cancelled => None,
+        }
+    }
+
+    pub fn is_cancellable(&self) -> bool {
+        matches!(self, Self::Requested | Self::Ordered)
+    }
+}
+
+/// 停借原因
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum BlockReason {
+    Conduct = 0,
+    UnpaidFines = 1,
+    LostItems = 2,
+    Other = 3,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum ItemCondition {
+    New = 0,
+    Good = 1,
+    Fair = 2,
+    Poor = 3,
+}
+
+/// 分类法：中国图书馆分类法与杜威十进分类法
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum CategoryScheme {
+    Clc = 0,
+    Dewey = 1,
+}
+
+/// 著者在图书中的职责，著录时写在姓名之后，例如 "刘宇昆 译"
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "u8", db_type = "Integer")]
+pub enum ContributorRole {
+    Author = 0,
+    Editor = 1,
+    Translator = 2,
+    Illustrator = 3,
+}
+
+impl ContributorRole {
+    /// 著者行中姓名后的职责说明，著者本人省略
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Self::Author => "",
+            Self::Editor => "编",
+            Self::Translator => "译",
+            Self::Illustrator => "绘",
+        }
+    }
+}
+
+/// 统计期间内借阅次数最多的图书
+#[derive(FromQueryResult, Serialize)]
+pub struct TopBooksResult {
+    pub book_id: i32,
+    pub name: String,
+    pub author: String,
+    pub loans: i64,
+}
+
+/// 统计期间内借阅次数最多的作者
+#[derive(FromQueryResult, Serialize)]
+pub struct TopAuthorsResult {
+    pub author: String,
+    pub loans: i64,
+}
+
+/// 按日或按月汇总的借阅与注册数量
+#[derive(Serialize)]
+pub struct PeriodCount {
+    pub period: String,
+    pub loans: i64,
+    pub registrations: i64,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct IdResult {
+    pub id: i32,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct BooksResult {
+    pub id: i32,
+    pub name: String,
+    pub author: String,
+    pub publisher: String,
+    pub publish_year: NaiveDate,
+    pub isbn: String,
+    pub call_number: String,
+    pub copies: i32,
+    pub available: i32,
+}
+
+/// 著者页中的图书及该著者在书中的职责
+#[derive(FromQueryResult, Serialize)]
+pub struct AuthorBooksResult {
+    pub id: i32,
+    pub name: String,
+    pub author: String,
+    pub publisher: String,
+    pub publish_year: NaiveDate,
+    pub isbn: String,
+    pub copies: i32,
+    pub available: i32,
+    pub role: ContributorRole,
+}
+
+/// 著者及其参与的图书种数
+#[derive(FromQueryResult, Serialize)]
+pub struct AuthorsResult {
+    pub id: i32,
+    pub name: String,
+    pub books: i64,
+}
+
+/// 图书的著者，按著者行中的顺序
+#[derive(FromQueryResult, Serialize)]
+pub struct ContributorsResult {
+    pub author_id: i32,
+    pub name: String,
+    pub role: ContributorRole,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct BorrowedBooksResult {
+    pub borrow_id: i32,
+    pub user_name: String,
+    pub user_nickname: String,
+    // pub book_id: i32,
+    pub book_name: String,
+    // pub book_author: String,
+    pub isbn: String,
+    pub barcode: String,
+    // pub user_id: i32,
+    pub borrow_date: NaiveDate,
+    pub return_date: NaiveDate,
+    pub due_at: Option<NaiveDateTime>,
+    pub renewals: i32,
+}
+
+#[derive(Debug, FromQueryResult, Serialize)]
+pub struct BorrowedBooksResultForBook {
+    pub borrow_id: i32,
+    pub book_id: i32,
+    pub book_name: String,
+    pub isbn: String,
+    pub book_author: String,
+    pub barcode: String,
+    pub borrow_date: NaiveDate,
+    pub return_date: NaiveDate,
+    pub due_at: Option<NaiveDateTime>,
+    pub renewals: i32,
+    pub status: LoanStatus,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct BorrowedBooksResultForUser {
+    pub borrow_id: i32,
+    pub user_id: i32,
+    pub user_name: String,
+    pub user_nickname: String,
+    pub barcode: String,
+    pub borrow_date: NaiveDate,
+    pub return_date: NaiveDate,
+    pub due_at: Option<NaiveDateTime>,
+    pub renewals: i32,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct LoanHistoryResult {
+    pub borrow_id: i32,
+    pub user_id: i32,
+    pub user_name: String,
+    pub user_nickname: String,
+    pub book_id: i32,
+    pub book_name: String,
+    pub isbn: String,
+    pub barcode: String,
+    pub borrow_date: NaiveDate,
+    pub return_date: NaiveDate,
+    pub returned_at: Option<NaiveDateTime>,
+    pub returned_by_name: Option<String>,
+    pub renewals: i32,
+    pub status: LoanStatus,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct LoanRenewalsResult {
+    pub borrow_id: i32,
+    pub renewed_at: NaiveDateTime,
+    pub renewed_by_name: Option<String>,
+    pub old_return_date: NaiveDate,
+    pub new_return_date: NaiveDate,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct FinesResult {
+    pub fine_id: i32,
+    pub borrow_id: Option<i32>,
+    pub book_name: Option<String>,
+    pub amount: i32,
+    pub waived: i32,
+    pub assessed_on: NaiveDate,
+    pub note: String,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct ItemIncidentsResult {
+    pub incident_id: i32,
+    pub kind: IncidentKind,
+    pub status: IncidentStatus,
+    pub item_id: i32,
+    pub barcode: String,
+    pub book_id: i32,
+    pub book_name: String,
+    pub user_id: Option<i32>,
+    pub user_name: Option<String>,
+    pub user_nickname: Option<String>,
+    pub borrow_id: Option<i32>,
+    pub fine_id: Option<i32>,
+    pub amount: Option<i32>,
+    pub note: String,
+    pub created_at: NaiveDateTime,
+    pub creator_name: Option<String>,
+    pub resolved_at: Option<NaiveDateTime>,
+    pub resolver_name: Option<String>,
+    pub resolution_note: String,
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct HoldsResult {
+    pub hold_id: i32,
+    pub book_id: i32,
+    pub book_name: String,
+    pub user_id: i32,
+    pub user_name: String,
+    pub user_nickname: String,
+    pub created_at: NaiveDateTime,
+    pub status: HoldStatus,
+    pub barcode: Option<String>,
+    pub pickup_deadline: Option<NaiveDate>,
+}
+
+#[derive(FromQueryResult, Serialize, Clone)]
+pub struct Email {
+    pub id: i32,
+    pub category: EmailCategory,
+    pub sender_id: i32,
+    pub sender_name: String,
+    pub recipient_id: i32,
+    pub recipient_name: String,
+    pub subject: String,
+    pub content: String,
+    pub date_time: NaiveDateTime,
+    pub deleted_by_sender: bool,
+    pub deleted_by_recipient: bool,
+}
+
+impl From<Email> for emails::Model {
+    fn from(email: Email) -> Self {
+        let Email {
+            id,
+            category,
+            sender_id,
+            recipient_id,
+            subject,
+            content,
+            date_time,
+            deleted_by_sender,
+            deleted_by_recipient,
+            ..
+        } = email;
+        emails::Model {
+            id,
+            category,
+            sender_id,
+            recipient_id,
+            subject,
+            content,
+            date_time,
+            deleted_by_sender,
+            deleted_by_recipient,
+        }
+    }
+}
+
+#[derive(FromQueryResult, Serialize)]
+pub struct IllRequestsResult {
+    pub request_id: i32,
+    pub user_id: i32,
+    pub user_name: String,
+    pub user_nickname: String,
+    pub name: String,
+    pub author: String,
+    pub isbn: String,
+    pub note: String,
+    pub status: IllStatus,
+    pub partner: String,
+    pub item_id: Option<i32>,
+    pub barcode: Option<String>,
+    pub book_id: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
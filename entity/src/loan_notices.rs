@@ -0,0 +1,34 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// 已发送的到期与逾期提醒，`offset_days` 为相对应还日期的天数，负数表示到期前
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "loan_notices")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub borrow_id: i32,
+    pub offset_days: i32,
+    pub sent_at: NaiveDateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::borrowed_books::Entity",
+        from = "Column::BorrowId",
+        to = "super::borrowed_books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    BorrowedBooks,
+}
+
+impl Related<super::borrowed_books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BorrowedBooks.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,49 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "loan_renewals")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub borrow_id: i32,
+    pub renewed_at: NaiveDateTime,
+    pub renewed_by: Option<i32>,
+    pub old_return_date: NaiveDate,
+    pub new_return_date: NaiveDate,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::borrowed_books::Entity",
+        from = "Column::BorrowId",
+        to = "super::borrowed_books::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    BorrowedBooks,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::RenewedBy",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Users,
+}
+
+impl Related<super::borrowed_books::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BorrowedBooks.def()
+    }
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,21 @@
+use chrono::NaiveTime;
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// 每周的开放时间，`weekday` 为 0 至 6，0 为周一
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "opening_hours")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    #[sea_orm(unique)]
+    pub weekday: i32,
+    pub open_time: NaiveTime,
+    pub close_time: NaiveTime,
+    pub closed: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,54 @@
+use chrono::NaiveDateTime;
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use crate::PaymentKind;
+
+/// 缴款与减免记录，金额以分为单位
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "payments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    pub fine_id: Option<i32>,
+    pub kind: PaymentKind,
+    pub amount: i32,
+    pub created_at: NaiveDateTime,
+    pub staff_id: Option<i32>,
+    pub note: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Restrict"
+    )]
+    Users,
+    #[sea_orm(
+        belongs_to = "super::fines::Entity",
+        from = "Column::FineId",
+        to = "super::fines::Column::Id",
+        on_update = "NoAction",
+        on_delete = "SetNull"
+    )]
+    Fines,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl Related<super::fines::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Fines.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
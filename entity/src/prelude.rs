@@ -1,5 +1,24 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
+pub use super::authors::Entity as Authors;
+pub use super::book_categories::Entity as BookCategories;
+pub use super::book_contributors::Entity as BookContributors;
+pub use super::book_items::Entity as BookItems;
 pub use super::books::Entity as Books;
 pub use super::borrowed_books::Entity as BorrowedBooks;
+pub use super::categories::Entity as Categories;
+pub use super::circulation_policies::Entity as CirculationPolicies;
+pub use super::closures::Entity as Closures;
+pub use super::course_reserves::Entity as CourseReserves;
+pub use super::courses::Entity as Courses;
+pub use super::fines::Entity as Fines;
+pub use super::holds::Entity as Holds;
+pub use super::ill_requests::Entity as IllRequests;
+pub use super::item_incidents::Entity as ItemIncidents;
+pub use super::job_runs::Entity as JobRuns;
+pub use super::loan_notices::Entity as LoanNotices;
+pub use super::loan_renewals::Entity as LoanRenewals;
+pub use super::opening_hours::Entity as OpeningHours;
+pub use super::payments::Entity as Payments;
+pub use super::user_blocks::Entity as UserBlocks;
 pub use super::users::Entity as Users;
@@ -0,0 +1,52 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+use crate::BlockReason;
+
+/// 读者的停借记录，解除后保留作为日志；`lifted_by` 为空表示到期自动解除
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize)]
+#[sea_orm(table_name = "user_blocks")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub user_id: i32,
+    pub reason: BlockReason,
+    pub note: String,
+    pub start_date: NaiveDate,
+    /// 为空时一直有效，直到手动解除
+    pub end_date: Option<NaiveDate>,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<i32>,
+    pub lifted_at: Option<NaiveDateTime>,
+    pub lifted_by: Option<i32>,
+}
+
+impl Model {
+    /// 未解除且 `today` 在停借期间内
+    pub fn is_active(&self, today: NaiveDate) -> bool {
+        self.lifted_at.is_none()
+            && self.start_date <= today
+            && self.end_date.is_none_or(|end_date| today <= end_date)
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::UserId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Users,
+}
+
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -16,6 +16,9 @@ pub struct Model {
     pub password_hash: String,
     pub permission: AccessPermission,
     pub registration_date: NaiveDate,
+    pub patron_category: String,
+    #[serde(skip_serializing)]
+    pub calendar_token: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,16 +1,45 @@
-pub use sea_orm_migration::prelude::*;
-
-mod versions;
-pub struct Migrator;
-
-#[async_trait::async_trait]
-impl MigratorTrait for Migrator {
-    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![
-            Box::new(versions::m001_create_books_table::Migration),
-            Box::new(versions::m002_create_users_table::Migration),
-            Box::new(versions::m003_create_borrowed_books_table::Migration),
-            Box::new(versions::m004_create_emails_table::Migration),
-        ]
-    }
-}
+pub use sea_orm_migration::prelude::*;
+
+mod versions;
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(versions::m001_create_books_table::Migration),
+            Box::new(versions::m002_create_users_table::Migration),
+            Box::new(versions::m003_create_borrowed_books_table::Migration),
+            Box::new(versions::m004_create_emails_table::Migration),
+            Box::new(versions::m006_create_book_items_table::Migration),
+            Box::new(versions::m007_migrate_book_copies_to_items::Migration),
+            Box::new(versions::m008_add_borrowed_books_return_fields::Migration),
+            Box::new(versions::m009_create_holds_table::Migration),
+            Box::new(versions::m010_add_borrowed_books_renewals::Migration),
+            Box::new(versions::m011_create_loan_renewals_table::Migration),
+            Box::new(versions::m012_add_patron_category_and_item_type::Migration),
+            Box::new(versions::m013_create_circulation_policies_table::Migration),
+            Box::new(versions::m014_add_circulation_policies_fine_fields::Migration),
+            Box::new(versions::m015_create_fines_table::Migration),
+            Box::new(versions::m016_create_payments_table::Migration),
+            Box::new(versions::m017_create_job_runs_table::Migration),
+            Box::new(versions::m018_create_loan_notices_table::Migration),
+            Box::new(versions::m019_create_opening_hours_table::Migration),
+            Box::new(versions::m020_create_closures_table::Migration),
+            Box::new(versions::m021_create_item_incidents_table::Migration),
+            Box::new(versions::m022_create_ill_requests_table::Migration),
+            Box::new(versions::m023_create_courses_table::Migration),
+            Box::new(versions::m024_create_course_reserves_table::Migration),
+            Box::new(versions::m025_add_borrowed_books_due_at::Migration),
+            Box::new(versions::m026_create_user_blocks_table::Migration),
+            Box::new(versions::m027_add_users_calendar_token::Migration),
+            Box::new(versions::m028_normalize_books_isbn::Migration),
+            Box::new(versions::m029_create_authors_table::Migration),
+            Box::new(versions::m030_create_book_contributors_table::Migration),
+            Box::new(versions::m031_migrate_book_authors_to_contributors::Migration),
+            Box::new(versions::m032_create_categories_table::Migration),
+            Box::new(versions::m033_create_book_categories_table::Migration),
+            Box::new(versions::m034_add_books_call_number::Migration),
+        ]
+    }
+}
@@ -50,4 +50,6 @@ pub(super) enum UserFields {
     PasswordHash,
     Permission,
     RegistrationDate,
+    PatronCategory,
+    CalendarToken,
 }
@@ -83,4 +83,10 @@ pub(super) enum BorrowedBookFields {
     BookId,
     BorrowDate,
     ReturnDate,
+    ItemId,
+    ReturnedAt,
+    ReturnedBy,
+    Renewals,
+    Status,
+    DueAt,
 }
@@ -0,0 +1,82 @@
+use super::m001_create_books_table::BookFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BookItemFields::BookItems)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BookItemFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BookItemFields::BookId).integer().not_null())
+                    .col(
+                        ColumnDef::new(BookItemFields::Barcode)
+                            .string()
+                            .unique_key()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BookItemFields::ShelfLocation)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(
+                        ColumnDef::new(BookItemFields::Condition)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(BookItemFields::AcquisitionDate)
+                            .date()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BookItemFields::Status)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_item_book_id")
+                            .from(BookItemFields::BookItems, BookItemFields::BookId)
+                            .to(BookFields::Books, BookFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BookItemFields::BookItems).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum BookItemFields {
+    BookItems,
+    Id,
+    BookId,
+    Barcode,
+    ShelfLocation,
+    Condition,
+    AcquisitionDate,
+    Status,
+    ItemType,
+}
@@ -0,0 +1,110 @@
+use super::{
+    m001_create_books_table::BookFields, m003_create_borrowed_books_table::BorrowedBookFields,
+};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 为每条借阅记录生成一个处于借出状态的副本，条码格式为 `图书ID(6位) + 序号(4位)`
+const CREATE_BORROWED_ITEMS: &str = r#"
+INSERT INTO "book_items" ("book_id", "barcode", "shelf_location", "condition", "acquisition_date", "status")
+SELECT "book_id",
+       printf('%06d%04d', "book_id", ROW_NUMBER() OVER (PARTITION BY "book_id" ORDER BY "id")),
+       '', 1, "borrow_date", 1
+FROM "borrowed_books"
+"#;
+
+const LINK_BORROWED_ITEMS: &str = r#"
+UPDATE "borrowed_books"
+SET "item_id" = (
+    SELECT "book_items"."id" FROM "book_items"
+    WHERE "book_items"."barcode" = printf('%06d%04d', "borrowed_books"."book_id", (
+        SELECT COUNT(*) FROM "borrowed_books" AS "b"
+        WHERE "b"."book_id" = "borrowed_books"."book_id" AND "b"."id" <= "borrowed_books"."id"
+    ))
+)
+"#;
+
+/// 剩余的库存数量转换为可借副本，序号接在已借出副本之后
+const CREATE_AVAILABLE_ITEMS: &str = r#"
+INSERT INTO "book_items" ("book_id", "barcode", "shelf_location", "condition", "acquisition_date", "status")
+WITH RECURSIVE "seq"("book_id", "n", "total") AS (
+    SELECT "id",
+           (SELECT COUNT(*) FROM "book_items" WHERE "book_items"."book_id" = "books"."id") + 1,
+           (SELECT COUNT(*) FROM "book_items" WHERE "book_items"."book_id" = "books"."id") + "copies"
+    FROM "books" WHERE "copies" > 0
+    UNION ALL
+    SELECT "book_id", "n" + 1, "total" FROM "seq" WHERE "n" < "total"
+)
+SELECT "book_id", printf('%06d%04d', "book_id", "n"), '', 1, date('now'), 0 FROM "seq"
+"#;
+
+const RESTORE_COPIES: &str = r#"
+UPDATE "books"
+SET "copies" = (
+    SELECT COUNT(*) FROM "book_items"
+    WHERE "book_items"."book_id" = "books"."id" AND "book_items"."status" = 0
+)
+"#;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .add_column(
+                        ColumnDef::new(BorrowedBookFields::ItemId)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared(CREATE_BORROWED_ITEMS).await?;
+        db.execute_unprepared(LINK_BORROWED_ITEMS).await?;
+        db.execute_unprepared(CREATE_AVAILABLE_ITEMS).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BookFields::Books)
+                    .drop_column(BookFields::Copies)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BookFields::Books)
+                    .add_column(
+                        ColumnDef::new(BookFields::Copies)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared(RESTORE_COPIES).await?;
+        db.execute_unprepared(r#"DELETE FROM "book_items""#).await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .drop_column(BorrowedBookFields::ItemId)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,50 @@
+use super::m003_create_borrowed_books_table::BorrowedBookFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .add_column(ColumnDef::new(BorrowedBookFields::ReturnedAt).date_time())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .add_column(ColumnDef::new(BorrowedBookFields::ReturnedBy).integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 已归还的记录在旧结构中不存在
+        manager
+            .get_connection()
+            .execute_unprepared(r#"DELETE FROM "borrowed_books" WHERE "returned_at" IS NOT NULL"#)
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .drop_column(BorrowedBookFields::ReturnedBy)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .drop_column(BorrowedBookFields::ReturnedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,79 @@
+use super::{
+    m001_create_books_table::BookFields, m002_create_users_table::UserFields,
+    m006_create_book_items_table::BookItemFields,
+};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(HoldFields::Holds)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(HoldFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(HoldFields::BookId).integer().not_null())
+                    .col(ColumnDef::new(HoldFields::UserId).integer().not_null())
+                    .col(ColumnDef::new(HoldFields::CreatedAt).date_time().not_null())
+                    .col(
+                        ColumnDef::new(HoldFields::Status)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(HoldFields::ItemId).integer())
+                    .col(ColumnDef::new(HoldFields::PickupDeadline).date())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_hold_book_id")
+                            .from(HoldFields::Holds, HoldFields::BookId)
+                            .to(BookFields::Books, BookFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_hold_user_id")
+                            .from(HoldFields::Holds, HoldFields::UserId)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_hold_item_id")
+                            .from(HoldFields::Holds, HoldFields::ItemId)
+                            .to(BookItemFields::BookItems, BookItemFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(HoldFields::Holds).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum HoldFields {
+    Holds,
+    Id,
+    BookId,
+    UserId,
+    CreatedAt,
+    Status,
+    ItemId,
+    PickupDeadline,
+}
@@ -0,0 +1,34 @@
+use super::m003_create_borrowed_books_table::BorrowedBookFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .add_column(
+                        ColumnDef::new(BorrowedBookFields::Renewals)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .drop_column(BorrowedBookFields::Renewals)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,64 @@
+use super::{
+    m002_create_users_table::UserFields, m003_create_borrowed_books_table::BorrowedBookFields,
+};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoanRenewalFields::LoanRenewals)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LoanRenewalFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LoanRenewalFields::BorrowId).integer().not_null())
+                    .col(ColumnDef::new(LoanRenewalFields::RenewedAt).date_time().not_null())
+                    .col(ColumnDef::new(LoanRenewalFields::RenewedBy).integer())
+                    .col(ColumnDef::new(LoanRenewalFields::OldReturnDate).date().not_null())
+                    .col(ColumnDef::new(LoanRenewalFields::NewReturnDate).date().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_renewal_borrow_id")
+                            .from(LoanRenewalFields::LoanRenewals, LoanRenewalFields::BorrowId)
+                            .to(BorrowedBookFields::BorrowedBooks, BorrowedBookFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_renewal_renewed_by")
+                            .from(LoanRenewalFields::LoanRenewals, LoanRenewalFields::RenewedBy)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoanRenewalFields::LoanRenewals).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum LoanRenewalFields {
+    LoanRenewals,
+    Id,
+    BorrowId,
+    RenewedAt,
+    RenewedBy,
+    OldReturnDate,
+    NewReturnDate,
+}
@@ -0,0 +1,55 @@
+use super::{m002_create_users_table::UserFields, m006_create_book_items_table::BookItemFields};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserFields::Users)
+                    .add_column(
+                        ColumnDef::new(UserFields::PatronCategory)
+                            .string()
+                            .not_null()
+                            .default("standard"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BookItemFields::BookItems)
+                    .add_column(
+                        ColumnDef::new(BookItemFields::ItemType)
+                            .string()
+                            .not_null()
+                            .default("standard"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BookItemFields::BookItems)
+                    .drop_column(BookItemFields::ItemType)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserFields::Users)
+                    .drop_column(UserFields::PatronCategory)
+                    .to_owned(),
+            )
+            .await
+    }
+}
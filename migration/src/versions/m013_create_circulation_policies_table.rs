@@ -0,0 +1,108 @@
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CirculationPolicyFields::CirculationPolicies)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CirculationPolicyFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CirculationPolicyFields::PatronCategory)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CirculationPolicyFields::ItemType).string().not_null())
+                    .col(ColumnDef::new(CirculationPolicyFields::MaxLoans).integer().not_null())
+                    .col(ColumnDef::new(CirculationPolicyFields::LoanDays).integer().not_null())
+                    .col(
+                        ColumnDef::new(CirculationPolicyFields::MaxRenewals)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CirculationPolicyFields::RenewalDays)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(CirculationPolicyFields::GraceDays).integer().not_null())
+                    .col(
+                        ColumnDef::new(CirculationPolicyFields::OverdueBlocks)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_policy_category_type")
+                            .col(CirculationPolicyFields::PatronCategory)
+                            .col(CirculationPolicyFields::ItemType)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // 默认规则与原先写死的限制一致
+        let insert = Query::insert()
+            .into_table(CirculationPolicyFields::CirculationPolicies)
+            .columns([
+                CirculationPolicyFields::PatronCategory,
+                CirculationPolicyFields::ItemType,
+                CirculationPolicyFields::MaxLoans,
+                CirculationPolicyFields::LoanDays,
+                CirculationPolicyFields::MaxRenewals,
+                CirculationPolicyFields::RenewalDays,
+                CirculationPolicyFields::GraceDays,
+                CirculationPolicyFields::OverdueBlocks,
+            ])
+            .values_panic([
+                "*".into(),
+                "*".into(),
+                8.into(),
+                30.into(),
+                2.into(),
+                30.into(),
+                0.into(),
+                true.into(),
+            ])
+            .to_owned();
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(CirculationPolicyFields::CirculationPolicies)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum CirculationPolicyFields {
+    CirculationPolicies,
+    Id,
+    PatronCategory,
+    ItemType,
+    MaxLoans,
+    LoanDays,
+    MaxRenewals,
+    RenewalDays,
+    GraceDays,
+    OverdueBlocks,
+    FinePerDay,
+    MaxFine,
+    MaxBalance,
+}
@@ -0,0 +1,44 @@
+use super::m013_create_circulation_policies_table::CirculationPolicyFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 金额均以分为单位
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (column, default) in [
+            (CirculationPolicyFields::FinePerDay, 10),
+            (CirculationPolicyFields::MaxFine, 1000),
+            (CirculationPolicyFields::MaxBalance, 500),
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CirculationPolicyFields::CirculationPolicies)
+                        .add_column(ColumnDef::new(column).integer().not_null().default(default))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            CirculationPolicyFields::MaxBalance,
+            CirculationPolicyFields::MaxFine,
+            CirculationPolicyFields::FinePerDay,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(CirculationPolicyFields::CirculationPolicies)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
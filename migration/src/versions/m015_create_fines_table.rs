@@ -0,0 +1,72 @@
+use super::{
+    m002_create_users_table::UserFields, m003_create_borrowed_books_table::BorrowedBookFields,
+};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FineFields::Fines)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(FineFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(FineFields::UserId).integer().not_null())
+                    .col(ColumnDef::new(FineFields::BorrowId).integer())
+                    .col(ColumnDef::new(FineFields::Amount).integer().not_null())
+                    .col(ColumnDef::new(FineFields::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(FineFields::AssessedOn).date().not_null())
+                    .col(ColumnDef::new(FineFields::Note).string().not_null().default(""))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_fine_user_id")
+                            .from(FineFields::Fines, FineFields::UserId)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_fine_borrow_id")
+                            .from(FineFields::Fines, FineFields::BorrowId)
+                            .to(BorrowedBookFields::BorrowedBooks, BorrowedBookFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_fine_borrow_id")
+                            .col(FineFields::BorrowId)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(FineFields::Fines).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum FineFields {
+    Fines,
+    Id,
+    UserId,
+    BorrowId,
+    Amount,
+    CreatedAt,
+    AssessedOn,
+    Note,
+}
@@ -0,0 +1,73 @@
+use super::{m002_create_users_table::UserFields, m015_create_fines_table::FineFields};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaymentFields::Payments)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaymentFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PaymentFields::UserId).integer().not_null())
+                    .col(ColumnDef::new(PaymentFields::FineId).integer())
+                    .col(ColumnDef::new(PaymentFields::Kind).integer().not_null())
+                    .col(ColumnDef::new(PaymentFields::Amount).integer().not_null())
+                    .col(ColumnDef::new(PaymentFields::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(PaymentFields::StaffId).integer())
+                    .col(ColumnDef::new(PaymentFields::Note).string().not_null().default(""))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payment_user_id")
+                            .from(PaymentFields::Payments, PaymentFields::UserId)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::Restrict),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payment_fine_id")
+                            .from(PaymentFields::Payments, PaymentFields::FineId)
+                            .to(FineFields::Fines, FineFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payment_staff_id")
+                            .from(PaymentFields::Payments, PaymentFields::StaffId)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaymentFields::Payments).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum PaymentFields {
+    Payments,
+    Id,
+    UserId,
+    FineId,
+    Kind,
+    Amount,
+    CreatedAt,
+    StaffId,
+    Note,
+}
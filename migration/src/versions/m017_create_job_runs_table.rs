@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(JobRunFields::JobRuns)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JobRunFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(JobRunFields::JobName).string().not_null())
+                    .col(ColumnDef::new(JobRunFields::StartedAt).date_time().not_null())
+                    .col(ColumnDef::new(JobRunFields::FinishedAt).date_time())
+                    .col(ColumnDef::new(JobRunFields::Status).integer().not_null())
+                    .col(
+                        ColumnDef::new(JobRunFields::Processed)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(JobRunFields::Message).string().not_null().default(""))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(JobRunFields::JobRuns).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum JobRunFields {
+    JobRuns,
+    Id,
+    JobName,
+    StartedAt,
+    FinishedAt,
+    Status,
+    Processed,
+    Message,
+}
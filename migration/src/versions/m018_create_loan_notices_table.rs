@@ -0,0 +1,58 @@
+use super::m003_create_borrowed_books_table::BorrowedBookFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoanNoticeFields::LoanNotices)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LoanNoticeFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(LoanNoticeFields::BorrowId).integer().not_null())
+                    .col(ColumnDef::new(LoanNoticeFields::OffsetDays).integer().not_null())
+                    .col(ColumnDef::new(LoanNoticeFields::SentAt).date_time().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notice_borrow_id")
+                            .from(LoanNoticeFields::LoanNotices, LoanNoticeFields::BorrowId)
+                            .to(BorrowedBookFields::BorrowedBooks, BorrowedBookFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_notice_borrow_offset")
+                            .col(LoanNoticeFields::BorrowId)
+                            .col(LoanNoticeFields::OffsetDays)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoanNoticeFields::LoanNotices).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum LoanNoticeFields {
+    LoanNotices,
+    Id,
+    BorrowId,
+    OffsetDays,
+    SentAt,
+}
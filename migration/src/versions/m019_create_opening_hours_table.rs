@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OpeningHourFields::OpeningHours)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OpeningHourFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OpeningHourFields::Weekday)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(OpeningHourFields::OpenTime).time().not_null())
+                    .col(ColumnDef::new(OpeningHourFields::CloseTime).time().not_null())
+                    .col(ColumnDef::new(OpeningHourFields::Closed).boolean().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        // 默认每天 8:00 至 22:00 开放，0 为周一
+        let mut insert = Query::insert()
+            .into_table(OpeningHourFields::OpeningHours)
+            .columns([
+                OpeningHourFields::Weekday,
+                OpeningHourFields::OpenTime,
+                OpeningHourFields::CloseTime,
+                OpeningHourFields::Closed,
+            ])
+            .to_owned();
+        for weekday in 0..7 {
+            insert.values_panic([
+                weekday.into(),
+                "08:00:00".into(),
+                "22:00:00".into(),
+                false.into(),
+            ]);
+        }
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OpeningHourFields::OpeningHours).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum OpeningHourFields {
+    OpeningHours,
+    Id,
+    Weekday,
+    OpenTime,
+    CloseTime,
+    Closed,
+}
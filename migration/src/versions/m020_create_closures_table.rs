@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ClosureFields::Closures)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ClosureFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ClosureFields::StartDate).date().not_null())
+                    .col(ColumnDef::new(ClosureFields::EndDate).date().not_null())
+                    .col(ColumnDef::new(ClosureFields::Name).string().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ClosureFields::Closures).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum ClosureFields {
+    Closures,
+    Id,
+    StartDate,
+    EndDate,
+    Name,
+}
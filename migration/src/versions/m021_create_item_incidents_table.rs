@@ -0,0 +1,134 @@
+use super::{
+    m002_create_users_table::UserFields, m003_create_borrowed_books_table::BorrowedBookFields,
+    m006_create_book_items_table::BookItemFields, m015_create_fines_table::FineFields,
+};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ItemIncidentFields::ItemIncidents)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ItemIncidentFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ItemIncidentFields::ItemId).integer().not_null())
+                    .col(ColumnDef::new(ItemIncidentFields::BorrowId).integer())
+                    .col(ColumnDef::new(ItemIncidentFields::UserId).integer())
+                    .col(ColumnDef::new(ItemIncidentFields::Kind).integer().not_null())
+                    .col(ColumnDef::new(ItemIncidentFields::Status).integer().not_null())
+                    .col(ColumnDef::new(ItemIncidentFields::FineId).integer())
+                    .col(ColumnDef::new(ItemIncidentFields::Note).string().not_null())
+                    .col(ColumnDef::new(ItemIncidentFields::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(ItemIncidentFields::CreatedBy).integer())
+                    .col(ColumnDef::new(ItemIncidentFields::ResolvedAt).date_time())
+                    .col(ColumnDef::new(ItemIncidentFields::ResolvedBy).integer())
+                    .col(
+                        ColumnDef::new(ItemIncidentFields::ResolutionNote)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incident_item_id")
+                            .from(ItemIncidentFields::ItemIncidents, ItemIncidentFields::ItemId)
+                            .to(BookItemFields::BookItems, BookItemFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incident_borrow_id")
+                            .from(ItemIncidentFields::ItemIncidents, ItemIncidentFields::BorrowId)
+                            .to(BorrowedBookFields::BorrowedBooks, BorrowedBookFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incident_user_id")
+                            .from(ItemIncidentFields::ItemIncidents, ItemIncidentFields::UserId)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incident_fine_id")
+                            .from(ItemIncidentFields::ItemIncidents, ItemIncidentFields::FineId)
+                            .to(FineFields::Fines, FineFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incident_created_by")
+                            .from(ItemIncidentFields::ItemIncidents, ItemIncidentFields::CreatedBy)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_incident_resolved_by")
+                            .from(ItemIncidentFields::ItemIncidents, ItemIncidentFields::ResolvedBy)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // 借阅状态区分归还、遗失与声称已还
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .add_column(
+                        ColumnDef::new(BorrowedBookFields::Status)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .drop_column(BorrowedBookFields::Status)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ItemIncidentFields::ItemIncidents).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum ItemIncidentFields {
+    ItemIncidents,
+    Id,
+    ItemId,
+    BorrowId,
+    UserId,
+    Kind,
+    Status,
+    FineId,
+    Note,
+    CreatedAt,
+    CreatedBy,
+    ResolvedAt,
+    ResolvedBy,
+    ResolutionNote,
+}
@@ -0,0 +1,90 @@
+use super::{m002_create_users_table::UserFields, m006_create_book_items_table::BookItemFields};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(IllRequestFields::IllRequests)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(IllRequestFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(IllRequestFields::UserId).integer().not_null())
+                    .col(ColumnDef::new(IllRequestFields::Name).string().not_null())
+                    .col(ColumnDef::new(IllRequestFields::Author).string().not_null())
+                    .col(ColumnDef::new(IllRequestFields::Publisher).string().not_null())
+                    .col(ColumnDef::new(IllRequestFields::PublishYear).date().not_null())
+                    .col(ColumnDef::new(IllRequestFields::Isbn).string().not_null())
+                    .col(ColumnDef::new(IllRequestFields::Note).string().not_null())
+                    .col(ColumnDef::new(IllRequestFields::Status).integer().not_null())
+                    .col(
+                        ColumnDef::new(IllRequestFields::Partner)
+                            .string()
+                            .not_null()
+                            .default(""),
+                    )
+                    .col(ColumnDef::new(IllRequestFields::ItemId).integer())
+                    .col(ColumnDef::new(IllRequestFields::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(IllRequestFields::UpdatedAt).date_time().not_null())
+                    .col(ColumnDef::new(IllRequestFields::UpdatedBy).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ill_request_user_id")
+                            .from(IllRequestFields::IllRequests, IllRequestFields::UserId)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ill_request_item_id")
+                            .from(IllRequestFields::IllRequests, IllRequestFields::ItemId)
+                            .to(BookItemFields::BookItems, BookItemFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ill_request_updated_by")
+                            .from(IllRequestFields::IllRequests, IllRequestFields::UpdatedBy)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IllRequestFields::IllRequests).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum IllRequestFields {
+    IllRequests,
+    Id,
+    UserId,
+    Name,
+    Author,
+    Publisher,
+    PublishYear,
+    Isbn,
+    Note,
+    Status,
+    Partner,
+    ItemId,
+    CreatedAt,
+    UpdatedAt,
+    UpdatedBy,
+}
@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseFields::Courses)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CourseFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CourseFields::Code).string().not_null())
+                    .col(ColumnDef::new(CourseFields::Name).string().not_null())
+                    .col(ColumnDef::new(CourseFields::Teacher).string().not_null())
+                    .col(ColumnDef::new(CourseFields::StartDate).date().not_null())
+                    .col(ColumnDef::new(CourseFields::EndDate).date().not_null())
+                    .col(ColumnDef::new(CourseFields::LoanHours).integer().not_null())
+                    .col(
+                        ColumnDef::new(CourseFields::MaxRenewals)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(CourseFields::Expired)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CourseFields::Courses).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum CourseFields {
+    Courses,
+    Id,
+    Code,
+    Name,
+    Teacher,
+    StartDate,
+    EndDate,
+    LoanHours,
+    MaxRenewals,
+    Expired,
+}
@@ -0,0 +1,63 @@
+use super::{m001_create_books_table::BookFields, m023_create_courses_table::CourseFields};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CourseReserveFields::CourseReserves)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CourseReserveFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CourseReserveFields::CourseId).integer().not_null())
+                    .col(ColumnDef::new(CourseReserveFields::BookId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reserve_course_id")
+                            .from(CourseReserveFields::CourseReserves, CourseReserveFields::CourseId)
+                            .to(CourseFields::Courses, CourseFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reserve_book_id")
+                            .from(CourseReserveFields::CourseReserves, CourseReserveFields::BookId)
+                            .to(BookFields::Books, BookFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_reserve_course_book")
+                            .col(CourseReserveFields::CourseId)
+                            .col(CourseReserveFields::BookId)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CourseReserveFields::CourseReserves).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum CourseReserveFields {
+    CourseReserves,
+    Id,
+    CourseId,
+    BookId,
+}
@@ -0,0 +1,30 @@
+use super::m003_create_borrowed_books_table::BorrowedBookFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 按小时计算借期的课程参考书记录具体的应还时间
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .add_column(ColumnDef::new(BorrowedBookFields::DueAt).date_time())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(BorrowedBookFields::BorrowedBooks)
+                    .drop_column(BorrowedBookFields::DueAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
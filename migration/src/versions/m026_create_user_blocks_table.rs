@@ -0,0 +1,77 @@
+use super::m002_create_users_table::UserFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserBlockFields::UserBlocks)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserBlockFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(UserBlockFields::UserId).integer().not_null())
+                    .col(ColumnDef::new(UserBlockFields::Reason).integer().not_null())
+                    .col(ColumnDef::new(UserBlockFields::Note).string().not_null())
+                    .col(ColumnDef::new(UserBlockFields::StartDate).date().not_null())
+                    .col(ColumnDef::new(UserBlockFields::EndDate).date())
+                    .col(ColumnDef::new(UserBlockFields::CreatedAt).date_time().not_null())
+                    .col(ColumnDef::new(UserBlockFields::CreatedBy).integer())
+                    .col(ColumnDef::new(UserBlockFields::LiftedAt).date_time())
+                    .col(ColumnDef::new(UserBlockFields::LiftedBy).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_block_user_id")
+                            .from(UserBlockFields::UserBlocks, UserBlockFields::UserId)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_block_created_by")
+                            .from(UserBlockFields::UserBlocks, UserBlockFields::CreatedBy)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_block_lifted_by")
+                            .from(UserBlockFields::UserBlocks, UserBlockFields::LiftedBy)
+                            .to(UserFields::Users, UserFields::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(UserBlockFields::UserBlocks).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum UserBlockFields {
+    UserBlocks,
+    Id,
+    UserId,
+    Reason,
+    Note,
+    StartDate,
+    EndDate,
+    CreatedAt,
+    CreatedBy,
+    LiftedAt,
+    LiftedBy,
+}
@@ -0,0 +1,48 @@
+use super::m002_create_users_table::UserFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 订阅借阅日历用的私密令牌，为空表示未开启或已撤销
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserFields::Users)
+                    .add_column(ColumnDef::new(UserFields::CalendarToken).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_users_calendar_token")
+                    .table(UserFields::Users)
+                    .col(UserFields::CalendarToken)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_users_calendar_token")
+                    .table(UserFields::Users)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(UserFields::Users)
+                    .drop_column(UserFields::CalendarToken)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use super::m001_create_books_table::BookFields;
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 把已有 ISBN 统一为不带连字符的 13 位形式。校验不通过，或规范化后与其他图书重复的
+/// 保持原样，由管理员在图书编辑页手动修正
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let builder = db.get_database_backend();
+        let select = Query::select()
+            .columns([BookFields::Id, BookFields::Isbn])
+            .from(BookFields::Books)
+            .order_by(BookFields::Id, Order::Asc)
+            .to_owned();
+        let mut books = Vec::new();
+        for row in db.query_all(builder.build(&select)).await? {
+            books.push((row.try_get::<i32>("", "id")?, row.try_get::<String>("", "isbn")?));
+        }
+        let mut taken: HashSet<String> = books.iter().map(|(_, isbn)| isbn.clone()).collect();
+        for (id, isbn) in books {
+            let Some(normalized) = normalize_isbn(&isbn) else {
+                continue;
+            };
+            if normalized == isbn || !taken.insert(normalized.clone()) {
+                continue;
+            }
+            let update = Query::update()
+                .table(BookFields::Books)
+                .value(BookFields::Isbn, normalized)
+                .and_where(Expr::col(BookFields::Id).eq(id))
+                .to_owned();
+            db.execute(builder.build(&update)).await?;
+        }
+        Ok(())
+    }
+
+    /// 原来的连字符写法无法恢复
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        Ok(())
+    }
+}
+
+/// 迁移时的 ISBN 规范化规则：去掉 "ISBN" 前缀、连字符和空格，10 位的转换为 13 位，
+/// 校验不通过时返回 `None`
+fn normalize_isbn(text: &str) -> Option<String> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix("ISBN")
+        .or_else(|| text.strip_prefix("isbn"))
+        .unwrap_or(text)
+        .trim_start_matches([':', '：', ' ']);
+    let chars: Vec<char> = text.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+    let digits = match chars.len() {
+        10 => {
+            let body = digits(&chars[..9])?;
+            if chars[9].to_ascii_uppercase() != isbn10_check(&body) {
+                return None;
+            }
+            let mut digits = vec![9, 7, 8];
+            digits.extend(body);
+            digits.push(isbn13_check(&digits));
+            digits
+        }
+        13 => {
+            let digits = digits(&chars)?;
+            if digits[..3] != [9, 7, 8] && digits[..3] != [9, 7, 9] {
+                return None;
+            }
+            if digits[12] != isbn13_check(&digits[..12]) {
+                return None;
+            }
+            digits
+        }
+        _ => return None,
+    };
+    digits.into_iter().map(|digit| char::from_digit(digit, 10)).collect()
+}
+
+fn digits(chars: &[char]) -> Option<Vec<u32>> {
+    chars.iter().map(|c| c.to_digit(10)).collect()
+}
+
+/// 权重依次为 10 到 2，余数为 10 时校验位写作 X
+fn isbn10_check(digits: &[u32]) -> char {
+    let sum: u32 = digits
+        .iter()
+        .zip((2..=10).rev())
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    match (11 - sum % 11) % 11 {
+        10 => 'X',
+        check => char::from(b'0' + check as u8),
+    }
+}
+
+/// 权重交替为 1 和 3
+fn isbn13_check(digits: &[u32]) -> u32 {
+    let sum: u32 = digits
+        .iter()
+        .zip([1, 3].into_iter().cycle())
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    (10 - sum % 10) % 10
+}
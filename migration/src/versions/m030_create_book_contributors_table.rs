@@ -0,0 +1,84 @@
+use super::{m001_create_books_table::BookFields, m029_create_authors_table::AuthorFields};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BookContributorFields::BookContributors)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BookContributorFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BookContributorFields::BookId).integer().not_null())
+                    .col(ColumnDef::new(BookContributorFields::AuthorId).integer().not_null())
+                    .col(ColumnDef::new(BookContributorFields::Role).integer().not_null())
+                    .col(ColumnDef::new(BookContributorFields::Position).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_contributor_book_id")
+                            .from(
+                                BookContributorFields::BookContributors,
+                                BookContributorFields::BookId,
+                            )
+                            .to(BookFields::Books, BookFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_contributor_author_id")
+                            .from(
+                                BookContributorFields::BookContributors,
+                                BookContributorFields::AuthorId,
+                            )
+                            .to(AuthorFields::Authors, AuthorFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_contributor_book_author_role")
+                            .col(BookContributorFields::BookId)
+                            .col(BookContributorFields::AuthorId)
+                            .col(BookContributorFields::Role)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        // 著者页按著者查找图书
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_contributor_author_id")
+                    .table(BookContributorFields::BookContributors)
+                    .col(BookContributorFields::AuthorId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BookContributorFields::BookContributors).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum BookContributorFields {
+    BookContributors,
+    Id,
+    BookId,
+    AuthorId,
+    Role,
+    Position,
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use super::{
+    m001_create_books_table::BookFields, m029_create_authors_table::AuthorFields,
+    m030_create_book_contributors_table::BookContributorFields,
+};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 拆分已有图书的著者行，建立著者及其与图书的关联
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let builder = db.get_database_backend();
+        let select = Query::select()
+            .columns([BookFields::Id, BookFields::Author])
+            .from(BookFields::Books)
+            .order_by(BookFields::Id, Order::Asc)
+            .to_owned();
+        let books = db.query_all(builder.build(&select)).await?;
+        let mut authors: HashMap<String, u64> = HashMap::new();
+        for row in books {
+            let book_id: i32 = row.try_get("", "id")?;
+            let author: String = row.try_get("", "author")?;
+            for (position, (name, role)) in parse_contributors(&author).into_iter().enumerate() {
+                let author_id = match authors.get(&name) {
+                    Some(&author_id) => author_id,
+                    None => {
+                        let insert = Query::insert()
+                            .into_table(AuthorFields::Authors)
+                            .columns([AuthorFields::Name])
+                            .values_panic([name.clone().into()])
+                            .to_owned();
+                        let author_id = db.execute(builder.build(&insert)).await?.last_insert_id();
+                        authors.insert(name, author_id);
+                        author_id
+                    }
+                };
+                let insert = Query::insert()
+                    .into_table(BookContributorFields::BookContributors)
+                    .columns([
+                        BookContributorFields::BookId,
+                        BookContributorFields::AuthorId,
+                        BookContributorFields::Role,
+                        BookContributorFields::Position,
+                    ])
+                    .values_panic([
+                        book_id.into(),
+                        author_id.into(),
+                        role.into(),
+                        (position as i32).into(),
+                    ])
+                    .to_owned();
+                db.execute(builder.build(&insert)).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        db.execute_unprepared(r#"DELETE FROM "book_contributors""#).await?;
+        db.execute_unprepared(r#"DELETE FROM "authors""#).await?;
+        Ok(())
+    }
+}
+
+/// 迁移时著者职责的取值：0 著者、1 编者、2 译者、3 绘者
+const AUTHOR: u8 = 0;
+const EDITOR: u8 = 1;
+const TRANSLATOR: u8 = 2;
+const ILLUSTRATOR: u8 = 3;
+
+/// 职责说明，较长的写在前面以免 "主编" 被当作 "编"
+const ROLE_SUFFIXES: [(&str, u8); 12] = [
+    ("主编", EDITOR),
+    ("编著", AUTHOR),
+    ("合著", AUTHOR),
+    ("编译", TRANSLATOR),
+    ("翻译", TRANSLATOR),
+    ("合译", TRANSLATOR),
+    ("绘图", ILLUSTRATOR),
+    ("插图", ILLUSTRATOR),
+    ("著", AUTHOR),
+    ("译", TRANSLATOR),
+    ("编", EDITOR),
+    ("绘", ILLUSTRATOR),
+];
+
+/// 迁移时的著者行拆分规则。各段用 "/"、分号或逗号分隔，段末可带职责说明，
+/// 同一职责的多人在段内用 "、" 分隔，两侧都是西文的英文逗号视为倒置姓名
+fn parse_contributors(text: &str) -> Vec<(String, u8)> {
+    let mut segments: Vec<String> = Vec::new();
+    for segment in text.split(['/', ';', '；', '，']) {
+        let mut pieces = segment.split(',');
+        let mut current = pieces.next().unwrap_or_default().to_owned();
+        for piece in pieces {
+            if current.is_ascii() && piece.is_ascii() {
+                current.push(',');
+                current.push_str(piece);
+            } else {
+                segments.push(std::mem::replace(&mut current, piece.to_owned()));
+            }
+        }
+        segments.push(current);
+    }
+
+    let mut contributors = Vec::new();
+    for segment in &segments {
+        let segment = segment.trim();
+        let (names, role) = ROLE_SUFFIXES
+            .iter()
+            .find_map(|(suffix, role)| {
+                let names = segment.strip_suffix(suffix)?.trim_end();
+                (!names.is_empty()).then_some((names, *role))
+            })
+            .unwrap_or((segment, AUTHOR));
+        for name in names.split('、').map(str::trim).filter(|name| !name.is_empty()) {
+            let contributor = (name.to_owned(), role);
+            if !contributors.contains(&contributor) {
+                contributors.push(contributor);
+            }
+        }
+    }
+    contributors
+}
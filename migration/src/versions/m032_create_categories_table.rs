@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// 中国图书馆分类法的 22 个基本大类，下级类目由管理员按需添加
+const CLC_CLASSES: [(&str, &str); 22] = [
+    ("A", "马克思主义、列宁主义、毛泽东思想、邓小平理论"),
+    ("B", "哲学、宗教"),
+    ("C", "社会科学总论"),
+    ("D", "政治、法律"),
+    ("E", "军事"),
+    ("F", "经济"),
+    ("G", "文化、科学、教育、体育"),
+    ("H", "语言、文字"),
+    ("I", "文学"),
+    ("J", "艺术"),
+    ("K", "历史、地理"),
+    ("N", "自然科学总论"),
+    ("O", "数理科学和化学"),
+    ("P", "天文学、地球科学"),
+    ("Q", "生物科学"),
+    ("R", "医药、卫生"),
+    ("S", "农业科学"),
+    ("T", "工业技术"),
+    ("U", "交通运输"),
+    ("V", "航空、航天"),
+    ("X", "环境科学、安全科学"),
+    ("Z", "综合性图书"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CategoryFields::Categories)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CategoryFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(CategoryFields::Scheme).integer().not_null())
+                    .col(ColumnDef::new(CategoryFields::Code).string().not_null())
+                    .col(ColumnDef::new(CategoryFields::Name).string().not_null())
+                    .col(ColumnDef::new(CategoryFields::ParentId).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_category_parent_id")
+                            .from(CategoryFields::Categories, CategoryFields::ParentId)
+                            .to(CategoryFields::Categories, CategoryFields::Id),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_category_scheme_code")
+                            .col(CategoryFields::Scheme)
+                            .col(CategoryFields::Code)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut insert = Query::insert()
+            .into_table(CategoryFields::Categories)
+            .columns([CategoryFields::Scheme, CategoryFields::Code, CategoryFields::Name])
+            .to_owned();
+        for (code, name) in CLC_CLASSES {
+            insert.values_panic([0.into(), code.into(), name.into()]);
+        }
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CategoryFields::Categories).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum CategoryFields {
+    Categories,
+    Id,
+    Scheme,
+    Code,
+    Name,
+    ParentId,
+}
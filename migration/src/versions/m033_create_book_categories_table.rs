@@ -0,0 +1,69 @@
+use super::{m001_create_books_table::BookFields, m032_create_categories_table::CategoryFields};
+use sea_orm_migration::prelude::*;
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(BookCategoryFields::BookCategories)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BookCategoryFields::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(BookCategoryFields::BookId).integer().not_null())
+                    .col(ColumnDef::new(BookCategoryFields::CategoryId).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_book_category_book_id")
+                            .from(
+                                BookCategoryFields::BookCategories,
+                                BookCategoryFields::BookId,
+                            )
+                            .to(BookFields::Books, BookFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_book_category_category_id")
+                            .from(
+                                BookCategoryFields::BookCategories,
+                                BookCategoryFields::CategoryId,
+                            )
+                            .to(CategoryFields::Categories, CategoryFields::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_book_category_book_category")
+                            .col(BookCategoryFields::BookId)
+                            .col(BookCategoryFields::CategoryId)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(BookCategoryFields::BookCategories).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+pub(super) enum BookCategoryFields {
+    BookCategories,
+    Id,
+    BookId,
+    CategoryId,
+}
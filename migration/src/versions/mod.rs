@@ -1,5 +1,35 @@
-pub(super) mod m001_create_books_table;
-pub(super) mod m002_create_users_table;
-pub(super) mod m003_create_borrowed_books_table;
-pub(super) mod m004_create_emails_table;
-pub(super) mod m005_create_email_messages_table;
+pub(super) mod m001_create_books_table;
+pub(super) mod m002_create_users_table;
+pub(super) mod m003_create_borrowed_books_table;
+pub(super) mod m004_create_emails_table;
+#[allow(dead_code)]
+pub(super) mod m005_create_email_messages_table;
+pub(super) mod m006_create_book_items_table;
+pub(super) mod m007_migrate_book_copies_to_items;
+pub(super) mod m008_add_borrowed_books_return_fields;
+pub(super) mod m009_create_holds_table;
+pub(super) mod m010_add_borrowed_books_renewals;
+pub(super) mod m011_create_loan_renewals_table;
+pub(super) mod m012_add_patron_category_and_item_type;
+pub(super) mod m013_create_circulation_policies_table;
+pub(super) mod m014_add_circulation_policies_fine_fields;
+pub(super) mod m015_create_fines_table;
+pub(super) mod m016_create_payments_table;
+pub(super) mod m017_create_job_runs_table;
+pub(super) mod m018_create_loan_notices_table;
+pub(super) mod m019_create_opening_hours_table;
+pub(super) mod m020_create_closures_table;
+pub(super) mod m021_create_item_incidents_table;
+pub(super) mod m022_create_ill_requests_table;
+pub(super) mod m023_create_courses_table;
+pub(super) mod m024_create_course_reserves_table;
+pub(super) mod m025_add_borrowed_books_due_at;
+pub(super) mod m026_create_user_blocks_table;
+pub(super) mod m027_add_users_calendar_token;
+pub(super) mod m028_normalize_books_isbn;
+pub(super) mod m029_create_authors_table;
+pub(super) mod m030_create_book_contributors_table;
+pub(super) mod m031_migrate_book_authors_to_contributors;
+pub(super) mod m032_create_categories_table;
+pub(super) mod m033_create_book_categories_table;
+pub(super) mod m034_add_books_call_number;
@@ -0,0 +1,121 @@
+use ::entity::{closures, opening_hours};
+use chrono::{Datelike, NaiveDate};
+use sea_orm::*;
+
+use crate::Query;
+
+/// 最多向后查找一年，避免全部设为闭馆时死循环
+const MAX_SHIFT_DAYS: i64 = 366;
+
+/// 图书馆日历：每周的开放时间与节假日闭馆日期
+#[derive(Clone)]
+pub struct Calendar {
+    hours: Vec<opening_hours::Model>,
+    closures: Vec<closures::Model>,
+}
+
+impl Calendar {
+    pub fn new(hours: Vec<opening_hours::Model>, closures: Vec<closures::Model>) -> Self {
+        Self { hours, closures }
+    }
+
+    pub async fn load<C: ConnectionTrait>(db: &C) -> Result<Self, DbErr> {
+        Ok(Self::new(
+            Query::find_opening_hours(db).await?,
+            Query::find_closures(db).await?,
+        ))
+    }
+
+    pub fn is_open(&self, date: NaiveDate) -> bool {
+        let weekday = date.weekday().num_days_from_monday() as i32;
+        let weekly_closed = self
+            .hours
+            .iter()
+            .any(|hours| hours.weekday == weekday && hours.closed);
+        !weekly_closed
+            && !self
+                .closures
+                .iter()
+                .any(|closure| closure.start_date <= date && date <= closure.end_date)
+    }
+
+    /// `date` 当天开放则返回当天，否则顺延到下一个开放日
+    pub fn next_open_day(&self, date: NaiveDate) -> NaiveDate {
+        (0..MAX_SHIFT_DAYS)
+            .map(|days| date + chrono::Duration::days(days))
+            .find(|&date| self.is_open(date))
+            .unwrap_or(date)
+    }
+
+    /// `from` 之后到 `to`（含）之间的开放天数
+    pub fn open_days_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        from.iter_days()
+            .skip(1)
+            .take_while(|&date| date <= to)
+            .filter(|&date| self.is_open(date))
+            .count() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap()
+    }
+
+    /// 周日闭馆，7 月 10 日至 12 日节假日闭馆
+    fn calendar() -> Calendar {
+        let hours = (0..7)
+            .map(|weekday| opening_hours::Model {
+                id: weekday + 1,
+                weekday,
+                open_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+                close_time: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+                closed: weekday == 6,
+            })
+            .collect();
+        let closures = vec![closures::Model {
+            id: 1,
+            start_date: date(7, 10),
+            end_date: date(7, 12),
+            name: "闭馆".to_owned(),
+        }];
+        Calendar::new(hours, closures)
+    }
+
+    #[test]
+    fn weekly_and_holiday_closures() {
+        let calendar = calendar();
+        // 2023 年 7 月 8 日是周六
+        assert!(calendar.is_open(date(7, 8)));
+        assert!(!calendar.is_open(date(7, 9)));
+        assert!(!calendar.is_open(date(7, 10)));
+        assert!(!calendar.is_open(date(7, 12)));
+        assert!(calendar.is_open(date(7, 13)));
+    }
+
+    #[test]
+    fn next_open_day_skips_closures() {
+        let calendar = calendar();
+        assert_eq!(calendar.next_open_day(date(7, 8)), date(7, 8));
+        assert_eq!(calendar.next_open_day(date(7, 9)), date(7, 13));
+        // 全部闭馆时不再顺延
+        let mut closed = calendar.clone();
+        closed.hours.iter_mut().for_each(|hours| hours.closed = true);
+        assert_eq!(closed.next_open_day(date(7, 9)), date(7, 9));
+    }
+
+    #[test]
+    fn counts_open_days_after_from() {
+        let calendar = calendar();
+        assert_eq!(calendar.open_days_between(date(7, 8), date(7, 8)), 0);
+        assert_eq!(calendar.open_days_between(date(7, 8), date(7, 7)), 0);
+        assert_eq!(calendar.open_days_between(date(7, 7), date(7, 8)), 1);
+        assert_eq!(calendar.open_days_between(date(7, 8), date(7, 12)), 0);
+        assert_eq!(calendar.open_days_between(date(7, 8), date(7, 14)), 2);
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::{HashMap, HashSet};
+
+use ::entity::{categories, CategoryScheme};
+use sea_orm::*;
+
+use crate::Query;
+
+/// 一种分类法下的类目树及各类目直接收录的图书
+pub struct CategoryTree {
+    categories: Vec<categories::Model>,
+    /// 类目 id 到直接归入该类目的图书 id
+    books: HashMap<i32, HashSet<i32>>,
+}
+
+impl CategoryTree {
+    /// `links` 为 (类目 id, 图书 id)
+    pub fn new(
+        categories: Vec<categories::Model>,
+        links: impl IntoIterator<Item = (i32, i32)>,
+    ) -> Self {
+        let mut books: HashMap<i32, HashSet<i32>> = HashMap::new();
+        for (category_id, book_id) in links {
+            books.entry(category_id).or_default().insert(book_id);
+        }
+        Self { categories, books }
+    }
+
+    pub async fn load<C: ConnectionTrait>(db: &C, scheme: CategoryScheme) -> Result<Self, DbErr> {
+        let links = Query::find_book_categories_by_scheme(db, scheme).await?;
+        Ok(Self::new(
+            Query::find_categories_by_scheme(db, scheme).await?,
+            links.into_iter().map(|link| (link.category_id, link.book_id)),
+        ))
+    }
+
+    pub fn get(&self, id: i32) -> Option<&categories::Model> {
+        self.categories.iter().find(|category| category.id == id)
+    }
+
+    /// `parent` 为 `None` 时返回顶层类目，按类号排序
+    pub fn children(&self, parent: Option<i32>) -> Vec<&categories::Model> {
+        self.categories
+            .iter()
+            .filter(|category| category.parent_id == parent)
+            .collect()
+    }
+
+    /// 从顶层到 `id` 的上级类目，不含自身
+    pub fn ancestors(&self, id: i32) -> Vec<&categories::Model> {
+        let mut ancestors = Vec::new();
+        let mut current = self.get(id).and_then(|category| category.parent_id);
+        while let Some(category) = current.and_then(|id| self.get(id)) {
+            // 数据有环时不再继续
+            if ancestors.iter().any(|c: &&categories::Model| c.id == category.id) {
+                break;
+            }
+            ancestors.push(category);
+            current = category.parent_id;
+        }
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// `id` 及其全部下级类目
+    pub fn subtree_ids(&self, id: i32) -> Vec<i32> {
+        let mut ids = vec![id];
+        let mut index = 0;
+        while index < ids.len() {
+            let parent = ids[index];
+            for child in self.children(Some(parent)) {
+                if !ids.contains(&child.id) {
+                    ids.push(child.id);
+                }
+            }
+            index += 1;
+        }
+        ids
+    }
+
+    /// 归入 `id` 或其下级类目的图书种数，同一本书只计一次
+    pub fn book_count(&self, id: i32) -> usize {
+        self.subtree_ids(id)
+            .iter()
+            .filter_map(|id| self.books.get(id))
+            .flatten()
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// 按树的先序排列的全部类目及其层级，用于下拉选项
+    pub fn flatten(&self) -> Vec<(usize, &categories::Model)> {
+        let mut flattened = Vec::new();
+        let mut stack: Vec<(usize, &categories::Model)> =
+            self.children(None).into_iter().rev().map(|category| (0, category)).collect();
+        while let Some((depth, category)) = stack.pop() {
+            flattened.push((depth, category));
+            stack.extend(
+                self.children(Some(category.id))
+                    .into_iter()
+                    .rev()
+                    .map(|child| (depth + 1, child)),
+            );
+        }
+        flattened
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i32, code: &str, parent_id: Option<i32>) -> categories::Model {
+        categories::Model {
+            id,
+            scheme: CategoryScheme::Clc,
+            code: code.to_owned(),
+            name: code.to_owned(),
+            parent_id,
+        }
+    }
+
+    /// T 工业技术 > TP 自动化技术 > TP3 计算机技术，另有顶层的 O 数理科学
+    fn tree() -> CategoryTree {
+        CategoryTree::new(
+            vec![
+                category(1, "O", None),
+                category(2, "T", None),
+                category(3, "TP", Some(2)),
+                category(4, "TP3", Some(3)),
+                category(5, "TN", Some(2)),
+            ],
+            [(3, 10), (4, 10), (4, 11), (5, 12), (1, 13)],
+        )
+    }
+
+    fn codes<'a>(categories: impl IntoIterator<Item = &'a categories::Model>) -> Vec<&'a str> {
+        categories.into_iter().map(|category| category.code.as_str()).collect()
+    }
+
+    #[test]
+    fn children_and_ancestors() {
+        let tree = tree();
+        assert_eq!(codes(tree.children(None)), ["O", "T"]);
+        assert_eq!(codes(tree.children(Some(2))), ["TP", "TN"]);
+        assert!(tree.children(Some(4)).is_empty());
+        assert_eq!(codes(tree.ancestors(4)), ["T", "TP"]);
+        assert!(tree.ancestors(2).is_empty());
+        assert!(tree.ancestors(99).is_empty());
+    }
+
+    #[test]
+    fn subtree_and_book_count() {
+        let tree = tree();
+        assert_eq!(tree.subtree_ids(2), [2, 3, 5, 4]);
+        assert_eq!(tree.subtree_ids(4), [4]);
+        // 图书 10 同时归入 TP 与 TP3，只计一次
+        assert_eq!(tree.book_count(2), 3);
+        assert_eq!(tree.book_count(3), 2);
+        assert_eq!(tree.book_count(1), 1);
+        assert_eq!(tree.book_count(99), 0);
+    }
+
+    #[test]
+    fn flattens_in_preorder() {
+        let flattened: Vec<_> = tree()
+            .flatten()
+            .into_iter()
+            .map(|(depth, category)| (depth, category.code.clone()))
+            .collect();
+        let expected = [(0, "O"), (0, "T"), (1, "TP"), (2, "TP3"), (1, "TN")];
+        assert_eq!(flattened, expected.map(|(depth, code)| (depth, code.to_owned())));
+    }
+
+    #[test]
+    fn ancestors_stop_at_cycles() {
+        let tree = CategoryTree::new(
+            vec![category(1, "A", Some(2)), category(2, "B", Some(1))],
+            [],
+        );
+        // 回到已经过的类目时停止
+        assert_eq!(codes(tree.ancestors(1)), ["A", "B"]);
+        assert_eq!(tree.subtree_ids(1), [1, 2]);
+    }
+}
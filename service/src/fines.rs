@@ -0,0 +1,82 @@
+use ::entity::{borrowed_books, fines, LoanStatus};
+use chrono::NaiveDateTime;
+use sea_orm::*;
+
+use crate::{Calendar, LoanState, Mutation, Policy, Query};
+
+pub struct Fines;
+
+impl Fines {
+    /// 按适用的流通规则计算借阅的逾期罚款，每笔借阅只保留一条罚款记录。
+    /// 日历由调用方加载，批量处理时只需加载一次
+    pub async fn assess_loan<C: ConnectionTrait>(
+        db: &C,
+        loan: &borrowed_books::Model,
+        as_of: NaiveDateTime,
+        calendar: &Calendar,
+    ) -> Result<Option<fines::Model>, DbErr> {
+        let patron = Query::find_user_by_id(db, loan.user_id)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find user.".to_owned()))?;
+        let item = Query::find_book_item_by_id(db, loan.item_id)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find book item.".to_owned()))?;
+        let policy = Policy::find_policy(db, &patron.patron_category, &item.item_type).await?;
+        let amount = Policy::overdue_fine(&policy, loan, as_of, calendar);
+        match Query::find_fine_by_borrow_id(db, loan.id).await? {
+            Some(fine) if fine.amount == amount => Ok(Some(fine)),
+            Some(fine) => Mutation::update_fine_amount_by_id(db, fine.id, amount, as_of.date())
+                .await
+                .map(Some),
+            None if amount == 0 => Ok(None),
+            None => Mutation::create_fine(
+                db,
+                loan.user_id,
+                Some(loan.id),
+                amount,
+                as_of.date(),
+                "逾期罚款".to_owned(),
+            )
+            .await
+            .map(Some),
+        }
+    }
+
+    /// 更新所有未归还借阅的逾期罚款，返回处理的借阅数量
+    pub async fn assess_all<C: ConnectionTrait>(
+        db: &C,
+        as_of: NaiveDateTime,
+    ) -> Result<u32, DbErr> {
+        let calendar = Calendar::load(db).await?;
+        let mut assessed = 0;
+        for loan in Query::find_borrowed_books_by_state(db, LoanState::Active).await? {
+            if loan.status == LoanStatus::OnLoan && Policy::deadline(&loan) < as_of {
+                Self::assess_loan(db, &loan, as_of, &calendar).await?;
+                assessed += 1;
+            }
+        }
+        Ok(assessed)
+    }
+
+    /// 更新读者所有未归还借阅的逾期罚款
+    pub async fn assess_user<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        as_of: NaiveDateTime,
+        calendar: &Calendar,
+    ) -> Result<(), DbErr> {
+        for loan in Query::find_borrowed_books_by_user_id(db, user_id, LoanState::Active).await? {
+            if loan.status == LoanStatus::OnLoan && Policy::deadline(&loan) < as_of {
+                Self::assess_loan(db, &loan, as_of, calendar).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 以分为单位的金额格式化为元
+pub fn format_money(amount: i64) -> String {
+    let sign = if amount < 0 { "-" } else { "" };
+    let amount = amount.abs();
+    format!("{sign}{}.{:02}", amount / 100, amount % 100)
+}
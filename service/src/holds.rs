@@ -0,0 +1,92 @@
+use ::entity::{book_items, holds, EmailCategory, HoldStatus, ItemStatus};
+use chrono::NaiveDate;
+use sea_orm::*;
+
+use crate::{Calendar, Mutation, Query};
+
+/// 预约到书后为读者保留的天数
+pub const HOLD_PICKUP_DAYS: i64 = 7;
+
+/// 馆际互借到馆后建立的临时副本使用的副本类型，只为申请人保留，不参与预约与一般借出
+pub const ILL_ITEM_TYPE: &str = "ill";
+
+pub struct Holds;
+
+impl Holds {
+    /// 副本归还或预约失效后，优先保留给排在最前的预约读者，没有预约时重新上架
+    pub async fn release_book_item<C: ConnectionTrait>(
+        db: &C,
+        item: &book_items::Model,
+        sender_id: i32,
+    ) -> Result<Option<holds::Model>, DbErr> {
+        let hold = match item.item_type.as_str() {
+            ILL_ITEM_TYPE => None,
+            _ => Query::find_next_waiting_hold_by_book_id(db, item.book_id).await?,
+        };
+        let Some(hold) = hold else {
+            Mutation::update_book_item_status_by_id(db, item.id, ItemStatus::Available).await?;
+            return Ok(None);
+        };
+        // 取书期限遇闭馆日顺延
+        let pickup_deadline = Calendar::load(db).await?.next_open_day(
+            chrono::Local::now().naive_local().date() + chrono::Duration::days(HOLD_PICKUP_DAYS),
+        );
+        let hold = Mutation::set_hold_ready_by_id(db, hold.id, item.id, pickup_deadline).await?;
+        Mutation::update_book_item_status_by_id(db, item.id, ItemStatus::OnHoldShelf).await?;
+        let book_name = Query::find_book_by_id(db, item.book_id)
+            .await?
+            .map(|book| book.name)
+            .unwrap_or_default();
+        Mutation::create_email(
+            db,
+            EmailCategory::Regular,
+            sender_id,
+            hold.user_id,
+            "预约到书通知".to_owned(),
+            format!(
+                "您预约的《{book_name}》已到馆（条码 {}），请于 {pickup_deadline} 前到馆借阅，逾期预约将自动取消。",
+                item.barcode
+            ),
+        )
+        .await?;
+        Ok(Some(hold))
+    }
+
+    /// 取消超过取书期限的预约，并把保留的副本交给下一位预约读者，返回取消的预约数量
+    pub async fn expire_holds<C: ConnectionTrait>(
+        db: &C,
+        today: NaiveDate,
+    ) -> Result<u32, DbErr> {
+        let overdue_holds = Query::find_overdue_ready_holds(db, today).await?;
+        if overdue_holds.is_empty() {
+            return Ok(0);
+        }
+        // 系统通知以第一位管理员的名义发送
+        let Some(&sender_id) = Query::find_admin_ids(db).await?.first() else {
+            return Ok(0);
+        };
+        let expired = overdue_holds.len() as u32;
+        for hold in overdue_holds {
+            Mutation::update_hold_status_by_id(db, hold.id, HoldStatus::Expired).await?;
+            let book_name = Query::find_book_by_id(db, hold.book_id)
+                .await?
+                .map(|book| book.name)
+                .unwrap_or_default();
+            Mutation::create_email(
+                db,
+                EmailCategory::Regular,
+                sender_id,
+                hold.user_id,
+                "预约过期通知".to_owned(),
+                format!("您预约的《{book_name}》超过取书期限未借阅，预约已取消。"),
+            )
+            .await?;
+            if let Some(item_id) = hold.item_id {
+                if let Some(item) = Query::find_book_item_by_id(db, item_id).await? {
+                    Self::release_book_item(db, &item, sender_id).await?;
+                }
+            }
+        }
+        Ok(expired)
+    }
+}
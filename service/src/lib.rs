@@ -1,7 +1,19 @@
-mod mutation;
-mod query;
-
-pub use mutation::*;
-pub use query::*;
-
-pub use sea_orm;
+mod calendar;
+mod category;
+mod fines;
+mod holds;
+mod mutation;
+mod policy;
+mod query;
+mod statistics;
+
+pub use calendar::*;
+pub use category::*;
+pub use fines::*;
+pub use holds::*;
+pub use mutation::*;
+pub use policy::*;
+pub use query::*;
+pub use statistics::*;
+
+pub use sea_orm;
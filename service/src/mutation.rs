@@ -1,263 +1,1089 @@
-use ::entity::{books, borrowed_books, emails, users, AccessPermission, EmailCategory};
-use chrono::NaiveDate;
-use paste::paste;
-use sea_orm::*;
-
-pub struct Mutation;
-
-// macro_rules! update_by_id_def {
-//     ($name:ident, $field:ident) => {
-//         paste! {
-//             pub async fn [<update_ $name _ $field _by_id>](
-//                 db: &DbConn,
-//                 id: i32,
-//                 form_data: [<$name s>]::Model,
-//             ) -> Result<[<$name s>]::Model, DbErr> {
-//                 let new_data: [<$name s>]::ActiveModel = [<$name s>]::Entity::find_by_id(id)
-//                     .one(db)
-//                     .await?
-//                     .ok_or(DbErr::Custom(format!("Cannot find {}.", stringify!($name))))
-//                     .map(Into::into)?;
-
-//                 [<$name s>]::ActiveModel {
-//                     id: new_data.id,
-//                     $field: Set(form_data.$field.to_owned()),
-//                     ..Default::default()
-//                 }
-//                 .update(db)
-//                 .await
-//             }
-//         }
-//     };
-// }
-
-macro_rules! delete_by_id_def {
-    ($name:ident) => {
-        paste! {
-            pub async fn [<delete_ $name>]<C: ConnectionTrait>(db: &C, id: i32) -> Result<DeleteResult, DbErr> {
-                let entity: [<$name s>]::ActiveModel = [<$name s>]::Entity::find_by_id(id)
-                    .one(db)
-                    .await?
-                    .ok_or(DbErr::Custom(format!("Cannot find {}.", stringify!($name))))
-                    .map(Into::into)?;
-
-                entity.delete(db).await
-            }
-        }
-    };
-}
-
-impl Mutation {
-    pub async fn create_user<C: ConnectionTrait>(
-        db: &C,
-        username: String,
-        nickname: String,
-        password_hash: String,
-        permission: AccessPermission,
-    ) -> Result<users::Model, DbErr> {
-        let registration_date = chrono::Local::now().naive_local().date();
-        users::ActiveModel {
-            name: Set(username),
-            nickname: Set(nickname),
-            password_hash: Set(password_hash),
-            permission: Set(permission),
-            registration_date: Set(registration_date),
-            ..Default::default()
-        }
-        .insert(db)
-        .await
-    }
-
-    pub async fn update_user_by_id<C: ConnectionTrait>(
-        db: &C,
-        id: i32,
-        nickname: String,
-        password_hash: String,
-    ) -> Result<users::Model, DbErr> {
-        let new_data: users::ActiveModel = users::Entity::find_by_id(id)
-            .one(db)
-            .await?
-            .ok_or(DbErr::Custom(format!("Cannot find user.")))
-            .map(Into::into)?;
-        users::ActiveModel {
-            id: new_data.id,
-            nickname: Set(nickname),
-            password_hash: Set(password_hash),
-            ..Default::default()
-        }
-        .update(db)
-        .await
-    }
-
-    pub async fn create_book<C: ConnectionTrait>(
-        db: &C,
-        form_data: books::Model,
-    ) -> Result<books::Model, DbErr> {
-        let books::Model {
-            name,
-            author,
-            publisher,
-            publish_year,
-            isbn,
-            copies,
-            ..
-        } = form_data;
-        books::ActiveModel {
-            name: Set(name),
-            author: Set(author),
-            publisher: Set(publisher),
-            publish_year: Set(publish_year),
-            isbn: Set(isbn),
-            copies: Set(copies),
-            ..Default::default()
-        }
-        .insert(db)
-        .await
-    }
-
-    pub async fn update_book_by_id<C: ConnectionTrait>(
-        db: &C,
-        id: i32,
-        form_data: books::Model,
-    ) -> Result<books::Model, DbErr> {
-        let new_data: books::ActiveModel = books::Entity::find_by_id(id)
-            .one(db)
-            .await?
-            .ok_or(DbErr::Custom(format!("Cannot find book.")))
-            .map(Into::into)?;
-        let books::Model {
-            name,
-            author,
-            publisher,
-            publish_year,
-            isbn,
-            copies,
-            ..
-        } = form_data;
-        books::ActiveModel {
-            id: new_data.id,
-            name: Set(name),
-            author: Set(author),
-            publisher: Set(publisher),
-            publish_year: Set(publish_year),
-            isbn: Set(isbn),
-            copies: Set(copies),
-            ..Default::default()
-        }
-        .update(db)
-        .await
-    }
-
-    pub async fn update_book_copies_by_id<C: ConnectionTrait>(
-        db: &C,
-        id: i32,
-        copies: i32,
-    ) -> Result<books::Model, DbErr> {
-        let new_data: books::ActiveModel = books::Entity::find_by_id(id)
-            .one(db)
-            .await?
-            .ok_or(DbErr::Custom(format!("Cannot find book.")))
-            .map(Into::into)?;
-        books::ActiveModel {
-            id: new_data.id,
-            copies: Set(copies),
-            ..Default::default()
-        }
-        .update(db)
-        .await
-    }
-
-    pub async fn create_borrowed_book<C: ConnectionTrait>(
-        db: &C,
-        user_id: i32,
-        book_id: i32,
-        borrow_date: NaiveDate,
-        return_date: NaiveDate,
-    ) -> Result<borrowed_books::Model, DbErr> {
-        borrowed_books::ActiveModel {
-            user_id: Set(user_id),
-            book_id: Set(book_id),
-            borrow_date: Set(borrow_date),
-            return_date: Set(return_date),
-            ..Default::default()
-        }
-        .insert(db)
-        .await
-    }
-
-    pub async fn create_email<C: ConnectionTrait>(
-        db: &C,
-        category: EmailCategory,
-        sender_id: i32,
-        recipient_id: i32,
-        subject: String,
-        content: String,
-    ) -> Result<emails::Model, DbErr> {
-        let date_time = chrono::Local::now().naive_local();
-        emails::ActiveModel {
-            category: Set(category),
-            sender_id: Set(sender_id),
-            recipient_id: Set(recipient_id),
-            subject: Set(subject),
-            content: Set(content),
-            date_time: Set(date_time),
-            ..Default::default()
-        }
-        .insert(db)
-        .await
-    }
-
-    // update_by_id_def!(user, name);
-    // update_by_id_def!(user, password);
-    // update_by_id_def!(book, copies);
-    // update_by_id_def!(borrowed_book, return_date);
-
-    delete_by_id_def!(user);
-    delete_by_id_def!(book);
-    delete_by_id_def!(borrowed_book);
-    delete_by_id_def!(email);
-
-    pub async fn delete_email_by_id_on_sender<C: ConnectionTrait>(
-        db: &C,
-        id: i32,
-    ) -> Result<(), DbErr> {
-        delete_email_by_id_weak(db, id, true).await
-    }
-
-    pub async fn delete_email_by_id_on_recipient<C: ConnectionTrait>(
-        db: &C,
-        id: i32,
-    ) -> Result<(), DbErr> {
-        delete_email_by_id_weak(db, id, false).await
-    }
-}
-
-pub async fn delete_email_by_id_weak<C: ConnectionTrait>(
-    db: &C,
-    id: i32,
-    on_sender: bool,
-) -> Result<(), DbErr> {
-    let entity: emails::ActiveModel = emails::Entity::find_by_id(id)
-        .one(db)
-        .await?
-        .ok_or(DbErr::Custom(format!("Cannot find email.")))
-        .map(Into::into)?;
-
-    let data = if on_sender {
-        emails::ActiveModel {
-            deleted_by_sender: Set(true),
-            ..entity
-        }
-    } else {
-        emails::ActiveModel {
-            deleted_by_recipient: Set(true),
-            ..entity
-        }
-    }
-    .update(db)
-    .await?;
-    if data.deleted_by_sender && data.deleted_by_recipient {
-        Mutation::delete_email(db, id).await?;
-    }
-    Ok(())
-}
+use ::entity::{
+    authors, book_categories, book_contributors, book_items, books, borrowed_books, categories,
+    circulation_policies, closures, course_reserves, courses, emails, fines, holds, ill_requests,
+    item_incidents, job_runs, loan_notices, loan_renewals, opening_hours, parse_contributors,
+    payments, user_blocks, users, AccessPermission, BlockReason, CategoryScheme, ContributorRole,
+    EmailCategory, HoldStatus, IllStatus, IncidentKind, IncidentStatus, ItemCondition, ItemStatus,
+    JobStatus, LoanStatus, PaymentKind,
+};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use paste::paste;
+use sea_orm::*;
+
+use crate::Query;
+
+pub struct Mutation;
+
+// macro_rules! update_by_id_def {
+//     ($name:ident, $field:ident) => {
+//         paste! {
+//             pub async fn [<update_ $name _ $field _by_id>](
+//                 db: &DbConn,
+//                 id: i32,
+//                 form_data: [<$name s>]::Model,
+//             ) -> Result<[<$name s>]::Model, DbErr> {
+//                 let new_data: [<$name s>]::ActiveModel = [<$name s>]::Entity::find_by_id(id)
+//                     .one(db)
+//                     .await?
+//                     .ok_or(DbErr::Custom(format!("Cannot find {}.", stringify!($name))))
+//                     .map(Into::into)?;
+
+//                 [<$name s>]::ActiveModel {
+//                     id: new_data.id,
+//                     $field: Set(form_data.$field.to_owned()),
+//                     ..Default::default()
+//                 }
+//                 .update(db)
+//                 .await
+//             }
+//         }
+//     };
+// }
+
+macro_rules! delete_by_id_def {
+    ($name:ident) => {
+        paste! {
+            pub async fn [<delete_ $name>]<C: ConnectionTrait>(db: &C, id: i32) -> Result<DeleteResult, DbErr> {
+                let entity: [<$name s>]::ActiveModel = [<$name s>]::Entity::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or(DbErr::Custom(format!("Cannot find {}.", stringify!($name))))
+                    .map(Into::into)?;
+
+                entity.delete(db).await
+            }
+        }
+    };
+}
+
+impl Mutation {
+    pub async fn create_user<C: ConnectionTrait>(
+        db: &C,
+        username: String,
+        nickname: String,
+        password_hash: String,
+        permission: AccessPermission,
+    ) -> Result<users::Model, DbErr> {
+        let registration_date = chrono::Local::now().naive_local().date();
+        users::ActiveModel {
+            name: Set(username),
+            nickname: Set(nickname),
+            password_hash: Set(password_hash),
+            permission: Set(permission),
+            registration_date: Set(registration_date),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn update_user_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        nickname: String,
+        password_hash: String,
+    ) -> Result<users::Model, DbErr> {
+        let new_data: users::ActiveModel = users::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find user.".to_owned()))
+            .map(Into::into)?;
+        users::ActiveModel {
+            id: new_data.id,
+            nickname: Set(nickname),
+            password_hash: Set(password_hash),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_book<C: ConnectionTrait>(
+        db: &C,
+        form_data: books::Model,
+    ) -> Result<books::Model, DbErr> {
+        let books::Model {
+            name,
+            author,
+            publisher,
+            publish_year,
+            isbn,
+            call_number,
+            ..
+        } = form_data;
+        let contributors = parse_contributors(&author);
+        let book = books::ActiveModel {
+            name: Set(name),
+            author: Set(author.trim().to_owned()),
+            publisher: Set(publisher),
+            publish_year: Set(publish_year),
+            isbn: Set(isbn),
+            call_number: Set(call_number),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Self::set_book_contributors(db, book.id, &contributors).await?;
+        Ok(book)
+    }
+
+    pub async fn update_book_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        form_data: books::Model,
+    ) -> Result<books::Model, DbErr> {
+        let new_data: books::ActiveModel = books::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find book.".to_owned()))
+            .map(Into::into)?;
+        let books::Model {
+            name,
+            author,
+            publisher,
+            publish_year,
+            isbn,
+            call_number,
+            ..
+        } = form_data;
+        let contributors = parse_contributors(&author);
+        let book = books::ActiveModel {
+            id: new_data.id,
+            name: Set(name),
+            author: Set(author.trim().to_owned()),
+            publisher: Set(publisher),
+            publish_year: Set(publish_year),
+            isbn: Set(isbn),
+            call_number: Set(call_number),
+        }
+        .update(db)
+        .await?;
+        Self::set_book_contributors(db, book.id, &contributors).await?;
+        Ok(book)
+    }
+
+    /// 替换图书的主题类目
+    pub async fn set_book_categories<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+        category_ids: &[i32],
+    ) -> Result<(), DbErr> {
+        book_categories::Entity::delete_many()
+            .filter(book_categories::Column::BookId.eq(book_id))
+            .exec(db)
+            .await?;
+        for &category_id in category_ids {
+            book_categories::ActiveModel {
+                book_id: Set(book_id),
+                category_id: Set(category_id),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn create_category<C: ConnectionTrait>(
+        db: &C,
+        scheme: CategoryScheme,
+        code: String,
+        name: String,
+        parent_id: Option<i32>,
+    ) -> Result<categories::Model, DbErr> {
+        categories::ActiveModel {
+            scheme: Set(scheme),
+            code: Set(code),
+            name: Set(name),
+            parent_id: Set(parent_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn delete_category<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<DeleteResult, DbErr> {
+        categories::Entity::delete_by_id(id).exec(db).await
+    }
+
+    /// 按顺序替换图书的著者，没有的著者按姓名新建
+    pub async fn set_book_contributors<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+        contributors: &[(String, ContributorRole)],
+    ) -> Result<(), DbErr> {
+        book_contributors::Entity::delete_many()
+            .filter(book_contributors::Column::BookId.eq(book_id))
+            .exec(db)
+            .await?;
+        for (position, (name, role)) in contributors.iter().enumerate() {
+            let author = match Query::find_author_by_name(db, name).await? {
+                Some(author) => author,
+                None => {
+                    authors::ActiveModel {
+                        name: Set(name.clone()),
+                        ..Default::default()
+                    }
+                    .insert(db)
+                    .await?
+                }
+            };
+            book_contributors::ActiveModel {
+                book_id: Set(book_id),
+                author_id: Set(author.id),
+                role: Set(*role),
+                position: Set(position as i32),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn create_book_item<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+        form_data: book_items::Model,
+    ) -> Result<book_items::Model, DbErr> {
+        let book_items::Model {
+            barcode,
+            shelf_location,
+            condition,
+            acquisition_date,
+            item_type,
+            ..
+        } = form_data;
+        book_items::ActiveModel {
+            book_id: Set(book_id),
+            barcode: Set(barcode),
+            shelf_location: Set(shelf_location),
+            condition: Set(condition),
+            acquisition_date: Set(acquisition_date),
+            status: Set(ItemStatus::Available),
+            item_type: Set(item_type),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// 为图书批量添加副本，条码自动生成
+    pub async fn create_book_items<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+        count: u32,
+        shelf_location: String,
+    ) -> Result<Vec<book_items::Model>, DbErr> {
+        let acquisition_date = chrono::Local::now().naive_local().date();
+        let mut items = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let barcode = Query::next_book_item_barcode(db, book_id).await?;
+            let item = book_items::ActiveModel {
+                book_id: Set(book_id),
+                barcode: Set(barcode),
+                shelf_location: Set(shelf_location.clone()),
+                condition: Set(ItemCondition::New),
+                acquisition_date: Set(acquisition_date),
+                status: Set(ItemStatus::Available),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    pub async fn update_book_item_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        form_data: book_items::Model,
+    ) -> Result<book_items::Model, DbErr> {
+        let new_data: book_items::ActiveModel = book_items::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find book item.".to_owned()))
+            .map(Into::into)?;
+        let book_items::Model {
+            barcode,
+            shelf_location,
+            condition,
+            acquisition_date,
+            status,
+            item_type,
+            ..
+        } = form_data;
+        book_items::ActiveModel {
+            id: new_data.id,
+            barcode: Set(barcode),
+            shelf_location: Set(shelf_location),
+            condition: Set(condition),
+            acquisition_date: Set(acquisition_date),
+            status: Set(status),
+            item_type: Set(item_type),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn update_book_item_status_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        status: ItemStatus,
+    ) -> Result<book_items::Model, DbErr> {
+        let new_data: book_items::ActiveModel = book_items::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find book item.".to_owned()))
+            .map(Into::into)?;
+        book_items::ActiveModel {
+            id: new_data.id,
+            status: Set(status),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_borrowed_book<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        item: &book_items::Model,
+        borrow_date: NaiveDate,
+        return_date: NaiveDate,
+        due_at: Option<NaiveDateTime>,
+    ) -> Result<borrowed_books::Model, DbErr> {
+        borrowed_books::ActiveModel {
+            user_id: Set(user_id),
+            book_id: Set(item.book_id),
+            item_id: Set(item.id),
+            borrow_date: Set(borrow_date),
+            return_date: Set(return_date),
+            due_at: Set(due_at),
+            status: Set(LoanStatus::OnLoan),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// 归还时保留借阅记录，只记录归还时间与经办人。借阅已结束或不在借出中时返回 `None`
+    pub async fn return_borrowed_book<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        returned_by: i32,
+        returned_at: NaiveDateTime,
+    ) -> Result<Option<borrowed_books::Model>, DbErr> {
+        Self::close_borrowed_book(
+            db,
+            id,
+            LoanStatus::OnLoan,
+            LoanStatus::Returned,
+            returned_by,
+            returned_at,
+        )
+        .await
+    }
+
+    /// 结束借阅并记录结束方式：归还或登记遗失。只有借阅未结束且状态仍为 `from` 时才会更新，
+    /// 已被其他请求处理时返回 `None`
+    pub async fn close_borrowed_book<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        from: LoanStatus,
+        status: LoanStatus,
+        closed_by: i32,
+        closed_at: NaiveDateTime,
+    ) -> Result<Option<borrowed_books::Model>, DbErr> {
+        let result = borrowed_books::Entity::update_many()
+            .col_expr(borrowed_books::Column::ReturnedAt, sea_query::Expr::value(closed_at))
+            .col_expr(borrowed_books::Column::ReturnedBy, sea_query::Expr::value(closed_by))
+            .col_expr(borrowed_books::Column::Status, sea_query::Expr::value(status))
+            .filter(borrowed_books::Column::Id.eq(id))
+            .filter(borrowed_books::Column::ReturnedAt.is_null())
+            .filter(borrowed_books::Column::Status.eq(from))
+            .exec(db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+        borrowed_books::Entity::find_by_id(id).one(db).await
+    }
+
+    /// 读者声称已还时借阅保留，只标记状态，不再计算罚款。借阅已结束或已登记时返回 `None`
+    pub async fn claim_borrowed_book_returned<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<Option<borrowed_books::Model>, DbErr> {
+        let result = borrowed_books::Entity::update_many()
+            .col_expr(
+                borrowed_books::Column::Status,
+                sea_query::Expr::value(LoanStatus::ClaimedReturned),
+            )
+            .filter(borrowed_books::Column::Id.eq(id))
+            .filter(borrowed_books::Column::ReturnedAt.is_null())
+            .filter(borrowed_books::Column::Status.eq(LoanStatus::OnLoan))
+            .exec(db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+        borrowed_books::Entity::find_by_id(id).one(db).await
+    }
+
+    /// 续借并记录续借日志。只有续借次数仍为读取时的值才会更新，
+    /// 期间已被其他请求续借时返回 `None`
+    pub async fn renew_borrowed_book<C: ConnectionTrait>(
+        db: &C,
+        borrowed_book: &borrowed_books::Model,
+        renewed_by: i32,
+        return_date: NaiveDate,
+        due_at: Option<NaiveDateTime>,
+    ) -> Result<Option<borrowed_books::Model>, DbErr> {
+        let result = borrowed_books::Entity::update_many()
+            .col_expr(borrowed_books::Column::ReturnDate, sea_query::Expr::value(return_date))
+            .col_expr(borrowed_books::Column::DueAt, sea_query::Expr::value(due_at))
+            .col_expr(
+                borrowed_books::Column::Renewals,
+                sea_query::Expr::col(borrowed_books::Column::Renewals).add(1),
+            )
+            .filter(borrowed_books::Column::Id.eq(borrowed_book.id))
+            .filter(borrowed_books::Column::Renewals.eq(borrowed_book.renewals))
+            .filter(borrowed_books::Column::Status.eq(LoanStatus::OnLoan))
+            .exec(db)
+            .await?;
+        if result.rows_affected == 0 {
+            return Ok(None);
+        }
+        loan_renewals::ActiveModel {
+            borrow_id: Set(borrowed_book.id),
+            renewed_at: Set(chrono::Local::now().naive_local()),
+            renewed_by: Set(Some(renewed_by)),
+            old_return_date: Set(borrowed_book.return_date),
+            new_return_date: Set(return_date),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        // 归还日期已改变，按新日期重新发送到期与逾期提醒
+        loan_notices::Entity::delete_many()
+            .filter(loan_notices::Column::BorrowId.eq(borrowed_book.id))
+            .exec(db)
+            .await?;
+        borrowed_books::Entity::find_by_id(borrowed_book.id).one(db).await
+    }
+
+    pub async fn create_circulation_policy<C: ConnectionTrait>(
+        db: &C,
+        form_data: circulation_policies::Model,
+    ) -> Result<circulation_policies::Model, DbErr> {
+        circulation_policies::ActiveModel {
+            id: NotSet,
+            ..form_data.into()
+        }
+        .reset_all()
+        .insert(db)
+        .await
+    }
+
+    pub async fn update_circulation_policy_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        form_data: circulation_policies::Model,
+    ) -> Result<circulation_policies::Model, DbErr> {
+        circulation_policies::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find circulation policy.".to_owned()))?;
+        circulation_policies::ActiveModel {
+            id: Set(id),
+            ..form_data.into()
+        }
+        .reset_all()
+        .update(db)
+        .await
+    }
+
+    pub async fn delete_circulation_policy<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<DeleteResult, DbErr> {
+        circulation_policies::Entity::delete_by_id(id).exec(db).await
+    }
+
+    pub async fn create_user_block<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        reason: BlockReason,
+        note: String,
+        start_date: NaiveDate,
+        end_date: Option<NaiveDate>,
+        created_by: i32,
+    ) -> Result<user_blocks::Model, DbErr> {
+        user_blocks::ActiveModel {
+            user_id: Set(user_id),
+            reason: Set(reason),
+            note: Set(note),
+            start_date: Set(start_date),
+            end_date: Set(end_date),
+            created_at: Set(chrono::Local::now().naive_local()),
+            created_by: Set(Some(created_by)),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn lift_user_block_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        lifted_by: i32,
+    ) -> Result<user_blocks::Model, DbErr> {
+        let new_data: user_blocks::ActiveModel = user_blocks::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find user block.".to_owned()))
+            .map(Into::into)?;
+        user_blocks::ActiveModel {
+            id: new_data.id,
+            lifted_at: Set(Some(chrono::Local::now().naive_local())),
+            lifted_by: Set(Some(lifted_by)),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    /// 解除 `today` 之前已到期的停借，返回解除的数量
+    pub async fn lift_expired_user_blocks<C: ConnectionTrait>(
+        db: &C,
+        today: NaiveDate,
+    ) -> Result<u32, DbErr> {
+        user_blocks::Entity::update_many()
+            .col_expr(
+                user_blocks::Column::LiftedAt,
+                sea_query::Expr::value(chrono::Local::now().naive_local()),
+            )
+            .filter(user_blocks::Column::LiftedAt.is_null())
+            .filter(user_blocks::Column::EndDate.lt(today))
+            .exec(db)
+            .await
+            .map(|result| result.rows_affected as u32)
+    }
+
+    pub async fn create_course<C: ConnectionTrait>(
+        db: &C,
+        form_data: courses::Model,
+    ) -> Result<courses::Model, DbErr> {
+        courses::ActiveModel {
+            id: NotSet,
+            ..form_data.into()
+        }
+        .reset_all()
+        .insert(db)
+        .await
+    }
+
+    pub async fn update_course_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        form_data: courses::Model,
+    ) -> Result<courses::Model, DbErr> {
+        courses::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find course.".to_owned()))?;
+        courses::ActiveModel {
+            id: Set(id),
+            ..form_data.into()
+        }
+        .reset_all()
+        .update(db)
+        .await
+    }
+
+    pub async fn create_course_reserve<C: ConnectionTrait>(
+        db: &C,
+        course_id: i32,
+        book_id: i32,
+    ) -> Result<course_reserves::Model, DbErr> {
+        course_reserves::ActiveModel {
+            course_id: Set(course_id),
+            book_id: Set(book_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn delete_course_reserve<C: ConnectionTrait>(
+        db: &C,
+        course_id: i32,
+        book_id: i32,
+    ) -> Result<DeleteResult, DbErr> {
+        course_reserves::Entity::delete_many()
+            .filter(course_reserves::Column::CourseId.eq(course_id))
+            .filter(course_reserves::Column::BookId.eq(book_id))
+            .exec(db)
+            .await
+    }
+
+    /// 标记 `today` 之前已结束的课程，返回标记的数量
+    pub async fn expire_courses<C: ConnectionTrait>(db: &C, today: NaiveDate) -> Result<u32, DbErr> {
+        courses::Entity::update_many()
+            .col_expr(courses::Column::Expired, sea_query::Expr::value(true))
+            .filter(courses::Column::Expired.eq(false))
+            .filter(courses::Column::EndDate.lt(today))
+            .exec(db)
+            .await
+            .map(|result| result.rows_affected as u32)
+    }
+
+    pub async fn update_user_patron_category_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        patron_category: String,
+    ) -> Result<users::Model, DbErr> {
+        let new_data: users::ActiveModel = users::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find user.".to_owned()))
+            .map(Into::into)?;
+        users::ActiveModel {
+            id: new_data.id,
+            patron_category: Set(patron_category),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn update_user_calendar_token_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        calendar_token: Option<String>,
+    ) -> Result<users::Model, DbErr> {
+        let new_data: users::ActiveModel = users::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find user.".to_owned()))
+            .map(Into::into)?;
+        users::ActiveModel {
+            id: new_data.id,
+            calendar_token: Set(calendar_token),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_fine<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        borrow_id: Option<i32>,
+        amount: i32,
+        assessed_on: NaiveDate,
+        note: String,
+    ) -> Result<fines::Model, DbErr> {
+        fines::ActiveModel {
+            user_id: Set(user_id),
+            borrow_id: Set(borrow_id),
+            amount: Set(amount),
+            created_at: Set(chrono::Local::now().naive_local()),
+            assessed_on: Set(assessed_on),
+            note: Set(note),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn update_fine_amount_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        amount: i32,
+        assessed_on: NaiveDate,
+    ) -> Result<fines::Model, DbErr> {
+        let new_data: fines::ActiveModel = fines::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find fine.".to_owned()))
+            .map(Into::into)?;
+        fines::ActiveModel {
+            id: new_data.id,
+            amount: Set(amount),
+            assessed_on: Set(assessed_on),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_payment<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        fine_id: Option<i32>,
+        kind: PaymentKind,
+        amount: i32,
+        staff_id: i32,
+        note: String,
+    ) -> Result<payments::Model, DbErr> {
+        payments::ActiveModel {
+            user_id: Set(user_id),
+            fine_id: Set(fine_id),
+            kind: Set(kind),
+            amount: Set(amount),
+            created_at: Set(chrono::Local::now().naive_local()),
+            staff_id: Set(Some(staff_id)),
+            note: Set(note),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn create_job_run<C: ConnectionTrait>(
+        db: &C,
+        job_name: &str,
+    ) -> Result<job_runs::Model, DbErr> {
+        job_runs::ActiveModel {
+            job_name: Set(job_name.to_owned()),
+            started_at: Set(chrono::Local::now().naive_local()),
+            status: Set(JobStatus::Running),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn finish_job_run_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        status: JobStatus,
+        processed: i32,
+        message: String,
+    ) -> Result<job_runs::Model, DbErr> {
+        let new_data: job_runs::ActiveModel = job_runs::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find job run.".to_owned()))
+            .map(Into::into)?;
+        job_runs::ActiveModel {
+            id: new_data.id,
+            finished_at: Set(Some(chrono::Local::now().naive_local())),
+            status: Set(status),
+            processed: Set(processed),
+            message: Set(message),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_loan_notice<C: ConnectionTrait>(
+        db: &C,
+        borrow_id: i32,
+        offset_days: i32,
+    ) -> Result<loan_notices::Model, DbErr> {
+        loan_notices::ActiveModel {
+            borrow_id: Set(borrow_id),
+            offset_days: Set(offset_days),
+            sent_at: Set(chrono::Local::now().naive_local()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_item_incident<C: ConnectionTrait>(
+        db: &C,
+        item_id: i32,
+        borrow_id: Option<i32>,
+        user_id: Option<i32>,
+        kind: IncidentKind,
+        fine_id: Option<i32>,
+        note: String,
+        created_by: i32,
+    ) -> Result<item_incidents::Model, DbErr> {
+        item_incidents::ActiveModel {
+            item_id: Set(item_id),
+            borrow_id: Set(borrow_id),
+            user_id: Set(user_id),
+            kind: Set(kind),
+            status: Set(IncidentStatus::Open),
+            fine_id: Set(fine_id),
+            note: Set(note),
+            created_at: Set(chrono::Local::now().naive_local()),
+            created_by: Set(Some(created_by)),
+            resolution_note: Set(String::new()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn resolve_item_incident_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        status: IncidentStatus,
+        resolution_note: String,
+        resolved_by: i32,
+    ) -> Result<item_incidents::Model, DbErr> {
+        let new_data: item_incidents::ActiveModel = item_incidents::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find item incident.".to_owned()))
+            .map(Into::into)?;
+        item_incidents::ActiveModel {
+            id: new_data.id,
+            status: Set(status),
+            resolved_at: Set(Some(chrono::Local::now().naive_local())),
+            resolved_by: Set(Some(resolved_by)),
+            resolution_note: Set(resolution_note),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_ill_request<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        form_data: ill_requests::Model,
+    ) -> Result<ill_requests::Model, DbErr> {
+        let ill_requests::Model {
+            name,
+            author,
+            publisher,
+            publish_year,
+            isbn,
+            note,
+            ..
+        } = form_data;
+        let now = chrono::Local::now().naive_local();
+        ill_requests::ActiveModel {
+            user_id: Set(user_id),
+            name: Set(name),
+            author: Set(author),
+            publisher: Set(publisher),
+            publish_year: Set(publish_year),
+            isbn: Set(isbn),
+            note: Set(note),
+            status: Set(IllStatus::Requested),
+            partner: Set(String::new()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn update_ill_request_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        status: IllStatus,
+        partner: String,
+        item_id: Option<i32>,
+        updated_by: i32,
+    ) -> Result<ill_requests::Model, DbErr> {
+        let new_data: ill_requests::ActiveModel = ill_requests::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find ill request.".to_owned()))
+            .map(Into::into)?;
+        ill_requests::ActiveModel {
+            id: new_data.id,
+            status: Set(status),
+            partner: Set(partner),
+            item_id: Set(item_id),
+            updated_at: Set(chrono::Local::now().naive_local()),
+            updated_by: Set(Some(updated_by)),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn update_opening_hour_by_weekday<C: ConnectionTrait>(
+        db: &C,
+        weekday: i32,
+        open_time: NaiveTime,
+        close_time: NaiveTime,
+        closed: bool,
+    ) -> Result<opening_hours::Model, DbErr> {
+        let new_data: opening_hours::ActiveModel = opening_hours::Entity::find()
+            .filter(opening_hours::Column::Weekday.eq(weekday))
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find opening hour.".to_owned()))
+            .map(Into::into)?;
+        opening_hours::ActiveModel {
+            id: new_data.id,
+            open_time: Set(open_time),
+            close_time: Set(close_time),
+            closed: Set(closed),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_closure<C: ConnectionTrait>(
+        db: &C,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        name: String,
+    ) -> Result<closures::Model, DbErr> {
+        closures::ActiveModel {
+            start_date: Set(start_date),
+            end_date: Set(end_date),
+            name: Set(name),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn create_hold<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        book_id: i32,
+    ) -> Result<holds::Model, DbErr> {
+        holds::ActiveModel {
+            user_id: Set(user_id),
+            book_id: Set(book_id),
+            created_at: Set(chrono::Local::now().naive_local()),
+            status: Set(HoldStatus::Waiting),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn update_hold_status_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        status: HoldStatus,
+    ) -> Result<holds::Model, DbErr> {
+        let new_data: holds::ActiveModel = holds::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find hold.".to_owned()))
+            .map(Into::into)?;
+        holds::ActiveModel {
+            id: new_data.id,
+            status: Set(status),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    /// 为预约保留副本，读者需在取书期限前借走
+    pub async fn set_hold_ready_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+        item_id: i32,
+        pickup_deadline: NaiveDate,
+    ) -> Result<holds::Model, DbErr> {
+        let new_data: holds::ActiveModel = holds::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or(DbErr::Custom("Cannot find hold.".to_owned()))
+            .map(Into::into)?;
+        holds::ActiveModel {
+            id: new_data.id,
+            status: Set(HoldStatus::Ready),
+            item_id: Set(Some(item_id)),
+            pickup_deadline: Set(Some(pickup_deadline)),
+            ..Default::default()
+        }
+        .update(db)
+        .await
+    }
+
+    pub async fn create_email<C: ConnectionTrait>(
+        db: &C,
+        category: EmailCategory,
+        sender_id: i32,
+        recipient_id: i32,
+        subject: String,
+        content: String,
+    ) -> Result<emails::Model, DbErr> {
+        let date_time = chrono::Local::now().naive_local();
+        emails::ActiveModel {
+            category: Set(category),
+            sender_id: Set(sender_id),
+            recipient_id: Set(recipient_id),
+            subject: Set(subject),
+            content: Set(content),
+            date_time: Set(date_time),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    // update_by_id_def!(user, name);
+    // update_by_id_def!(user, password);
+    // update_by_id_def!(borrowed_book, return_date);
+
+    delete_by_id_def!(user);
+    delete_by_id_def!(book);
+    delete_by_id_def!(book_item);
+    delete_by_id_def!(borrowed_book);
+    delete_by_id_def!(email);
+    delete_by_id_def!(closure);
+    delete_by_id_def!(course);
+
+    pub async fn delete_email_by_id_on_sender<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<(), DbErr> {
+        delete_email_by_id_weak(db, id, true).await
+    }
+
+    pub async fn delete_email_by_id_on_recipient<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<(), DbErr> {
+        delete_email_by_id_weak(db, id, false).await
+    }
+}
+
+pub async fn delete_email_by_id_weak<C: ConnectionTrait>(
+    db: &C,
+    id: i32,
+    on_sender: bool,
+) -> Result<(), DbErr> {
+    let entity: emails::ActiveModel = emails::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or(DbErr::Custom("Cannot find email.".to_owned()))
+        .map(Into::into)?;
+
+    let data = if on_sender {
+        emails::ActiveModel {
+            deleted_by_sender: Set(true),
+            ..entity
+        }
+    } else {
+        emails::ActiveModel {
+            deleted_by_recipient: Set(true),
+            ..entity
+        }
+    }
+    .update(db)
+    .await?;
+    if data.deleted_by_sender && data.deleted_by_recipient {
+        Mutation::delete_email(db, id).await?;
+    }
+    Ok(())
+}
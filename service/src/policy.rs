@@ -0,0 +1,504 @@
+use ::entity::{borrowed_books, circulation_policies, courses, LoanStatus};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use sea_orm::*;
+
+use crate::{format_money, Calendar};
+
+/// 匹配任意读者类别或副本类型
+pub const ANY: &str = "*";
+/// 未指定时使用的读者类别与副本类型
+pub const DEFAULT_CATEGORY: &str = "standard";
+
+pub struct Policy;
+
+/// 流通规则拒绝借阅或续借的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyRejection {
+    ReturnDateBeforeToday,
+    LoanPeriodTooLong(i32),
+    TooManyLoans(i32),
+    HasOverdueLoans,
+    BalanceTooHigh(i32),
+    AlreadyReturned,
+    LoanOverdue,
+    TooManyRenewals(i32),
+    HasPendingHolds,
+    PatronBlocked(Option<NaiveDate>),
+    /// 检查之后借阅记录已被其他请求修改
+    LoanChanged,
+}
+
+impl std::fmt::Display for PolicyRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyRejection::ReturnDateBeforeToday => write!(f, "归还日期不能早于当前日期"),
+            PolicyRejection::LoanPeriodTooLong(days) => write!(f, "无法一次性借书超过{}天", days),
+            PolicyRejection::TooManyLoans(max) => write!(f, "最多只能借{}本书", max),
+            PolicyRejection::HasOverdueLoans => write!(f, "有逾期未还的书籍"),
+            PolicyRejection::BalanceTooHigh(max) => {
+                write!(f, "未缴罚款超过{}元，请先缴清", format_money(*max as i64))
+            }
+            PolicyRejection::AlreadyReturned => write!(f, "该书已归还"),
+            PolicyRejection::LoanOverdue => write!(f, "已逾期，请先归还"),
+            PolicyRejection::TooManyRenewals(max) => write!(f, "最多只能续借{}次", max),
+            PolicyRejection::HasPendingHolds => write!(f, "该书有读者预约"),
+            PolicyRejection::PatronBlocked(Some(end_date)) => {
+                write!(f, "读者已被停借至{}", end_date)
+            }
+            PolicyRejection::PatronBlocked(None) => write!(f, "读者已被停借"),
+            PolicyRejection::LoanChanged => write!(f, "借阅记录已更新，请刷新后重试"),
+        }
+    }
+}
+
+impl std::error::Error for PolicyRejection {}
+
+impl Policy {
+    /// 依次按 类别+类型、类别+任意、任意+类型、任意+任意 选取最具体的规则
+    pub async fn find_policy<C: ConnectionTrait>(
+        db: &C,
+        patron_category: &str,
+        item_type: &str,
+    ) -> Result<circulation_policies::Model, DbErr> {
+        let candidates = circulation_policies::Entity::find()
+            .filter(circulation_policies::Column::PatronCategory.is_in([patron_category, ANY]))
+            .filter(circulation_policies::Column::ItemType.is_in([item_type, ANY]))
+            .all(db)
+            .await?;
+        Ok(most_specific(candidates, patron_category, item_type))
+    }
+
+    pub fn check_borrow(
+        policy: &circulation_policies::Model,
+        active_loans: &[borrowed_books::Model],
+        balance: i64,
+        now: NaiveDateTime,
+        return_date: NaiveDate,
+        calendar: &Calendar,
+    ) -> Result<(), PolicyRejection> {
+        let current_date = now.date();
+        if return_date < current_date {
+            return Err(PolicyRejection::ReturnDateBeforeToday);
+        }
+        if (return_date - current_date).num_days() > policy.loan_days as i64 {
+            return Err(PolicyRejection::LoanPeriodTooLong(policy.loan_days));
+        }
+        if active_loans.len() >= policy.max_loans as usize {
+            return Err(PolicyRejection::TooManyLoans(policy.max_loans));
+        }
+        if policy.overdue_blocks
+            && active_loans
+                .iter()
+                .filter(|loan| loan.status == LoanStatus::OnLoan)
+                .any(|loan| Self::is_overdue(policy, loan, now, calendar))
+        {
+            return Err(PolicyRejection::HasOverdueLoans);
+        }
+        if balance > policy.max_balance as i64 {
+            return Err(PolicyRejection::BalanceTooHigh(policy.max_balance));
+        }
+        Ok(())
+    }
+
+    pub fn check_renew(
+        policy: &circulation_policies::Model,
+        loan: &borrowed_books::Model,
+        now: NaiveDateTime,
+        has_pending_holds: bool,
+        calendar: &Calendar,
+    ) -> Result<(), PolicyRejection> {
+        if loan.returned_at.is_some() || loan.status != LoanStatus::OnLoan {
+            return Err(PolicyRejection::AlreadyReturned);
+        }
+        if Self::is_overdue(policy, loan, now, calendar) {
+            return Err(PolicyRejection::LoanOverdue);
+        }
+        if loan.renewals >= policy.max_renewals {
+            return Err(PolicyRejection::TooManyRenewals(policy.max_renewals));
+        }
+        if has_pending_holds {
+            return Err(PolicyRejection::HasPendingHolds);
+        }
+        Ok(())
+    }
+
+    /// 借出时的归还日期，未指定时按借期计算。落在闭馆日时顺延到下一个开放日，
+    /// 顺延后超出借期则提前到之前最近的开放日
+    pub fn due_date(
+        policy: &circulation_policies::Model,
+        current_date: NaiveDate,
+        requested: Option<NaiveDate>,
+        calendar: &Calendar,
+    ) -> NaiveDate {
+        let latest = current_date + chrono::Duration::days(policy.loan_days as i64);
+        let date = requested.unwrap_or(latest);
+        // 不在借期内的日期原样返回，由 `check_borrow` 拒绝
+        if date < current_date || date > latest {
+            return date;
+        }
+        let shifted = calendar.next_open_day(date);
+        if shifted <= latest {
+            return shifted;
+        }
+        (0..=(date - current_date).num_days())
+            .map(|days| date - chrono::Duration::days(days))
+            .find(|&date| calendar.is_open(date))
+            .unwrap_or(shifted)
+    }
+
+    /// 续借后的归还日期，从原归还日期起顺延，遇闭馆日再顺延到下一个开放日
+    pub fn renewed_return_date(
+        policy: &circulation_policies::Model,
+        loan: &borrowed_books::Model,
+        calendar: &Calendar,
+    ) -> NaiveDate {
+        calendar.next_open_day(loan.return_date + chrono::Duration::days(policy.renewal_days as i64))
+    }
+
+    /// 截至 `as_of` 的逾期罚款，宽限期内不计罚款，超出后按全部逾期的开放天数计算并封顶。
+    /// 按小时借出的逾期不足一天按一天计
+    pub fn overdue_fine(
+        policy: &circulation_policies::Model,
+        loan: &borrowed_books::Model,
+        as_of: NaiveDateTime,
+        calendar: &Calendar,
+    ) -> i32 {
+        if !Self::is_overdue(policy, loan, as_of, calendar) {
+            return 0;
+        }
+        let days = match loan.due_at {
+            Some(due_at) => calendar.open_days_between(due_at.date(), as_of.date()).max(1),
+            None => calendar.open_days_between(loan.return_date, as_of.date()),
+        };
+        (days * policy.fine_per_day as i64).min(policy.max_fine as i64) as i32
+    }
+
+    /// 课程期间的参考书改用课程的借期与续借次数，不足一天的借期按一天检查
+    pub fn with_course(
+        mut policy: circulation_policies::Model,
+        course: &courses::Model,
+    ) -> circulation_policies::Model {
+        policy.loan_days = ((course.loan_hours + 23) / 24).max(1);
+        policy.renewal_days = policy.loan_days;
+        policy.max_renewals = course.max_renewals;
+        policy
+    }
+
+    /// 归还日期之后的开放天数超过宽限天数才算逾期，与罚款按开放日计算一致。
+    /// 按小时借出的过了应还时间即算逾期，不设宽限
+    pub fn is_overdue(
+        policy: &circulation_policies::Model,
+        loan: &borrowed_books::Model,
+        now: NaiveDateTime,
+        calendar: &Calendar,
+    ) -> bool {
+        match loan.due_at {
+            Some(due_at) => now > due_at,
+            None => {
+                calendar.open_days_between(loan.return_date, now.date()) > policy.grace_days as i64
+            }
+        }
+    }
+
+    /// 应还的时间点，按日借出的在归还日期当天结束时到期
+    pub fn deadline(loan: &borrowed_books::Model) -> NaiveDateTime {
+        loan.due_at.unwrap_or_else(|| {
+            loan.return_date.and_time(NaiveTime::MIN) + chrono::Duration::days(1)
+        })
+    }
+}
+
+/// 从规则中选出适用于该读者类别与副本类型的最具体的一条
+fn most_specific(
+    candidates: Vec<circulation_policies::Model>,
+    patron_category: &str,
+    item_type: &str,
+) -> circulation_policies::Model {
+    candidates
+        .into_iter()
+        .filter(|policy| {
+            [patron_category, ANY].contains(&policy.patron_category.as_str())
+                && [item_type, ANY].contains(&policy.item_type.as_str())
+        })
+        .min_by_key(|policy| (policy.patron_category == ANY, policy.item_type == ANY))
+        .unwrap_or_else(default_policy)
+}
+
+/// 数据库中没有任何匹配规则时使用，与迁移写入的默认规则一致
+fn default_policy() -> circulation_policies::Model {
+    circulation_policies::Model {
+        id: 0,
+        patron_category: ANY.to_owned(),
+        item_type: ANY.to_owned(),
+        max_loans: 8,
+        loan_days: 30,
+        max_renewals: 2,
+        renewal_days: 30,
+        grace_days: 0,
+        overdue_blocks: true,
+        fine_per_day: 10,
+        max_fine: 1000,
+        max_balance: 500,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::entity::closures;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap()
+    }
+
+    fn at(month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        date(month, day).and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn policy(patron_category: &str, item_type: &str) -> circulation_policies::Model {
+        circulation_policies::Model {
+            patron_category: patron_category.to_owned(),
+            item_type: item_type.to_owned(),
+            max_loans: 2,
+            loan_days: 14,
+            max_renewals: 1,
+            renewal_days: 7,
+            grace_days: 2,
+            ..default_policy()
+        }
+    }
+
+    fn loan(return_date: NaiveDate) -> borrowed_books::Model {
+        borrowed_books::Model {
+            id: 1,
+            user_id: 1,
+            book_id: 1,
+            item_id: 1,
+            borrow_date: return_date - chrono::Duration::days(14),
+            return_date,
+            returned_at: None,
+            returned_by: None,
+            renewals: 0,
+            due_at: None,
+            status: LoanStatus::OnLoan,
+        }
+    }
+
+    fn closed(start_date: NaiveDate, end_date: NaiveDate) -> Calendar {
+        let closure = closures::Model {
+            id: 1,
+            start_date,
+            end_date,
+            name: "闭馆".to_owned(),
+        };
+        Calendar::new(vec![], vec![closure])
+    }
+
+    fn keys(policy: &circulation_policies::Model) -> (&str, &str) {
+        (&policy.patron_category, &policy.item_type)
+    }
+
+    #[test]
+    fn picks_most_specific_policy() {
+        let candidates = vec![
+            policy(ANY, ANY),
+            policy(ANY, "dvd"),
+            policy("student", ANY),
+            policy("student", "dvd"),
+            policy("staff", "dvd"),
+        ];
+        let picked = most_specific(candidates.clone(), "student", "dvd");
+        assert_eq!(keys(&picked), ("student", "dvd"));
+        // 类别优先于类型
+        let picked = most_specific(candidates.clone(), "student", "book");
+        assert_eq!(keys(&picked), ("student", ANY));
+        let picked = most_specific(candidates.clone(), "guest", "dvd");
+        assert_eq!(keys(&picked), (ANY, "dvd"));
+        let picked = most_specific(candidates, "guest", "book");
+        assert_eq!(keys(&picked), (ANY, ANY));
+    }
+
+    #[test]
+    fn falls_back_to_default_policy() {
+        assert_eq!(most_specific(vec![], "student", "dvd"), default_policy());
+        let picked = most_specific(vec![policy("staff", "dvd")], "student", "dvd");
+        assert_eq!(picked, default_policy());
+    }
+
+    #[test]
+    fn borrow_period_limits() {
+        let policy = policy(ANY, ANY);
+        let open = Calendar::new(vec![], vec![]);
+        let today = date(7, 1);
+        let now = at(7, 1, 10);
+        assert_eq!(
+            Policy::check_borrow(&policy, &[], 0, now, date(6, 30), &open),
+            Err(PolicyRejection::ReturnDateBeforeToday)
+        );
+        assert_eq!(Policy::check_borrow(&policy, &[], 0, now, today, &open), Ok(()));
+        assert_eq!(Policy::check_borrow(&policy, &[], 0, now, date(7, 15), &open), Ok(()));
+        assert_eq!(
+            Policy::check_borrow(&policy, &[], 0, now, date(7, 16), &open),
+            Err(PolicyRejection::LoanPeriodTooLong(14))
+        );
+    }
+
+    #[test]
+    fn borrow_loan_count_and_balance_limits() {
+        let policy = policy(ANY, ANY);
+        let open = Calendar::new(vec![], vec![]);
+        let now = at(7, 1, 10);
+        let due = date(7, 10);
+        let one = [loan(due)];
+        assert_eq!(Policy::check_borrow(&policy, &one, 0, now, due, &open), Ok(()));
+        let two = [loan(due), loan(due)];
+        assert_eq!(
+            Policy::check_borrow(&policy, &two, 0, now, due, &open),
+            Err(PolicyRejection::TooManyLoans(2))
+        );
+        assert_eq!(Policy::check_borrow(&policy, &one, 500, now, due, &open), Ok(()));
+        assert_eq!(
+            Policy::check_borrow(&policy, &one, 501, now, due, &open),
+            Err(PolicyRejection::BalanceTooHigh(500))
+        );
+    }
+
+    #[test]
+    fn overdue_loans_block_borrowing() {
+        let mut policy = policy(ANY, ANY);
+        let open = Calendar::new(vec![], vec![]);
+        let today = date(7, 10);
+        let now = at(7, 10, 10);
+        // 宽限期内不算逾期
+        let loans = [loan(date(7, 8))];
+        assert_eq!(Policy::check_borrow(&policy, &loans, 0, now, today, &open), Ok(()));
+        let loans = [loan(date(7, 7))];
+        assert_eq!(
+            Policy::check_borrow(&policy, &loans, 0, now, today, &open),
+            Err(PolicyRejection::HasOverdueLoans)
+        );
+        // 声称已还的借阅不再算作逾期
+        let mut claimed = loan(date(7, 7));
+        claimed.status = LoanStatus::ClaimedReturned;
+        assert_eq!(Policy::check_borrow(&policy, &[claimed], 0, now, today, &open), Ok(()));
+        policy.overdue_blocks = false;
+        assert_eq!(Policy::check_borrow(&policy, &loans, 0, now, today, &open), Ok(()));
+    }
+
+    #[test]
+    fn renew_edge_cases() {
+        let policy = policy(ANY, ANY);
+        let open = Calendar::new(vec![], vec![]);
+        let now = at(7, 10, 10);
+        let mut renewable = loan(date(7, 8));
+        assert_eq!(Policy::check_renew(&policy, &renewable, now, false, &open), Ok(()));
+        assert_eq!(
+            Policy::check_renew(&policy, &renewable, now, true, &open),
+            Err(PolicyRejection::HasPendingHolds)
+        );
+        renewable.renewals = 1;
+        assert_eq!(
+            Policy::check_renew(&policy, &renewable, now, false, &open),
+            Err(PolicyRejection::TooManyRenewals(1))
+        );
+        let overdue = loan(date(7, 7));
+        assert_eq!(
+            Policy::check_renew(&policy, &overdue, now, false, &open),
+            Err(PolicyRejection::LoanOverdue)
+        );
+        let mut claimed = loan(date(7, 8));
+        claimed.status = LoanStatus::ClaimedReturned;
+        assert_eq!(
+            Policy::check_renew(&policy, &claimed, now, false, &open),
+            Err(PolicyRejection::AlreadyReturned)
+        );
+        let mut returned = loan(date(7, 7));
+        returned.returned_at = Some(now);
+        assert_eq!(
+            Policy::check_renew(&policy, &returned, now, false, &open),
+            Err(PolicyRejection::AlreadyReturned)
+        );
+    }
+
+    #[test]
+    fn renewal_extends_from_return_date_to_open_day() {
+        let policy = policy(ANY, ANY);
+        let loan = loan(date(7, 3));
+        let calendar = Calendar::new(vec![], vec![]);
+        assert_eq!(Policy::renewed_return_date(&policy, &loan, &calendar), date(7, 10));
+        let calendar = closed(date(7, 10), date(7, 11));
+        assert_eq!(Policy::renewed_return_date(&policy, &loan, &calendar), date(7, 12));
+    }
+
+    #[test]
+    fn overdue_after_grace_days() {
+        let mut policy = policy(ANY, ANY);
+        let open = Calendar::new(vec![], vec![]);
+        let loan = loan(date(7, 1));
+        assert!(!Policy::is_overdue(&policy, &loan, at(7, 1, 10), &open));
+        assert!(!Policy::is_overdue(&policy, &loan, at(7, 3, 10), &open));
+        assert!(Policy::is_overdue(&policy, &loan, at(7, 4, 10), &open));
+        policy.grace_days = 0;
+        assert!(Policy::is_overdue(&policy, &loan, at(7, 2, 10), &open));
+    }
+
+    #[test]
+    fn due_date_shifts_to_open_day_within_loan_period() {
+        let policy = policy(ANY, ANY);
+        let today = date(7, 1);
+        let now = at(7, 1, 10);
+        let open = Calendar::new(vec![], vec![]);
+        assert_eq!(Policy::due_date(&policy, today, None, &open), date(7, 15));
+        assert_eq!(Policy::due_date(&policy, today, Some(date(7, 5)), &open), date(7, 5));
+        // 顺延到 7 月 8 日仍在借期内
+        let calendar = closed(date(7, 5), date(7, 7));
+        assert_eq!(Policy::due_date(&policy, today, Some(date(7, 5)), &calendar), date(7, 8));
+        // 借期最后一天闭馆，顺延会超出借期，改为提前
+        let calendar = closed(date(7, 14), date(7, 16));
+        let due = Policy::due_date(&policy, today, None, &calendar);
+        assert_eq!(due, date(7, 13));
+        assert_eq!(Policy::check_borrow(&policy, &[], 0, now, due, &calendar), Ok(()));
+        // 不在借期内的日期不调整，交给规则检查拒绝
+        let calendar = closed(date(6, 30), date(7, 20));
+        assert_eq!(Policy::due_date(&policy, today, Some(date(6, 30)), &calendar), date(6, 30));
+        assert_eq!(Policy::due_date(&policy, today, Some(date(7, 16)), &calendar), date(7, 16));
+    }
+
+    #[test]
+    fn grace_days_count_open_days() {
+        let policy = policy(ANY, ANY);
+        let loan = loan(date(7, 1));
+        // 7 月 2 日至 4 日闭馆，宽限期顺延到 7 月 6 日
+        let calendar = closed(date(7, 2), date(7, 4));
+        assert!(!Policy::is_overdue(&policy, &loan, at(7, 6, 10), &calendar));
+        assert!(Policy::is_overdue(&policy, &loan, at(7, 7, 10), &calendar));
+        assert_eq!(Policy::overdue_fine(&policy, &loan, at(7, 6, 10), &calendar), 0);
+        assert_eq!(Policy::overdue_fine(&policy, &loan, at(7, 7, 10), &calendar), 30);
+    }
+
+    #[test]
+    fn hourly_loans_overdue_after_due_time() {
+        let policy = policy(ANY, ANY);
+        let open = Calendar::new(vec![], vec![]);
+        let mut loan = loan(date(7, 1));
+        loan.due_at = Some(at(7, 1, 10));
+        // 过了应还时间即算逾期，不等到第二天，也不适用宽限期
+        assert!(!Policy::is_overdue(&policy, &loan, at(7, 1, 10), &open));
+        assert!(Policy::is_overdue(&policy, &loan, at(7, 1, 11), &open));
+        assert_eq!(
+            Policy::check_renew(&policy, &loan, at(7, 1, 11), false, &open),
+            Err(PolicyRejection::LoanOverdue)
+        );
+        assert_eq!(
+            Policy::check_borrow(&policy, &[loan.clone()], 0, at(7, 1, 11), date(7, 2), &open),
+            Err(PolicyRejection::HasOverdueLoans)
+        );
+        // 不足一天按一天计
+        assert_eq!(Policy::overdue_fine(&policy, &loan, at(7, 1, 11), &open), 10);
+        assert_eq!(Policy::overdue_fine(&policy, &loan, at(7, 3, 9), &open), 20);
+        assert_eq!(Policy::deadline(&loan), at(7, 1, 10));
+        loan.due_at = None;
+        assert_eq!(Policy::deadline(&loan), at(7, 2, 0));
+    }
+}
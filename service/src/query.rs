@@ -1,15 +1,44 @@
 use ::entity::{
-    books, borrowed_books, emails, users, AccessPermission, BorrowedBooksResult,
-    BorrowedBooksResultForBook, BorrowedBooksResultForUser, Email, IdResult,
+    authors, book_categories, book_contributors, book_items, books, borrowed_books, categories,
+    circulation_policies, closures, course_reserves, courses, emails, fines, holds, ill_requests,
+    item_incidents, job_runs, loan_notices, loan_renewals, opening_hours, payments, user_blocks,
+    users, AccessPermission, AuthorBooksResult, AuthorsResult, BooksResult, BorrowedBooksResult,
+    BorrowedBooksResultForBook, BorrowedBooksResultForUser, CategoryScheme, ContributorsResult,
+    Email, FinesResult, HoldStatus, HoldsResult, IdResult, IllRequestsResult, IllStatus,
+    IncidentStatus, ItemIncidentsResult, ItemStatus, JobStatus, LoanHistoryResult,
+    LoanRenewalsResult, PaymentKind,
 };
+use chrono::NaiveDate;
 use paste::paste;
 use sea_orm::{
-    sea_query::{Alias, Expr, SimpleExpr},
+    sea_query::{self, Alias, Expr, SimpleExpr},
     *,
 };
 
+use crate::ILL_ITEM_TYPE;
+
 pub struct Query;
 
+/// 借阅记录的状态：未归还的为当前借阅，已归还的为历史记录
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoanState {
+    Active,
+    Returned,
+    All,
+}
+
+impl LoanState {
+    fn condition(self) -> Condition {
+        match self {
+            LoanState::Active => Condition::all().add(borrowed_books::Column::ReturnedAt.is_null()),
+            LoanState::Returned => {
+                Condition::all().add(borrowed_books::Column::ReturnedAt.is_not_null())
+            }
+            LoanState::All => Condition::all(),
+        }
+    }
+}
+
 macro_rules! basic_query_def {
     ($name:ident) => {
         paste!{
@@ -56,17 +85,41 @@ macro_rules! query_by_field_unique_def {
 }
 
 impl Query {
+    basic_query_def!(author);
     basic_query_def!(book);
+    basic_query_def!(book_item);
     basic_query_def!(user);
     basic_query_def!(borrowed_book);
     basic_query_def!(email);
+    basic_query_def!(hold);
+    basic_query_def!(fine);
+    basic_query_def!(course);
+    basic_query_def!(user_block);
     query_by_field_unique_def!(user, name);
+    query_by_field_unique_def!(author, name);
+    query_by_field_unique_def!(user, calendar_token);
     query_by_field_def!(book, name);
-    query_by_field_def!(book, author);
-    query_by_field_def!(borrowed_book, user_id);
-    query_by_field_def!(borrowed_book, book_id);
+    query_by_field_def!(book, isbn);
+    query_by_field_def!(book_item, book_id);
+    query_by_field_unique_def!(book_item, barcode);
     query_by_field_def!(email, sender_id);
     query_by_field_def!(email, recipient_id);
+    query_by_field_unique_def!(fine, borrow_id);
+    query_by_field_def!(payment, user_id);
+
+    /// 按用户名或读者证条码查找读者，用户名优先
+    pub async fn find_user_by_card<C: ConnectionTrait>(
+        db: &C,
+        code: &str,
+    ) -> Result<Option<users::Model>, DbErr> {
+        if let Some(user) = Self::find_user_by_name(db, code).await? {
+            return Ok(Some(user));
+        }
+        match parse_library_card_number(code) {
+            Some(user_id) => Self::find_user_by_id(db, user_id).await,
+            None => Ok(None),
+        }
+    }
 
     pub async fn find_admin_ids<C: ConnectionTrait>(db: &C) -> Result<Vec<i32>, DbErr> {
         users::Entity::find()
@@ -118,16 +171,65 @@ impl Query {
             .await
     }
 
+    pub async fn find_borrowed_books_by_user_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        state: LoanState,
+    ) -> Result<Vec<borrowed_books::Model>, DbErr> {
+        borrowed_books::Entity::find()
+            .filter(borrowed_books::Column::UserId.eq(user_id))
+            .filter(state.condition())
+            .all(db)
+            .await
+    }
+
+    pub async fn find_borrowed_books_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+        state: LoanState,
+    ) -> Result<Vec<borrowed_books::Model>, DbErr> {
+        borrowed_books::Entity::find()
+            .filter(borrowed_books::Column::BookId.eq(book_id))
+            .filter(state.condition())
+            .all(db)
+            .await
+    }
+
+    pub async fn find_borrowed_books_by_item_id<C: ConnectionTrait>(
+        db: &C,
+        item_id: i32,
+        state: LoanState,
+    ) -> Result<Vec<borrowed_books::Model>, DbErr> {
+        borrowed_books::Entity::find()
+            .filter(borrowed_books::Column::ItemId.eq(item_id))
+            .filter(state.condition())
+            .all(db)
+            .await
+    }
+
+    pub async fn find_borrowed_books_by_state<C: ConnectionTrait>(
+        db: &C,
+        state: LoanState,
+    ) -> Result<Vec<borrowed_books::Model>, DbErr> {
+        borrowed_books::Entity::find()
+            .filter(state.condition())
+            .all(db)
+            .await
+    }
+
     pub async fn find_borrowed_books_detail_by_user_id<C: ConnectionTrait>(
         db: &C,
         user_id: i32,
+        state: LoanState,
     ) -> Result<Vec<BorrowedBooksResultForBook>, DbErr> {
         borrowed_books::Entity::find()
             .column_as(borrowed_books::Column::Id, "borrow_id")
             .column_as(books::Column::Name, "book_name")
             .column_as(books::Column::Author, "book_author")
             .column_as(books::Column::Isbn, "isbn")
+            .column_as(book_items::Column::Barcode, "barcode")
             .filter(borrowed_books::Column::UserId.eq(user_id))
+            .filter(state.condition())
             .join(
                 JoinType::InnerJoin,
                 borrowed_books::Entity::belongs_to(books::Entity)
@@ -135,6 +237,7 @@ impl Query {
                     .to(books::Column::Id)
                     .into(),
             )
+            .join(JoinType::InnerJoin, borrowed_books::Relation::BookItems.def())
             .into_model::<BorrowedBooksResultForBook>()
             .all(db)
             .await
@@ -142,13 +245,16 @@ impl Query {
 
     pub async fn find_borrowed_books_detail_by_book_id<C: ConnectionTrait>(
         db: &C,
-        user_id: i32,
+        book_id: i32,
+        state: LoanState,
     ) -> Result<Vec<BorrowedBooksResultForUser>, DbErr> {
         borrowed_books::Entity::find()
             .column_as(borrowed_books::Column::Id, "borrow_id")
             .column_as(users::Column::Name, "user_name")
             .column_as(users::Column::Nickname, "user_nickname")
-            .filter(borrowed_books::Column::BookId.eq(user_id))
+            .column_as(book_items::Column::Barcode, "barcode")
+            .filter(borrowed_books::Column::BookId.eq(book_id))
+            .filter(state.condition())
             .join(
                 JoinType::InnerJoin,
                 borrowed_books::Entity::belongs_to(users::Entity)
@@ -156,6 +262,7 @@ impl Query {
                     .to(users::Column::Id)
                     .into(),
             )
+            .join(JoinType::InnerJoin, borrowed_books::Relation::BookItems.def())
             .order_by_asc(borrowed_books::Column::ReturnDate)
             .into_model::<BorrowedBooksResultForUser>()
             .all(db)
@@ -182,34 +289,688 @@ impl Query {
 
     pub async fn find_borrowed_books_detail_in_page<C: ConnectionTrait>(
         db: &C,
+        state: LoanState,
         page: u64,
         number_per_page: u64,
     ) -> Result<(Vec<BorrowedBooksResult>, u64), DbErr> {
-        find_borrowed_books_in_page(db, page, number_per_page).await
+        find_borrowed_books_in_page(db, state, page, number_per_page).await
+    }
+
+    pub async fn find_loan_history_in_page_by_user_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<LoanHistoryResult>, u64), DbErr> {
+        find_loan_history_in_page(
+            db,
+            borrowed_books::Column::UserId.eq(user_id),
+            page,
+            number_per_page,
+        )
+        .await
+    }
+
+    pub async fn find_loan_history_in_page_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<LoanHistoryResult>, u64), DbErr> {
+        find_loan_history_in_page(
+            db,
+            borrowed_books::Column::BookId.eq(book_id),
+            page,
+            number_per_page,
+        )
+        .await
+    }
+
+    /// 一组借阅记录的续借明细，按续借时间排列
+    pub async fn find_loan_renewals_by_borrow_ids<C: ConnectionTrait>(
+        db: &C,
+        borrow_ids: Vec<i32>,
+    ) -> Result<Vec<LoanRenewalsResult>, DbErr> {
+        loan_renewals::Entity::find()
+            .select_only()
+            .column(loan_renewals::Column::BorrowId)
+            .column(loan_renewals::Column::RenewedAt)
+            .column(loan_renewals::Column::OldReturnDate)
+            .column(loan_renewals::Column::NewReturnDate)
+            .column_as(users::Column::Name, "renewed_by_name")
+            .filter(loan_renewals::Column::BorrowId.is_in(borrow_ids))
+            .join(JoinType::LeftJoin, loan_renewals::Relation::Users.def())
+            .order_by_asc(loan_renewals::Column::RenewedAt)
+            .into_model::<LoanRenewalsResult>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_circulation_policy_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<Option<circulation_policies::Model>, DbErr> {
+        circulation_policies::Entity::find_by_id(id).one(db).await
+    }
+
+    pub async fn find_circulation_policies<C: ConnectionTrait>(
+        db: &C,
+    ) -> Result<Vec<circulation_policies::Model>, DbErr> {
+        circulation_policies::Entity::find()
+            .order_by_asc(circulation_policies::Column::PatronCategory)
+            .order_by_asc(circulation_policies::Column::ItemType)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_circulation_policy_by_key<C: ConnectionTrait>(
+        db: &C,
+        patron_category: &str,
+        item_type: &str,
+    ) -> Result<Option<circulation_policies::Model>, DbErr> {
+        circulation_policies::Entity::find()
+            .filter(circulation_policies::Column::PatronCategory.eq(patron_category))
+            .filter(circulation_policies::Column::ItemType.eq(item_type))
+            .one(db)
+            .await
+    }
+
+    /// 罚款明细，附带图书名称与已减免金额
+    pub async fn find_fines_detail_by_user_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+    ) -> Result<Vec<FinesResult>, DbErr> {
+        let mut waived = sea_query::Query::select();
+        waived
+            .expr(Expr::col((payments::Entity, payments::Column::Amount)).sum())
+            .from(payments::Entity)
+            .and_where(
+                Expr::col((payments::Entity, payments::Column::FineId))
+                    .equals((fines::Entity, fines::Column::Id)),
+            )
+            .and_where(Expr::col((payments::Entity, payments::Column::Kind)).eq(PaymentKind::Waiver));
+        let waived = SimpleExpr::SubQuery(None, Box::new(waived.into_sub_query_statement()));
+
+        fines::Entity::find()
+            .select_only()
+            .column_as(fines::Column::Id, "fine_id")
+            .column(fines::Column::BorrowId)
+            .column(fines::Column::Amount)
+            .column(fines::Column::AssessedOn)
+            .column(fines::Column::Note)
+            .column_as(books::Column::Name, "book_name")
+            .column_as(
+                Into::<SimpleExpr>::into(sea_query::Func::coalesce([waived, Expr::val(0).into()])),
+                "waived",
+            )
+            .filter(fines::Column::UserId.eq(user_id))
+            .join(JoinType::LeftJoin, fines::Relation::BorrowedBooks.def())
+            .join(JoinType::LeftJoin, borrowed_books::Relation::Books.def())
+            .order_by_desc(fines::Column::CreatedAt)
+            .into_model::<FinesResult>()
+            .all(db)
+            .await
+    }
+
+    /// 未结清金额：罚款总额减去缴款与减免总额，单位为分
+    pub async fn find_user_balance<C: ConnectionTrait>(db: &C, user_id: i32) -> Result<i64, DbErr> {
+        let charged = fines::Entity::find()
+            .select_only()
+            .column_as(fines::Column::Amount.sum(), "sum")
+            .filter(fines::Column::UserId.eq(user_id))
+            .into_tuple::<Option<i64>>()
+            .one(db)
+            .await?
+            .flatten()
+            .unwrap_or(0);
+        let paid = payments::Entity::find()
+            .select_only()
+            .column_as(payments::Column::Amount.sum(), "sum")
+            .filter(payments::Column::UserId.eq(user_id))
+            .into_tuple::<Option<i64>>()
+            .one(db)
+            .await?
+            .flatten()
+            .unwrap_or(0);
+        Ok(charged - paid)
+    }
+
+    /// 任务运行记录，最近的在前
+    pub async fn find_job_runs_in_page<C: ConnectionTrait>(
+        db: &C,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<job_runs::Model>, u64), DbErr> {
+        let paginator = job_runs::Entity::find()
+            .order_by_desc(job_runs::Column::Id)
+            .paginate(db, number_per_page);
+        let num_pages = paginator.num_pages().await?;
+        // Fetch paginated posts
+        paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
+    }
+
+    pub async fn find_last_job_run_by_name<C: ConnectionTrait>(
+        db: &C,
+        job_name: &str,
+        status: JobStatus,
+    ) -> Result<Option<job_runs::Model>, DbErr> {
+        job_runs::Entity::find()
+            .filter(job_runs::Column::JobName.eq(job_name))
+            .filter(job_runs::Column::Status.eq(status))
+            .order_by_desc(job_runs::Column::Id)
+            .one(db)
+            .await
+    }
+
+    /// 借阅已发送的最后一次提醒对应的天数
+    pub async fn find_last_loan_notice_offset<C: ConnectionTrait>(
+        db: &C,
+        borrow_id: i32,
+    ) -> Result<Option<i32>, DbErr> {
+        loan_notices::Entity::find()
+            .select_only()
+            .column_as(loan_notices::Column::OffsetDays.max(), "offset_days")
+            .filter(loan_notices::Column::BorrowId.eq(borrow_id))
+            .into_tuple::<Option<i32>>()
+            .one(db)
+            .await
+            .map(Option::flatten)
+    }
+
+    pub async fn find_item_incident_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<Option<item_incidents::Model>, DbErr> {
+        item_incidents::Entity::find_by_id(id).one(db).await
+    }
+
+    /// 借阅尚未处理的遗失、声称已还或损坏登记
+    pub async fn find_open_item_incident_by_borrow_id<C: ConnectionTrait>(
+        db: &C,
+        borrow_id: i32,
+    ) -> Result<Option<item_incidents::Model>, DbErr> {
+        item_incidents::Entity::find()
+            .filter(item_incidents::Column::BorrowId.eq(borrow_id))
+            .filter(item_incidents::Column::Status.eq(IncidentStatus::Open))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_item_incidents_detail_in_page<C: ConnectionTrait>(
+        db: &C,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<ItemIncidentsResult>, u64), DbErr> {
+        let paginator = select_item_incidents_detail()
+            .into_model::<ItemIncidentsResult>()
+            .paginate(db, number_per_page);
+        let num_pages = paginator.num_pages().await?;
+        // Fetch paginated posts
+        paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
+    }
+
+    pub async fn find_item_incidents_detail_by_user_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+    ) -> Result<Vec<ItemIncidentsResult>, DbErr> {
+        select_item_incidents_detail()
+            .filter(item_incidents::Column::UserId.eq(user_id))
+            .into_model::<ItemIncidentsResult>()
+            .all(db)
+            .await
+    }
+
+    /// `today` 仍在停借期间且未解除的停借记录
+    pub async fn find_active_user_block<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        today: NaiveDate,
+    ) -> Result<Option<user_blocks::Model>, DbErr> {
+        user_blocks::Entity::find()
+            .filter(user_blocks::Column::UserId.eq(user_id))
+            .filter(user_blocks::Column::LiftedAt.is_null())
+            .filter(user_blocks::Column::StartDate.lte(today))
+            .filter(
+                Condition::any()
+                    .add(user_blocks::Column::EndDate.is_null())
+                    .add(user_blocks::Column::EndDate.gte(today)),
+            )
+            // 同时有多条停借时取结束最晚的，永久停借优先
+            .order_by_desc(Expr::col(user_blocks::Column::EndDate).is_null())
+            .order_by_desc(user_blocks::Column::EndDate)
+            .one(db)
+            .await
+    }
+
+    pub async fn find_user_blocks_by_user_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+    ) -> Result<Vec<user_blocks::Model>, DbErr> {
+        user_blocks::Entity::find()
+            .filter(user_blocks::Column::UserId.eq(user_id))
+            .order_by_desc(user_blocks::Column::CreatedAt)
+            .all(db)
+            .await
+    }
+
+    /// 课程按开始日期从近到远排列
+    pub async fn find_courses<C: ConnectionTrait>(db: &C) -> Result<Vec<courses::Model>, DbErr> {
+        courses::Entity::find()
+            .order_by_desc(courses::Column::StartDate)
+            .order_by_desc(courses::Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// `today` 在课程期间且尚未到期的课程中，列出该书且借期最短的一门
+    pub async fn find_active_course_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+        today: NaiveDate,
+    ) -> Result<Option<courses::Model>, DbErr> {
+        courses::Entity::find()
+            .join(JoinType::InnerJoin, courses::Relation::CourseReserves.def())
+            .filter(course_reserves::Column::BookId.eq(book_id))
+            .filter(courses::Column::Expired.eq(false))
+            .filter(courses::Column::StartDate.lte(today))
+            .filter(courses::Column::EndDate.gte(today))
+            .order_by_asc(courses::Column::LoanHours)
+            .one(db)
+            .await
+    }
+
+    /// 课程参考书列表，附带副本总数与可借数量
+    pub async fn find_course_reserve_books<C: ConnectionTrait>(
+        db: &C,
+        course_id: i32,
+    ) -> Result<Vec<BooksResult>, DbErr> {
+        select_books_detail()
+            .filter(
+                books::Column::Id.in_subquery(
+                    sea_query::Query::select()
+                        .column(course_reserves::Column::BookId)
+                        .from(course_reserves::Entity)
+                        .and_where(course_reserves::Column::CourseId.eq(course_id))
+                        .to_owned(),
+                ),
+            )
+            .order_by_asc(books::Column::Name)
+            .into_model::<BooksResult>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_course_reserve<C: ConnectionTrait>(
+        db: &C,
+        course_id: i32,
+        book_id: i32,
+    ) -> Result<Option<course_reserves::Model>, DbErr> {
+        course_reserves::Entity::find()
+            .filter(course_reserves::Column::CourseId.eq(course_id))
+            .filter(course_reserves::Column::BookId.eq(book_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn find_ill_request_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<Option<ill_requests::Model>, DbErr> {
+        ill_requests::Entity::find_by_id(id).one(db).await
+    }
+
+    pub async fn find_ill_requests_detail_in_page<C: ConnectionTrait>(
+        db: &C,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<IllRequestsResult>, u64), DbErr> {
+        let paginator = select_ill_requests_detail()
+            .into_model::<IllRequestsResult>()
+            .paginate(db, number_per_page);
+        let num_pages = paginator.num_pages().await?;
+        // Fetch paginated posts
+        paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
+    }
+
+    pub async fn find_ill_requests_detail_by_user_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+    ) -> Result<Vec<IllRequestsResult>, DbErr> {
+        select_ill_requests_detail()
+            .filter(ill_requests::Column::UserId.eq(user_id))
+            .into_model::<IllRequestsResult>()
+            .all(db)
+            .await
+    }
+
+    /// 罚款已减免的部分，单位为分
+    pub async fn find_fine_waived<C: ConnectionTrait>(db: &C, fine_id: i32) -> Result<i64, DbErr> {
+        Ok(payments::Entity::find()
+            .select_only()
+            .column_as(payments::Column::Amount.sum(), "sum")
+            .filter(payments::Column::FineId.eq(fine_id))
+            .filter(payments::Column::Kind.eq(PaymentKind::Waiver))
+            .into_tuple::<Option<i64>>()
+            .one(db)
+            .await?
+            .flatten()
+            .unwrap_or(0))
+    }
+
+    /// 罚款尚未缴清或减免的部分，单位为分
+    pub async fn find_fine_remaining<C: ConnectionTrait>(
+        db: &C,
+        fine: &fines::Model,
+    ) -> Result<i64, DbErr> {
+        let settled = payments::Entity::find()
+            .select_only()
+            .column_as(payments::Column::Amount.sum(), "sum")
+            .filter(payments::Column::FineId.eq(fine.id))
+            .into_tuple::<Option<i64>>()
+            .one(db)
+            .await?
+            .flatten()
+            .unwrap_or(0);
+        Ok((fine.amount as i64 - settled).max(0))
+    }
+
+    /// 一周七天的开放时间，从周一开始
+    pub async fn find_opening_hours<C: ConnectionTrait>(
+        db: &C,
+    ) -> Result<Vec<opening_hours::Model>, DbErr> {
+        opening_hours::Entity::find()
+            .order_by_asc(opening_hours::Column::Weekday)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_closures<C: ConnectionTrait>(
+        db: &C,
+    ) -> Result<Vec<closures::Model>, DbErr> {
+        closures::Entity::find()
+            .order_by_asc(closures::Column::StartDate)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_available_book_item_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+    ) -> Result<Option<book_items::Model>, DbErr> {
+        book_items::Entity::find()
+            .filter(book_items::Column::BookId.eq(book_id))
+            .filter(book_items::Column::Status.eq(ItemStatus::Available))
+            .filter(book_items::Column::ItemType.ne(ILL_ITEM_TYPE))
+            .order_by_asc(book_items::Column::Id)
+            .one(db)
+            .await
+    }
+
+    /// 生成下一个未被占用的自动条码
+    pub async fn next_book_item_barcode<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+    ) -> Result<String, DbErr> {
+        let mut n = book_items::Entity::find()
+            .filter(book_items::Column::BookId.eq(book_id))
+            .count(db)
+            .await?
+            + 1;
+        loop {
+            let barcode = book_item_barcode(book_id, n);
+            if Self::find_book_item_by_barcode(db, &barcode).await?.is_none() {
+                return Ok(barcode);
+            }
+            n += 1;
+        }
+    }
+
+    /// 读者对某本书尚未完成的预约（排队中或已到书）
+    pub async fn find_active_hold_by_user_id_and_book_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        book_id: i32,
+    ) -> Result<Option<holds::Model>, DbErr> {
+        holds::Entity::find()
+            .filter(holds::Column::UserId.eq(user_id))
+            .filter(holds::Column::BookId.eq(book_id))
+            .filter(active_holds())
+            .one(db)
+            .await
+    }
+
+    /// 按预约先后顺序取排在最前的等待中预约
+    pub async fn find_next_waiting_hold_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+    ) -> Result<Option<holds::Model>, DbErr> {
+        holds::Entity::find()
+            .filter(holds::Column::BookId.eq(book_id))
+            .filter(holds::Column::Status.eq(HoldStatus::Waiting))
+            .order_by_asc(holds::Column::CreatedAt)
+            .order_by_asc(holds::Column::Id)
+            .one(db)
+            .await
+    }
+
+    /// 已到书但超过取书期限的预约
+    pub async fn find_overdue_ready_holds<C: ConnectionTrait>(
+        db: &C,
+        today: NaiveDate,
+    ) -> Result<Vec<holds::Model>, DbErr> {
+        holds::Entity::find()
+            .filter(holds::Column::Status.eq(HoldStatus::Ready))
+            .filter(holds::Column::PickupDeadline.lt(today))
+            .all(db)
+            .await
+    }
+
+    pub async fn find_holds_detail_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+    ) -> Result<Vec<HoldsResult>, DbErr> {
+        select_holds_detail()
+            .filter(holds::Column::BookId.eq(book_id))
+            .into_model::<HoldsResult>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_holds_detail_by_user_id<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+    ) -> Result<Vec<HoldsResult>, DbErr> {
+        select_holds_detail()
+            .filter(holds::Column::UserId.eq(user_id))
+            .into_model::<HoldsResult>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_holds_detail_in_page<C: ConnectionTrait>(
+        db: &C,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<HoldsResult>, u64), DbErr> {
+        let paginator = select_holds_detail()
+            .into_model::<HoldsResult>()
+            .paginate(db, number_per_page);
+        let num_pages = paginator.num_pages().await?;
+        // Fetch paginated posts
+        paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
+    }
+
+    /// `category_ids` 不为空时只列出归入其中任一类目的图书
+    pub async fn find_books_detail_in_page<C: ConnectionTrait>(
+        db: &C,
+        category_ids: Option<&[i32]>,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<BooksResult>, u64), DbErr> {
+        let paginator = select_books_detail()
+            .apply_if(category_ids, |select, ids| {
+                select.filter(books::Column::Id.in_subquery(book_ids_in_categories(ids)))
+            })
+            .order_by_asc(books::Column::Id)
+            .into_model::<BooksResult>()
+            .paginate(db, number_per_page);
+        let num_pages = paginator.num_pages().await?;
+        // Fetch paginated posts
+        paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
     }
 
     pub async fn find_books_by_keyword_in_page<C: ConnectionTrait>(
         db: &C,
         keyword: &str,
+        category_ids: Option<&[i32]>,
         page: u64,
         number_per_page: u64,
-    ) -> Result<(Vec<books::Model>, u64), DbErr> {
+    ) -> Result<(Vec<BooksResult>, u64), DbErr> {
         let pattern = format!("%{}%", keyword);
-        let paginator = books::Entity::find()
+        let paginator = select_books_detail()
+            .apply_if(category_ids, |select, ids| {
+                select.filter(books::Column::Id.in_subquery(book_ids_in_categories(ids)))
+            })
             .filter(
                 books::Column::Name
                     .like(&pattern)
                     .or(books::Column::Isbn.like(&pattern))
-                    .or(books::Column::Author.like(&pattern))
+                    .or(books::Column::Id.in_subquery(book_ids_by_author_name(&pattern)))
                     .or(books::Column::Publisher.like(&pattern)),
             )
             .order_by_asc(books::Column::Name)
+            .into_model::<BooksResult>()
             .paginate(db, number_per_page);
         let num_pages = paginator.num_pages().await?;
         // Fetch paginated posts
         paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
     }
 
+    pub async fn find_category_by_id<C: ConnectionTrait>(
+        db: &C,
+        id: i32,
+    ) -> Result<Option<categories::Model>, DbErr> {
+        categories::Entity::find_by_id(id).one(db).await
+    }
+
+    /// 类号在同一分类法中唯一
+    pub async fn find_category_by_code<C: ConnectionTrait>(
+        db: &C,
+        scheme: CategoryScheme,
+        code: &str,
+    ) -> Result<Option<categories::Model>, DbErr> {
+        categories::Entity::find()
+            .filter(categories::Column::Scheme.eq(scheme))
+            .filter(categories::Column::Code.eq(code))
+            .one(db)
+            .await
+    }
+
+    /// 全部类目，按分类法与类号排序
+    pub async fn find_categories<C: ConnectionTrait>(
+        db: &C,
+    ) -> Result<Vec<categories::Model>, DbErr> {
+        categories::Entity::find()
+            .order_by_asc(categories::Column::Scheme)
+            .order_by_asc(categories::Column::Code)
+            .all(db)
+            .await
+    }
+
+    pub async fn find_categories_by_scheme<C: ConnectionTrait>(
+        db: &C,
+        scheme: CategoryScheme,
+    ) -> Result<Vec<categories::Model>, DbErr> {
+        categories::Entity::find()
+            .filter(categories::Column::Scheme.eq(scheme))
+            .order_by_asc(categories::Column::Code)
+            .all(db)
+            .await
+    }
+
+    /// 图书与该分类法中类目的全部关联
+    pub async fn find_book_categories_by_scheme<C: ConnectionTrait>(
+        db: &C,
+        scheme: CategoryScheme,
+    ) -> Result<Vec<book_categories::Model>, DbErr> {
+        book_categories::Entity::find()
+            .join(JoinType::InnerJoin, book_categories::Relation::Categories.def())
+            .filter(categories::Column::Scheme.eq(scheme))
+            .all(db)
+            .await
+    }
+
+    pub async fn find_categories_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+    ) -> Result<Vec<categories::Model>, DbErr> {
+        categories::Entity::find()
+            .join(JoinType::InnerJoin, categories::Relation::BookCategories.def())
+            .filter(book_categories::Column::BookId.eq(book_id))
+            .order_by_asc(categories::Column::Scheme)
+            .order_by_asc(categories::Column::Code)
+            .all(db)
+            .await
+    }
+
+    /// 参与过图书的著者及图书种数
+    pub async fn find_authors_detail_in_page<C: ConnectionTrait>(
+        db: &C,
+        page: u64,
+        number_per_page: u64,
+    ) -> Result<(Vec<AuthorsResult>, u64), DbErr> {
+        let paginator = authors::Entity::find()
+            .select_only()
+            .column(authors::Column::Id)
+            .column(authors::Column::Name)
+            .column_as(
+                Expr::col((book_contributors::Entity, book_contributors::Column::BookId))
+                    .count_distinct(),
+                "books",
+            )
+            .join(JoinType::InnerJoin, authors::Relation::BookContributors.def())
+            .group_by(authors::Column::Id)
+            .group_by(authors::Column::Name)
+            .order_by_asc(authors::Column::Name)
+            .into_model::<AuthorsResult>()
+            .paginate(db, number_per_page);
+        let num_pages = paginator.num_pages().await?;
+        paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
+    }
+
+    /// 著者参与的图书，同一本书担任多种职责时出现多次
+    pub async fn find_books_by_author<C: ConnectionTrait>(
+        db: &C,
+        author_id: i32,
+    ) -> Result<Vec<AuthorBooksResult>, DbErr> {
+        select_books_detail()
+            .column_as(book_contributors::Column::Role, "role")
+            .join(JoinType::InnerJoin, books::Relation::BookContributors.def())
+            .filter(book_contributors::Column::AuthorId.eq(author_id))
+            .order_by_desc(books::Column::PublishYear)
+            .order_by_asc(books::Column::Id)
+            .into_model::<AuthorBooksResult>()
+            .all(db)
+            .await
+    }
+
+    pub async fn find_contributors_by_book_id<C: ConnectionTrait>(
+        db: &C,
+        book_id: i32,
+    ) -> Result<Vec<ContributorsResult>, DbErr> {
+        book_contributors::Entity::find()
+            .select_only()
+            .column_as(book_contributors::Column::AuthorId, "author_id")
+            .column_as(authors::Column::Name, "name")
+            .column_as(book_contributors::Column::Role, "role")
+            .join(JoinType::InnerJoin, book_contributors::Relation::Authors.def())
+            .filter(book_contributors::Column::BookId.eq(book_id))
+            .order_by_asc(book_contributors::Column::Position)
+            .into_model::<ContributorsResult>()
+            .all(db)
+            .await
+    }
+
     pub async fn find_users_by_keyword_in_page<C: ConnectionTrait>(
         db: &C,
         keyword: &str,
@@ -280,15 +1041,18 @@ pub async fn find_emails_in_page<C: ConnectionTrait>(
 
 pub async fn find_borrowed_books_in_page<C: ConnectionTrait>(
     db: &C,
+    state: LoanState,
     page: u64,
     number_per_page: u64,
 ) -> Result<(Vec<BorrowedBooksResult>, u64), DbErr> {
     let paginator = borrowed_books::Entity::find()
+        .filter(state.condition())
         .column_as(borrowed_books::Column::Id, "borrow_id")
         .column_as(users::Column::Name, "user_name")
         .column_as(users::Column::Nickname, "user_nickname")
         .column_as(books::Column::Name, "book_name")
         .column_as(books::Column::Isbn, "isbn")
+        .column_as(book_items::Column::Barcode, "barcode")
         .join(
             JoinType::InnerJoin,
             borrowed_books::Entity::belongs_to(books::Entity)
@@ -296,6 +1060,7 @@ pub async fn find_borrowed_books_in_page<C: ConnectionTrait>(
                 .to(books::Column::Id)
                 .into(),
         )
+        .join(JoinType::InnerJoin, borrowed_books::Relation::BookItems.def())
         .join(
             JoinType::InnerJoin,
             borrowed_books::Entity::belongs_to(users::Entity)
@@ -311,3 +1076,221 @@ pub async fn find_borrowed_books_in_page<C: ConnectionTrait>(
     // Fetch paginated posts
     paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
 }
+
+/// 借阅历史包含当前借阅与已归还的记录，按借阅日期倒序排列
+pub async fn find_loan_history_in_page<C: ConnectionTrait>(
+    db: &C,
+    condition: SimpleExpr,
+    page: u64,
+    number_per_page: u64,
+) -> Result<(Vec<LoanHistoryResult>, u64), DbErr> {
+    let staff = Alias::new("staff");
+    let paginator = borrowed_books::Entity::find()
+        .select_only()
+        .column_as(borrowed_books::Column::Id, "borrow_id")
+        .column(borrowed_books::Column::UserId)
+        .column(borrowed_books::Column::BookId)
+        .column(borrowed_books::Column::BorrowDate)
+        .column(borrowed_books::Column::ReturnDate)
+        .column(borrowed_books::Column::ReturnedAt)
+        .column(borrowed_books::Column::Renewals)
+        .column(borrowed_books::Column::Status)
+        .column_as(users::Column::Name, "user_name")
+        .column_as(users::Column::Nickname, "user_nickname")
+        .column_as(books::Column::Name, "book_name")
+        .column_as(books::Column::Isbn, "isbn")
+        .column_as(book_items::Column::Barcode, "barcode")
+        .column_as(
+            Into::<SimpleExpr>::into(Expr::col((staff.clone(), users::Column::Name))),
+            "returned_by_name",
+        )
+        .filter(condition)
+        .join(JoinType::InnerJoin, borrowed_books::Relation::Books.def())
+        .join(JoinType::InnerJoin, borrowed_books::Relation::Users.def())
+        .join(JoinType::InnerJoin, borrowed_books::Relation::BookItems.def())
+        .join_as(
+            JoinType::LeftJoin,
+            borrowed_books::Entity::belongs_to(users::Entity)
+                .from(borrowed_books::Column::ReturnedBy)
+                .to(users::Column::Id)
+                .into(),
+            staff,
+        )
+        .order_by_desc(borrowed_books::Column::BorrowDate)
+        .order_by_desc(borrowed_books::Column::Id)
+        .into_model::<LoanHistoryResult>()
+        .paginate(db, number_per_page);
+    let num_pages = paginator.num_pages().await?;
+
+    // Fetch paginated posts
+    paginator.fetch_page(page - 1).await.map(|p| (p, num_pages))
+}
+
+fn active_holds() -> Condition {
+    Condition::any()
+        .add(holds::Column::Status.eq(HoldStatus::Waiting))
+        .add(holds::Column::Status.eq(HoldStatus::Ready))
+}
+
+/// 未完成的预约，按预约先后排列，附带读者、图书与保留副本的信息
+fn select_holds_detail() -> Select<holds::Entity> {
+    holds::Entity::find()
+        .select_only()
+        .column_as(holds::Column::Id, "hold_id")
+        .column(holds::Column::BookId)
+        .column(holds::Column::UserId)
+        .column(holds::Column::CreatedAt)
+        .column(holds::Column::Status)
+        .column(holds::Column::PickupDeadline)
+        .column_as(books::Column::Name, "book_name")
+        .column_as(users::Column::Name, "user_name")
+        .column_as(users::Column::Nickname, "user_nickname")
+        .column_as(book_items::Column::Barcode, "barcode")
+        .filter(active_holds())
+        .join(JoinType::InnerJoin, holds::Relation::Books.def())
+        .join(JoinType::InnerJoin, holds::Relation::Users.def())
+        .join(JoinType::LeftJoin, holds::Relation::BookItems.def())
+        .order_by_asc(holds::Column::CreatedAt)
+        .order_by_asc(holds::Column::Id)
+}
+
+/// 遗失与损坏登记，未处理的排在前面，附带副本、读者与经办人的信息
+fn select_item_incidents_detail() -> Select<item_incidents::Entity> {
+    let staff = |column: item_incidents::Column| -> RelationDef {
+        item_incidents::Entity::belongs_to(users::Entity)
+            .from(column)
+            .to(users::Column::Id)
+            .into()
+    };
+    item_incidents::Entity::find()
+        .select_only()
+        .column_as(item_incidents::Column::Id, "incident_id")
+        .column(item_incidents::Column::Kind)
+        .column(item_incidents::Column::Status)
+        .column(item_incidents::Column::ItemId)
+        .column(item_incidents::Column::UserId)
+        .column(item_incidents::Column::BorrowId)
+        .column(item_incidents::Column::FineId)
+        .column(item_incidents::Column::Note)
+        .column(item_incidents::Column::CreatedAt)
+        .column(item_incidents::Column::ResolvedAt)
+        .column(item_incidents::Column::ResolutionNote)
+        .column(book_items::Column::Barcode)
+        .column(book_items::Column::BookId)
+        .column_as(books::Column::Name, "book_name")
+        .column_as(users::Column::Name, "user_name")
+        .column_as(users::Column::Nickname, "user_nickname")
+        .column_as(fines::Column::Amount, "amount")
+        .column_as(Expr::col((Alias::new("creator"), users::Column::Nickname)), "creator_name")
+        .column_as(Expr::col((Alias::new("resolver"), users::Column::Nickname)), "resolver_name")
+        .join(JoinType::InnerJoin, item_incidents::Relation::BookItems.def())
+        .join(JoinType::InnerJoin, book_items::Relation::Books.def())
+        .join(JoinType::LeftJoin, item_incidents::Relation::Users.def())
+        .join(JoinType::LeftJoin, item_incidents::Relation::Fines.def())
+        .join_as(
+            JoinType::LeftJoin,
+            staff(item_incidents::Column::CreatedBy),
+            Alias::new("creator"),
+        )
+        .join_as(
+            JoinType::LeftJoin,
+            staff(item_incidents::Column::ResolvedBy),
+            Alias::new("resolver"),
+        )
+        .order_by_asc(
+            Expr::col((item_incidents::Entity, item_incidents::Column::Status))
+                .ne(IncidentStatus::Open),
+        )
+        .order_by_desc(item_incidents::Column::CreatedAt)
+}
+
+/// 馆际互借申请，未结束的排在前面，附带读者与临时副本的信息
+fn select_ill_requests_detail() -> Select<ill_requests::Entity> {
+    ill_requests::Entity::find()
+        .select_only()
+        .column_as(ill_requests::Column::Id, "request_id")
+        .column(ill_requests::Column::UserId)
+        .column(ill_requests::Column::Name)
+        .column(ill_requests::Column::Author)
+        .column(ill_requests::Column::Isbn)
+        .column(ill_requests::Column::Note)
+        .column(ill_requests::Column::Status)
+        .column(ill_requests::Column::Partner)
+        .column(ill_requests::Column::ItemId)
+        .column(ill_requests::Column::CreatedAt)
+        .column(ill_requests::Column::UpdatedAt)
+        .column_as(users::Column::Name, "user_name")
+        .column_as(users::Column::Nickname, "user_nickname")
+        .column(book_items::Column::Barcode)
+        .column(book_items::Column::BookId)
+        .join(JoinType::InnerJoin, ill_requests::Relation::Users.def())
+        .join(JoinType::LeftJoin, ill_requests::Relation::BookItems.def())
+        .order_by_asc(
+            Expr::col((ill_requests::Entity, ill_requests::Column::Status))
+                .is_in([IllStatus::Returned, IllStatus::Cancelled]),
+        )
+        .order_by_desc(ill_requests::Column::CreatedAt)
+}
+
+/// 自动生成的副本条码：`图书ID(6位) + 序号(4位)`
+pub fn book_item_barcode(book_id: i32, n: u64) -> String {
+    format!("{book_id:06}{n:04}")
+}
+
+/// 读者证条码：`用户ID(8位)`，与副本条码的位数不同
+pub fn library_card_number(user_id: i32) -> String {
+    format!("{user_id:08}")
+}
+
+/// 不是 8 位数字时返回 `None`
+pub fn parse_library_card_number(code: &str) -> Option<i32> {
+    if code.len() != 8 || !code.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    code.parse().ok()
+}
+
+/// 属于任一类目的图书
+fn book_ids_in_categories(category_ids: &[i32]) -> sea_query::SelectStatement {
+    sea_query::Query::select()
+        .column(book_categories::Column::BookId)
+        .from(book_categories::Entity)
+        .and_where(book_categories::Column::CategoryId.is_in(category_ids.iter().copied()))
+        .to_owned()
+}
+
+/// 姓名匹配 `pattern` 的著者参与的图书
+fn book_ids_by_author_name(pattern: &str) -> sea_query::SelectStatement {
+    sea_query::Query::select()
+        .column((book_contributors::Entity, book_contributors::Column::BookId))
+        .from(book_contributors::Entity)
+        .inner_join(
+            authors::Entity,
+            Expr::col((authors::Entity, authors::Column::Id))
+                .equals((book_contributors::Entity, book_contributors::Column::AuthorId)),
+        )
+        .and_where(Expr::col((authors::Entity, authors::Column::Name)).like(pattern))
+        .to_owned()
+}
+
+/// 图书列表附带副本总数与可借数量，二者均由 `book_items` 的状态统计得出
+fn select_books_detail() -> Select<books::Entity> {
+    let count_items = |status: Option<ItemStatus>| {
+        let mut select = sea_query::Query::select();
+        select
+            .expr(Expr::col((book_items::Entity, book_items::Column::Id)).count())
+            .from(book_items::Entity)
+            .and_where(
+                Expr::col((book_items::Entity, book_items::Column::BookId))
+                    .equals((books::Entity, books::Column::Id)),
+            );
+        if let Some(status) = status {
+            select.and_where(Expr::col((book_items::Entity, book_items::Column::Status)).eq(status));
+        }
+        SimpleExpr::SubQuery(None, Box::new(select.into_sub_query_statement()))
+    };
+
+    books::Entity::find()
+        .column_as(count_items(None), "copies")
+        .column_as(count_items(Some(ItemStatus::Available)), "available")
+}
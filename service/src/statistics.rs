@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use ::entity::{
+    authors, book_contributors, books, borrowed_books, users, ContributorRole, PeriodCount,
+    TopAuthorsResult, TopBooksResult,
+};
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime};
+use sea_orm::{
+    sea_query::{Alias, Expr, Func, IntoColumnRef, SimpleExpr},
+    *,
+};
+
+/// 统计图表的时间粒度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    Day,
+    Month,
+}
+
+impl Granularity {
+    /// 日期所在统计区间的第一天
+    fn period_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => date,
+            Granularity::Month => date.with_day(1).unwrap(),
+        }
+    }
+
+    fn next(self, period: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Day => period + chrono::Duration::days(1),
+            Granularity::Month => period + Months::new(1),
+        }
+    }
+
+    /// 在 SQL 中把日期归到所在区间的第一天
+    fn period_expr(self, column: impl IntoColumnRef) -> SimpleExpr {
+        let format = match self {
+            Granularity::Day => "%Y-%m-%d",
+            Granularity::Month => "%Y-%m-01",
+        };
+        Func::cust(Alias::new("strftime"))
+            .arg(format)
+            .arg(Expr::col(column))
+            .into()
+    }
+
+    fn label(self, period: NaiveDate) -> String {
+        match self {
+            Granularity::Day => period.format("%m-%d").to_string(),
+            Granularity::Month => period.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// `start` 至 `end`（含）期间的流通统计
+pub struct CirculationStatistics {
+    /// 每个区间的借阅与注册数量，没有数据的区间计为零
+    pub periods: Vec<PeriodCount>,
+    pub total_loans: i64,
+    /// 逾期归还或至今逾期未还的借阅数量
+    pub overdue_loans: i64,
+    /// 逾期借阅占全部借阅的百分比
+    pub overdue_rate: f64,
+    /// 期间内有借阅的读者数量
+    pub active_patrons: i64,
+    pub new_users: i64,
+    pub top_books: Vec<TopBooksResult>,
+    pub top_authors: Vec<TopAuthorsResult>,
+}
+
+pub struct Statistics;
+
+impl Statistics {
+    /// 排行榜的条目数
+    pub const TOP_LIMIT: u64 = 10;
+
+    pub async fn circulation<C: ConnectionTrait>(
+        db: &C,
+        start: NaiveDate,
+        end: NaiveDate,
+        granularity: Granularity,
+        now: NaiveDateTime,
+    ) -> Result<CirculationStatistics, DbErr> {
+        let loans_per_period: Vec<(NaiveDate, i64)> = borrowed_books::Entity::find()
+            .select_only()
+            .column_as(granularity.period_expr(borrowed_books::Column::BorrowDate), "period")
+            .column_as(borrowed_books::Column::Id.count(), "loans")
+            .filter(borrowed_books::Column::BorrowDate.between(start, end))
+            .group_by(Expr::col(Alias::new("period")))
+            .into_tuple()
+            .all(db)
+            .await?;
+        let registrations_per_period: Vec<(NaiveDate, i64)> = users::Entity::find()
+            .select_only()
+            .column_as(granularity.period_expr(users::Column::RegistrationDate), "period")
+            .column_as(users::Column::Id.count(), "registrations")
+            .filter(users::Column::RegistrationDate.between(start, end))
+            .group_by(Expr::col(Alias::new("period")))
+            .into_tuple()
+            .all(db)
+            .await?;
+        // 与 `Policy::deadline` 一致，按日借出的在归还日期当天结束时到期
+        let (total_loans, active_patrons, overdue_loans): (i64, i64, Option<i64>) =
+            borrowed_books::Entity::find()
+                .select_only()
+                .column_as(borrowed_books::Column::Id.count(), "total_loans")
+                .column_as(Expr::col(borrowed_books::Column::UserId).count_distinct(), "patrons")
+                .column_as(
+                    Expr::cust_with_values(
+                        "SUM(CASE WHEN COALESCE(returned_at, ?) > \
+                         COALESCE(due_at, datetime(return_date, '+1 day')) THEN 1 ELSE 0 END)",
+                        [now],
+                    ),
+                    "overdue_loans",
+                )
+                .filter(borrowed_books::Column::BorrowDate.between(start, end))
+                .into_tuple()
+                .one(db)
+                .await?
+                .unwrap_or_default();
+        let overdue_loans = overdue_loans.unwrap_or(0);
+
+        let mut periods = BTreeMap::new();
+        let mut period = granularity.period_of(start);
+        while period <= end {
+            periods.insert(period, (0, 0));
+            period = granularity.next(period);
+        }
+        for (period, loans) in loans_per_period {
+            if let Some(count) = periods.get_mut(&period) {
+                count.0 = loans;
+            }
+        }
+        let mut new_users = 0;
+        for (period, registrations) in registrations_per_period {
+            if let Some(count) = periods.get_mut(&period) {
+                count.1 = registrations;
+            }
+            new_users += registrations;
+        }
+
+        let overdue_rate = if total_loans == 0 {
+            0.0
+        } else {
+            overdue_loans as f64 * 100.0 / total_loans as f64
+        };
+        Ok(CirculationStatistics {
+            periods: periods
+                .into_iter()
+                .map(|(period, (loans, registrations))| PeriodCount {
+                    period: granularity.label(period),
+                    loans,
+                    registrations,
+                })
+                .collect(),
+            total_loans,
+            overdue_loans,
+            overdue_rate,
+            active_patrons,
+            new_users,
+            top_books: Self::top_books(db, start, end).await?,
+            top_authors: Self::top_authors(db, start, end).await?,
+        })
+    }
+
+    pub async fn top_books<C: ConnectionTrait>(
+        db: &C,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<TopBooksResult>, DbErr> {
+        borrowed_books::Entity::find()
+            .select_only()
+            .column_as(borrowed_books::Column::BookId, "book_id")
+            .column_as(books::Column::Name, "name")
+            .column_as(books::Column::Author, "author")
+            .column_as(borrowed_books::Column::Id.count(), "loans")
+            .join(JoinType::InnerJoin, borrowed_books::Relation::Books.def())
+            .filter(borrowed_books::Column::BorrowDate.between(start, end))
+            .group_by(borrowed_books::Column::BookId)
+            .group_by(books::Column::Name)
+            .group_by(books::Column::Author)
+            .order_by_desc(Expr::col(Alias::new("loans")))
+            .order_by_asc(books::Column::Name)
+            .limit(Self::TOP_LIMIT)
+            .into_model::<TopBooksResult>()
+            .all(db)
+            .await
+    }
+
+    pub async fn top_authors<C: ConnectionTrait>(
+        db: &C,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<TopAuthorsResult>, DbErr> {
+        // 合著的图书计入每位著者，译者、编者等不计
+        borrowed_books::Entity::find()
+            .select_only()
+            .column_as(authors::Column::Name, "author")
+            .column_as(borrowed_books::Column::Id.count(), "loans")
+            .join(JoinType::InnerJoin, borrowed_books::Relation::Books.def())
+            .join(JoinType::InnerJoin, books::Relation::BookContributors.def())
+            .join(JoinType::InnerJoin, book_contributors::Relation::Authors.def())
+            .filter(borrowed_books::Column::BorrowDate.between(start, end))
+            .filter(book_contributors::Column::Role.eq(ContributorRole::Author))
+            .group_by(authors::Column::Id)
+            .group_by(authors::Column::Name)
+            .order_by_desc(Expr::col(Alias::new("loans")))
+            .order_by_asc(authors::Column::Name)
+            .limit(Self::TOP_LIMIT)
+            .into_model::<TopAuthorsResult>()
+            .all(db)
+            .await
+    }
+}